@@ -68,6 +68,10 @@ pub fn meta_list_item_to_string(li: &ast::NestedMetaItem) -> String {
     State::new().meta_list_item_to_string(li)
 }
 
+pub fn meta_item_to_string(mi: &ast::MetaItem) -> String {
+    State::new().meta_item_to_string(mi)
+}
+
 pub fn attribute_to_string(attr: &ast::Attribute) -> String {
     State::new().attribute_to_string(attr)
 }
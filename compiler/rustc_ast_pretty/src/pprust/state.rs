@@ -885,6 +885,10 @@ fn meta_list_item_to_string(&self, li: &ast::NestedMetaItem) -> String {
         Self::to_string(|s| s.print_meta_list_item(li))
     }
 
+    fn meta_item_to_string(&self, mi: &ast::MetaItem) -> String {
+        Self::to_string(|s| s.print_meta_item(mi))
+    }
+
     fn attr_item_to_string(&self, ai: &ast::AttrItem) -> String {
         Self::to_string(|s| s.print_attr_item(ai, ai.path.span))
     }
@@ -5,6 +5,7 @@
 use rustc_ast_pretty::pprust;
 use rustc_feature::{find_gated_cfg, is_builtin_attr_name, Features, GatedCfg};
 use rustc_macros::HashStable_Generic;
+use rustc_session::cfg_eval::CfgEvalKind;
 use rustc_session::lint::builtin::UNEXPECTED_CFGS;
 use rustc_session::lint::BuiltinLintDiagnostics;
 use rustc_session::parse::{feature_err, ParseSess};
@@ -571,14 +572,16 @@ pub struct Condition {
     pub span: Span,
 }
 
-/// Tests if a cfg-pattern matches the cfg set
+/// Tests if a cfg-pattern matches the cfg set. The evaluation is recorded as a
+/// `kind` evaluation for `-Z print-cfg-eval`.
 pub fn cfg_matches(
     cfg: &ast::MetaItem,
     sess: &ParseSess,
     lint_node_id: NodeId,
     features: Option<&Features>,
+    kind: CfgEvalKind,
 ) -> bool {
-    eval_condition(cfg, sess, features, &mut |cfg| {
+    let result = eval_condition(cfg, sess, features, &mut |cfg| {
         try_gate_cfg(cfg.name, cfg.span, sess, features);
         if let Some(names_valid) = &sess.check_config.names_valid {
             if !names_valid.contains(&cfg.name) {
@@ -608,7 +611,11 @@ pub fn cfg_matches(
             }
         }
         sess.config.contains(&(cfg.name, cfg.value))
-    })
+    });
+    if sess.record_cfg_evals {
+        sess.cfg_evals.record(kind, pprust::meta_item_to_string(cfg), result, cfg.span);
+    }
+    result
 }
 
 fn try_gate_cfg(name: Symbol, span: Span, sess: &ParseSess, features: Option<&Features>) {
@@ -34,6 +34,7 @@
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::{self, CapturedPlace, ParamEnv, RegionVid, TyCtxt};
 use rustc_session::lint::builtin::UNUSED_MUT;
+use rustc_session::CostPhase;
 use rustc_span::{Span, Symbol};
 
 use either::Either;
@@ -131,14 +132,15 @@ fn mir_borrowck<'tcx>(
     debug!("run query mir_borrowck: {}", tcx.def_path_str(def.did.to_def_id()));
     let hir_owner = tcx.hir().local_def_id_to_hir_id(def.did).owner;
 
-    let opt_closure_req = tcx
-        .infer_ctxt()
-        .with_opaque_type_inference(DefiningAnchor::Bind(hir_owner.def_id))
-        .enter(|infcx| {
-            let input_body: &Body<'_> = &input_body.borrow();
-            let promoted: &IndexVec<_, _> = &promoted.borrow();
-            do_mir_borrowck(&infcx, input_body, promoted, false).0
-        });
+    let opt_closure_req = tcx.sess.time_item(def.did.to_def_id(), CostPhase::Borrowck, || {
+        tcx.infer_ctxt().with_opaque_type_inference(DefiningAnchor::Bind(hir_owner.def_id)).enter(
+            |infcx| {
+                let input_body: &Body<'_> = &input_body.borrow();
+                let promoted: &IndexVec<_, _> = &promoted.borrow();
+                do_mir_borrowck(&infcx, input_body, promoted, false).0
+            },
+        )
+    });
     debug!("mir_borrowck done");
 
     tcx.arena.alloc(opt_closure_req)
@@ -263,6 +265,9 @@ fn do_mir_borrowck<'a, 'tcx>(
     // write unit-tests, as well as helping with debugging.
     nll::dump_mir_results(infcx, &body, &regioncx, &opt_closure_req);
 
+    // Explain the region errors of the function named by `-Z borrowck-explain`.
+    nll::dump_borrowck_explanation(infcx, &body, &regioncx, &nll_errors);
+
     // We also have a `#[rustc_regions]` annotation that causes us to dump
     // information.
     nll::dump_annotation(
@@ -9,6 +9,7 @@
     BasicBlock, Body, ClosureOutlivesSubject, ClosureRegionRequirements, LocalKind, Location,
     Promoted,
 };
+use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::{self, OpaqueHiddenType, Region, RegionVid};
 use rustc_span::symbol::sym;
 use std::env;
@@ -368,6 +369,39 @@ pub(super) fn dump_mir_results<'a, 'tcx>(
     };
 }
 
+/// Dumps the region constraint graph and the constraints blamed for the region
+/// errors of the function named by `-Z borrowck-explain`, and of its closures.
+pub(super) fn dump_borrowck_explanation<'a, 'tcx>(
+    infcx: &InferCtxt<'a, 'tcx>,
+    body: &Body<'tcx>,
+    regioncx: &RegionInferenceContext<'tcx>,
+    nll_errors: &RegionErrors<'tcx>,
+) {
+    let tcx = infcx.tcx;
+    let Some(path) = &tcx.sess.opts.unstable_opts.borrowck_explain else {
+        return;
+    };
+    with_no_trimmed_paths!({
+        let base_def_id = tcx.typeck_root_def_id(body.source.def_id());
+        if tcx.def_path_str(base_def_id) != *path {
+            return;
+        }
+
+        let errors = regioncx.explain_region_errors(body, nll_errors);
+
+        let _: io::Result<()> = try {
+            let mut file = create_dump_file(tcx, "dot", None, "borrowck-explain", &0, body.source)?;
+            regioncx.dump_graphviz_explanation(tcx, &errors, &mut file)?;
+        };
+
+        let _: io::Result<()> = try {
+            let mut file =
+                create_dump_file(tcx, "json", None, "borrowck-explain", &0, body.source)?;
+            regioncx.dump_json_explanation(tcx, body, &errors, &mut file)?;
+        };
+    })
+}
+
 pub(super) fn dump_annotation<'a, 'tcx>(
     infcx: &InferCtxt<'a, 'tcx>,
     body: &Body<'tcx>,
@@ -0,0 +1,327 @@
+//! Support for `-Z borrowck-explain`, which dumps the region constraint graph
+//! of a function along with the constraints blamed for each of its region
+//! errors, so that lifetime errors can be followed through the whole graph
+//! rather than through the single path that the diagnostic shows.
+
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+use rustc_graphviz as dot;
+use rustc_span::DUMMY_SP;
+use serde::Serialize;
+
+use super::*;
+
+/// A region error of the function being explained, along with the outlives
+/// constraints that cause it.
+pub(crate) struct ExplainedError<'tcx> {
+    /// The outlives relation or type test that could not be proven.
+    description: String,
+    /// Whether a diagnostic is emitted for the error, see `RegionErrorKind::RegionError`.
+    reported: bool,
+    span: Span,
+    /// The minimal path of constraints from the longer region to the region
+    /// it cannot outlive, empty for errors that aren't caused by such a path.
+    path: Vec<OutlivesConstraint<'tcx>>,
+    /// The constraint of `path` that the diagnostic points at.
+    blame: Option<OutlivesConstraint<'tcx>>,
+}
+
+impl<'tcx> RegionInferenceContext<'tcx> {
+    /// Finds the constraint paths that `report_region_errors` blames for each error.
+    pub(crate) fn explain_region_errors(
+        &self,
+        body: &Body<'tcx>,
+        errors: &RegionErrors<'tcx>,
+    ) -> Vec<ExplainedError<'tcx>> {
+        errors
+            .iter()
+            .map(|error| match *error {
+                RegionErrorKind::RegionError { fr_origin, longer_fr, shorter_fr, is_reported } => {
+                    self.explain_region_error(
+                        format!("{:?}: {:?}", longer_fr, shorter_fr),
+                        is_reported,
+                        longer_fr,
+                        fr_origin,
+                        |r| self.provides_universal_region(r, longer_fr, shorter_fr),
+                    )
+                }
+                RegionErrorKind::BoundUniversalRegionError {
+                    longer_fr,
+                    ref error_element,
+                    placeholder,
+                } => {
+                    let error_vid = self.region_from_element(longer_fr, error_element);
+                    self.explain_region_error(
+                        format!("{:?}: {:?}", longer_fr, error_vid),
+                        true,
+                        longer_fr,
+                        NllRegionVariableOrigin::Placeholder(placeholder),
+                        |r| r == error_vid,
+                    )
+                }
+                RegionErrorKind::TypeTestError { ref type_test } => ExplainedError {
+                    description: format!("{}: {:?}", type_test.generic_kind, type_test.lower_bound),
+                    reported: true,
+                    span: type_test.locations.span(body),
+                    path: Vec::new(),
+                    blame: None,
+                },
+                RegionErrorKind::UnexpectedHiddenRegion {
+                    span, hidden_ty, member_region, ..
+                } => ExplainedError {
+                    description: format!(
+                        "hidden type `{}` captures `{}`",
+                        hidden_ty, member_region
+                    ),
+                    reported: true,
+                    span,
+                    path: Vec::new(),
+                    blame: None,
+                },
+            })
+            .collect()
+    }
+
+    fn explain_region_error(
+        &self,
+        description: String,
+        reported: bool,
+        longer_fr: RegionVid,
+        fr_origin: NllRegionVariableOrigin,
+        target_test: impl Fn(RegionVid) -> bool,
+    ) -> ExplainedError<'tcx> {
+        let Some((path, _)) = self.find_constraint_paths_between_regions(longer_fr, &target_test)
+        else {
+            return ExplainedError {
+                description,
+                reported,
+                span: DUMMY_SP,
+                path: Vec::new(),
+                blame: None,
+            };
+        };
+        let (blame, _) = self.best_blame_constraint(longer_fr, fr_origin, target_test);
+        ExplainedError {
+            description,
+            reported,
+            span: blame.cause.span,
+            path,
+            blame: Some(blame.outlives_constraint),
+        }
+    }
+
+    /// Write out the region constraint graph, with the constraints on the path
+    /// of an error in bold and labelled with the number of the error.
+    pub(crate) fn dump_graphviz_explanation(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        errors: &[ExplainedError<'tcx>],
+        mut w: &mut dyn Write,
+    ) -> io::Result<()> {
+        dot::render(&ExplainedConstraints { regioncx: self, tcx, errors }, &mut w)
+    }
+
+    /// Write out the regions, constraints and errors of the function as JSON.
+    pub(crate) fn dump_json_explanation(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        body: &Body<'tcx>,
+        errors: &[ExplainedError<'tcx>],
+        w: &mut dyn Write,
+    ) -> io::Result<()> {
+        let explanation = JsonExplanation {
+            function: tcx.def_path_str(body.source.def_id()),
+            regions: self
+                .definitions
+                .iter_enumerated()
+                .map(|(vid, definition)| JsonRegion {
+                    id: format!("{:?}", vid),
+                    name: definition.external_name.map(|r| r.to_string()),
+                    origin: origin_kind(definition.origin),
+                    span: span_to_string(tcx, self.var_infos[vid].origin.span()),
+                })
+                .collect(),
+            constraints: self
+                .constraints
+                .outlives()
+                .raw
+                .iter()
+                .map(|constraint| JsonConstraint::new(tcx, constraint))
+                .collect(),
+            errors: errors
+                .iter()
+                .map(|error| JsonError {
+                    description: error.description.clone(),
+                    reported: error.reported,
+                    span: span_to_string(tcx, error.span),
+                    path: error.path.iter().map(|c| JsonConstraint::new(tcx, c)).collect(),
+                    blame: error.blame.as_ref().map(|c| JsonConstraint::new(tcx, c)),
+                })
+                .collect(),
+        };
+        serde_json::to_writer_pretty(&mut *w, &explanation)?;
+        writeln!(w)
+    }
+}
+
+fn origin_kind(origin: NllRegionVariableOrigin) -> &'static str {
+    match origin {
+        NllRegionVariableOrigin::FreeRegion => "free",
+        NllRegionVariableOrigin::Placeholder(_) => "placeholder",
+        NllRegionVariableOrigin::Existential { .. } => "existential",
+    }
+}
+
+fn span_to_string(tcx: TyCtxt<'_>, span: Span) -> Option<String> {
+    (!span.is_dummy()).then(|| tcx.sess.source_map().span_to_embeddable_string(span))
+}
+
+struct ExplainedConstraints<'a, 'tcx> {
+    regioncx: &'a RegionInferenceContext<'tcx>,
+    tcx: TyCtxt<'tcx>,
+    errors: &'a [ExplainedError<'tcx>],
+}
+
+impl<'a, 'tcx> ExplainedConstraints<'a, 'tcx> {
+    /// The numbers of the errors whose path contains `constraint`.
+    fn errors_on_path(&self, constraint: &OutlivesConstraint<'tcx>) -> Vec<usize> {
+        self.errors
+            .iter()
+            .enumerate()
+            .filter(|(_, error)| error.path.contains(constraint))
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+impl<'a, 'this, 'tcx> dot::Labeller<'this> for ExplainedConstraints<'a, 'tcx> {
+    type Node = RegionVid;
+    type Edge = OutlivesConstraint<'tcx>;
+
+    fn graph_id(&'this self) -> dot::Id<'this> {
+        dot::Id::new("RegionInferenceContext").unwrap()
+    }
+    fn node_id(&'this self, n: &RegionVid) -> dot::Id<'this> {
+        dot::Id::new(format!("r{}", n.index())).unwrap()
+    }
+    fn node_shape(&'this self, _node: &RegionVid) -> Option<dot::LabelText<'this>> {
+        Some(dot::LabelText::LabelStr(Cow::Borrowed("box")))
+    }
+    fn node_label(&'this self, n: &RegionVid) -> dot::LabelText<'this> {
+        let definition = &self.regioncx.definitions[*n];
+        let mut label = format!("{:?} ({})", n, origin_kind(definition.origin));
+        if let Some(name) = definition.external_name {
+            label.push_str(&format!(" {}", name));
+        }
+        if let Some(span) = span_to_string(self.tcx, self.regioncx.var_infos[*n].origin.span()) {
+            label.push_str(&format!("\n{}", span));
+        }
+        dot::LabelText::LabelStr(label.into())
+    }
+    fn node_style(&'this self, n: &RegionVid) -> dot::Style {
+        match self.regioncx.definitions[*n].origin {
+            NllRegionVariableOrigin::Existential { .. } => dot::Style::None,
+            _ => dot::Style::Bold,
+        }
+    }
+    fn edge_label(&'this self, e: &OutlivesConstraint<'tcx>) -> dot::LabelText<'this> {
+        let mut label = format!("{:?}", e.category);
+        if let Some(span) = span_to_string(self.tcx, e.span) {
+            label.push_str(&format!("\n{}", span));
+        }
+        for i in self.errors_on_path(e) {
+            if self.errors[i].blame.as_ref() == Some(e) {
+                label.push_str(&format!("\nblamed for error #{}", i));
+            } else {
+                label.push_str(&format!("\non the path of error #{}", i));
+            }
+        }
+        dot::LabelText::LabelStr(label.into())
+    }
+    fn edge_style(&'this self, e: &OutlivesConstraint<'tcx>) -> dot::Style {
+        if self.errors_on_path(e).is_empty() {
+            dot::Style::None
+        } else {
+            dot::Style::Bold
+        }
+    }
+}
+
+impl<'a, 'this, 'tcx> dot::GraphWalk<'this> for ExplainedConstraints<'a, 'tcx> {
+    type Node = RegionVid;
+    type Edge = OutlivesConstraint<'tcx>;
+
+    fn nodes(&'this self) -> dot::Nodes<'this, RegionVid> {
+        let vids: Vec<RegionVid> = self.regioncx.definitions.indices().collect();
+        vids.into()
+    }
+    fn edges(&'this self) -> dot::Edges<'this, OutlivesConstraint<'tcx>> {
+        // The paths of errors can contain constraints that are not in the
+        // constraint set, like those from `'static` to every region.
+        let mut edges = self.regioncx.constraints.outlives().raw.clone();
+        for error in self.errors {
+            for constraint in &error.path {
+                if !edges.contains(constraint) {
+                    edges.push(*constraint);
+                }
+            }
+        }
+        edges.into()
+    }
+
+    // Render `a: b` as `a -> b`, indicating the flow
+    // of data during inference.
+
+    fn source(&'this self, edge: &OutlivesConstraint<'tcx>) -> RegionVid {
+        edge.sup
+    }
+
+    fn target(&'this self, edge: &OutlivesConstraint<'tcx>) -> RegionVid {
+        edge.sub
+    }
+}
+
+#[derive(Serialize)]
+struct JsonExplanation {
+    function: String,
+    regions: Vec<JsonRegion>,
+    constraints: Vec<JsonConstraint>,
+    errors: Vec<JsonError>,
+}
+
+#[derive(Serialize)]
+struct JsonRegion {
+    id: String,
+    name: Option<String>,
+    origin: &'static str,
+    span: Option<String>,
+}
+
+#[derive(Serialize)]
+struct JsonConstraint {
+    sup: String,
+    sub: String,
+    category: String,
+    span: Option<String>,
+}
+
+impl JsonConstraint {
+    fn new<'tcx>(tcx: TyCtxt<'tcx>, constraint: &OutlivesConstraint<'tcx>) -> JsonConstraint {
+        JsonConstraint {
+            sup: format!("{:?}", constraint.sup),
+            sub: format!("{:?}", constraint.sub),
+            category: format!("{:?}", constraint.category),
+            span: span_to_string(tcx, constraint.span),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonError {
+    description: String,
+    reported: bool,
+    span: Option<String>,
+    path: Vec<JsonConstraint>,
+    blame: Option<JsonConstraint>,
+}
@@ -41,6 +41,7 @@
 };
 
 mod dump_mir;
+mod explain;
 mod graphviz;
 mod opaque_types;
 mod reverse_sccs;
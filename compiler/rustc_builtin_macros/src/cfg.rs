@@ -9,6 +9,7 @@
 use rustc_errors::PResult;
 use rustc_expand::base::{self, *};
 use rustc_macros::Diagnostic;
+use rustc_session::cfg_eval::CfgEvalKind;
 use rustc_span::Span;
 
 pub fn expand_cfg(
@@ -25,6 +26,7 @@ pub fn expand_cfg(
                 &cx.sess.parse_sess,
                 cx.current_expansion.lint_node_id,
                 cx.ecfg.features,
+                CfgEvalKind::CfgMacro,
             );
             MacEager::expr(cx.expr_bool(sp, matches_cfg))
         }
@@ -118,5 +118,6 @@ pub fn register_builtin_macros(resolver: &mut dyn ResolverExpand) {
     }
 
     let client = proc_macro::bridge::client::Client::expand1(proc_macro::quote);
-    register(sym::quote, SyntaxExtensionKind::Bang(Box::new(BangProcMacro { client })));
+    let quote = BangProcMacro { client, dylib: None };
+    register(sym::quote, SyntaxExtensionKind::Bang(Box::new(quote)));
 }
@@ -13,7 +13,7 @@
 
 use rustc_attr as attr;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
-use rustc_data_structures::profiling::{get_resident_set_size, print_time_passes_entry};
+use rustc_data_structures::profiling::get_resident_set_size;
 
 use rustc_data_structures::sync::par_iter;
 #[cfg(parallel_compiler)]
@@ -754,7 +754,7 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
     if tcx.sess.time_passes() {
         let end_rss = get_resident_set_size();
 
-        print_time_passes_entry(
+        tcx.sess.prof.time_passes_entry(
             "codegen_to_LLVM_IR",
             total_codegen_time,
             start_rss.unwrap(),
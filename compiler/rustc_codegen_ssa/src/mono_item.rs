@@ -7,6 +7,7 @@
 use rustc_middle::ty;
 use rustc_middle::ty::layout::{HasTyCtxt, LayoutOf};
 use rustc_middle::ty::Instance;
+use rustc_session::CostPhase;
 
 pub trait MonoItemExt<'a, 'tcx> {
     fn define<Bx: BuilderMethods<'a, 'tcx>>(&self, cx: &'a Bx::CodegenCx);
@@ -30,7 +31,9 @@ fn define<Bx: BuilderMethods<'a, 'tcx>>(&self, cx: &'a Bx::CodegenCx) {
 
         match *self {
             MonoItem::Static(def_id) => {
-                cx.codegen_static(def_id, cx.tcx().is_mutable_static(def_id));
+                cx.tcx().sess.time_item(def_id, CostPhase::IrGen, || {
+                    cx.codegen_static(def_id, cx.tcx().is_mutable_static(def_id));
+                });
             }
             MonoItem::GlobalAsm(item_id) => {
                 let item = cx.tcx().hir().item(item_id);
@@ -88,7 +91,9 @@ fn define<Bx: BuilderMethods<'a, 'tcx>>(&self, cx: &'a Bx::CodegenCx) {
                 }
             }
             MonoItem::Fn(instance) => {
-                base::codegen_instance::<Bx>(&cx, instance);
+                cx.tcx().sess.time_item(instance.def_id(), CostPhase::IrGen, || {
+                    base::codegen_instance::<Bx>(&cx, instance);
+                });
             }
         }
 
@@ -88,6 +88,7 @@
 use std::collections::hash_map::Entry;
 use std::convert::Into;
 use std::error::Error;
+use std::fmt::Write;
 use std::fs;
 use std::path::Path;
 use std::process;
@@ -96,7 +97,7 @@
 
 pub use measureme::EventId;
 use measureme::{EventIdBuilder, Profiler, SerializableString, StringId};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use smallvec::SmallVec;
 
 bitflags::bitflags! {
@@ -142,6 +143,15 @@ struct EventFilter: u32 {
     ("artifact-sizes", EventFilter::ARTIFACT_SIZES),
 ];
 
+/// The format of the output of `-Z time-passes`.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum TimePassesFormat {
+    /// One line of human-readable text per pass, printed when the pass ends.
+    Text,
+    /// A single JSON document printed once compilation finished, see [`TimePassesReport`].
+    Json,
+}
+
 /// Something that uniquely identifies a query invocation.
 pub struct QueryInvocationId(pub u32);
 
@@ -163,6 +173,10 @@ pub struct SelfProfilerRef {
 
     // Print extra verbose generic activities to stdout
     print_extra_verbose_generic_activities: bool,
+
+    // Collects the verbose generic activities and query timings instead of
+    // printing them, for `-Z time-passes-format=json`
+    time_passes_report: Option<Arc<TimePassesReport>>,
 }
 
 impl SelfProfilerRef {
@@ -170,17 +184,23 @@ pub fn new(
         profiler: Option<Arc<SelfProfiler>>,
         print_verbose_generic_activities: bool,
         print_extra_verbose_generic_activities: bool,
+        time_passes_format: TimePassesFormat,
     ) -> SelfProfilerRef {
         // If there is no SelfProfiler then the filter mask is set to NONE,
         // ensuring that nothing ever tries to actually access it.
         let event_filter_mask =
             profiler.as_ref().map_or(EventFilter::empty(), |p| p.event_filter_mask);
 
+        let time_passes_report = (print_verbose_generic_activities
+            && time_passes_format == TimePassesFormat::Json)
+            .then(|| Arc::new(TimePassesReport::new()));
+
         SelfProfilerRef {
             profiler,
             event_filter_mask,
             print_verbose_generic_activities,
             print_extra_verbose_generic_activities,
+            time_passes_report,
         }
     }
 
@@ -222,7 +242,11 @@ pub fn verbose_generic_activity<'a>(
         let message =
             if self.print_verbose_generic_activities { Some(event_label.to_owned()) } else { None };
 
-        VerboseTimingGuard::start(message, self.generic_activity(event_label))
+        VerboseTimingGuard::start(
+            message,
+            self.time_passes_report.as_deref(),
+            self.generic_activity(event_label),
+        )
     }
 
     /// Start profiling an extra verbose generic activity. Profiling continues until the
@@ -243,7 +267,35 @@ pub fn extra_verbose_generic_activity<'a, A>(
             None
         };
 
-        VerboseTimingGuard::start(message, self.generic_activity_with_arg(event_label, event_arg))
+        VerboseTimingGuard::start(
+            message,
+            self.time_passes_report.as_deref(),
+            self.generic_activity_with_arg(event_label, event_arg),
+        )
+    }
+
+    /// Reports a pass that was timed manually, e.g. because it was interleaved
+    /// with other work, if the compiler is invoked with -Ztime or -Ztime-passes.
+    pub fn time_passes_entry(
+        &self,
+        what: &str,
+        dur: Duration,
+        start_rss: Option<usize>,
+        end_rss: Option<usize>,
+    ) {
+        if let Some(report) = &self.time_passes_report {
+            report.start_pass(what, start_rss);
+            report.end_pass(dur, end_rss);
+        } else if self.print_verbose_generic_activities {
+            print_time_passes_entry(what, dur, start_rss, end_rss);
+        }
+    }
+
+    /// Prints the report collected for `-Z time-passes-format=json`, if any.
+    pub fn print_time_passes_report(&self) {
+        if let Some(report) = &self.time_passes_report {
+            eprintln!("{}", report.to_json());
+        }
     }
 
     /// Start profiling a generic activity. Profiling continues until the
@@ -408,6 +460,13 @@ pub fn query_provider(&self) -> TimingGuard<'_> {
         })
     }
 
+    /// Start timing a query provider for `-Z time-passes-format=json`. Timing
+    /// continues until the `QueryTimingGuard` returned from this call is dropped.
+    #[inline(always)]
+    pub fn time_passes_query(&self, query_name: &'static str) -> QueryTimingGuard<'_> {
+        QueryTimingGuard::start(query_name, self.time_passes_report.as_deref())
+    }
+
     /// Record a query in-memory cache hit.
     #[inline(always)]
     pub fn query_cache_hit(&self, query_invocation_id: QueryInvocationId) {
@@ -722,15 +781,22 @@ pub fn run<R>(self, f: impl FnOnce() -> R) -> R {
 #[must_use]
 pub struct VerboseTimingGuard<'a> {
     start_and_message: Option<(Instant, Option<usize>, String)>,
+    report: Option<&'a TimePassesReport>,
     _guard: TimingGuard<'a>,
 }
 
 impl<'a> VerboseTimingGuard<'a> {
-    pub fn start(message: Option<String>, _guard: TimingGuard<'a>) -> Self {
-        VerboseTimingGuard {
-            _guard,
-            start_and_message: message.map(|msg| (Instant::now(), get_resident_set_size(), msg)),
+    pub fn start(
+        message: Option<String>,
+        report: Option<&'a TimePassesReport>,
+        _guard: TimingGuard<'a>,
+    ) -> Self {
+        let start_and_message = message.map(|msg| (Instant::now(), get_resident_set_size(), msg));
+        let report = start_and_message.as_ref().and(report);
+        if let (Some(report), Some((_, start_rss, message))) = (report, &start_and_message) {
+            report.start_pass(message, *start_rss);
         }
+        VerboseTimingGuard { _guard, report, start_and_message }
     }
 
     #[inline(always)]
@@ -746,13 +812,239 @@ fn drop(&mut self) {
             let end_rss = get_resident_set_size();
             let dur = start_time.elapsed();
 
-            if should_print_passes(dur, start_rss, end_rss) {
+            if let Some(report) = self.report {
+                report.end_pass(dur, end_rss);
+            } else if should_print_passes(dur, start_rss, end_rss) {
                 print_time_passes_entry(&message, dur, start_rss, end_rss);
             }
         }
     }
 }
 
+#[must_use]
+pub struct QueryTimingGuard<'a>(Option<(&'a TimePassesReport, &'static str)>);
+
+impl<'a> QueryTimingGuard<'a> {
+    #[inline(always)]
+    fn start(query_name: &'static str, report: Option<&'a TimePassesReport>) -> Self {
+        QueryTimingGuard(report.map(|report| {
+            cold_path(|| report.start_query(query_name));
+            (report, query_name)
+        }))
+    }
+
+    #[inline(always)]
+    pub fn finish(self) {}
+}
+
+impl Drop for QueryTimingGuard<'_> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        if let Some((report, query_name)) = self.0 {
+            cold_path(|| report.end_query(query_name));
+        }
+    }
+}
+
+/// Number of queries listed in the `top_queries` of a [`TimePassesReport`].
+const TOP_QUERIES: usize = 20;
+
+/// The passes and query providers timed for `-Z time-passes-format=json`.
+///
+/// Instead of printing a line per pass, the passes are collected into a tree
+/// that mirrors how they are nested and printed as a single JSON document once
+/// compilation finished. Each pass lists its wall time, the resident set size
+/// before and after it, and how often each query was computed while it, and
+/// none of the passes nested in it, was running. The report ends with the
+/// queries that took the most time, not counting the time spent in the queries
+/// they called.
+pub struct TimePassesReport {
+    start: Instant,
+    start_rss: Option<usize>,
+    inner: Mutex<TimePassesReportInner>,
+}
+
+#[derive(Default)]
+struct TimePassesReportInner {
+    /// Passes that ended outside of any other pass.
+    passes: Vec<PassTiming>,
+    /// Passes that are still running on each thread, innermost last.
+    running_passes: FxHashMap<u32, Vec<PassTiming>>,
+    /// Query providers that are still running on each thread, innermost last,
+    /// with when they started and how long the queries they called took.
+    running_queries: FxHashMap<u32, Vec<(&'static str, Instant, Duration)>>,
+    queries: FxHashMap<&'static str, QueryTiming>,
+}
+
+struct PassTiming {
+    name: String,
+    time: Duration,
+    start_rss: Option<usize>,
+    end_rss: Option<usize>,
+    query_counts: FxHashMap<&'static str, usize>,
+    passes: Vec<PassTiming>,
+}
+
+#[derive(Default)]
+struct QueryTiming {
+    count: usize,
+    self_time: Duration,
+}
+
+impl TimePassesReport {
+    fn new() -> TimePassesReport {
+        TimePassesReport {
+            start: Instant::now(),
+            start_rss: get_resident_set_size(),
+            inner: Default::default(),
+        }
+    }
+
+    fn start_pass(&self, name: &str, start_rss: Option<usize>) {
+        let pass = PassTiming {
+            name: name.to_owned(),
+            time: Duration::ZERO,
+            start_rss,
+            end_rss: None,
+            query_counts: Default::default(),
+            passes: Vec::new(),
+        };
+        self.inner.lock().running_passes.entry(get_thread_id()).or_default().push(pass);
+    }
+
+    fn end_pass(&self, time: Duration, end_rss: Option<usize>) {
+        let inner = &mut *self.inner.lock();
+        let running = inner.running_passes.get_mut(&get_thread_id()).unwrap();
+        let mut pass = running.pop().unwrap();
+        pass.time = time;
+        pass.end_rss = end_rss;
+        match running.last_mut() {
+            Some(parent) => parent.passes.push(pass),
+            None => inner.passes.push(pass),
+        }
+    }
+
+    fn start_query(&self, name: &'static str) {
+        let running = (name, Instant::now(), Duration::ZERO);
+        self.inner.lock().running_queries.entry(get_thread_id()).or_default().push(running);
+    }
+
+    fn end_query(&self, name: &'static str) {
+        let thread_id = get_thread_id();
+        let inner = &mut *self.inner.lock();
+        let running = inner.running_queries.get_mut(&thread_id).unwrap();
+        let (_, start, nested_time) = running.pop().unwrap();
+        let time = start.elapsed();
+        if let Some((_, _, parent_nested_time)) = running.last_mut() {
+            *parent_nested_time += time;
+        }
+
+        let timing = inner.queries.entry(name).or_default();
+        timing.count += 1;
+        timing.self_time += time.saturating_sub(nested_time);
+        if let Some(pass) = inner.running_passes.get_mut(&thread_id).and_then(|p| p.last_mut()) {
+            *pass.query_counts.entry(name).or_default() += 1;
+        }
+    }
+
+    /// Renders the report as JSON. Times are in seconds and sizes in bytes.
+    fn to_json(&self) -> String {
+        let inner = self.inner.lock();
+        let mut out = String::new();
+        out.push_str("{\"total\":{");
+        write_timing_json(
+            &mut out,
+            "total",
+            self.start.elapsed(),
+            self.start_rss,
+            get_resident_set_size(),
+        );
+        out.push_str("},\"passes\":");
+        write_passes_json(&mut out, &inner.passes);
+
+        let mut queries: Vec<_> = inner.queries.iter().collect();
+        queries.sort_by(|(a_name, a), (b_name, b)| {
+            b.self_time.cmp(&a.self_time).then_with(|| a_name.cmp(b_name))
+        });
+        out.push_str(",\"top_queries\":[");
+        for (i, (name, timing)) in queries.into_iter().take(TOP_QUERIES).enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write!(
+                out,
+                "{{\"name\":\"{name}\",\"count\":{},\"self_time\":{:.6}}}",
+                timing.count,
+                timing.self_time.as_secs_f64()
+            )
+            .unwrap();
+        }
+        out.push_str("]}");
+        out
+    }
+}
+
+fn write_passes_json(out: &mut String, passes: &[PassTiming]) {
+    out.push('[');
+    for (i, pass) in passes.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push('{');
+        write_timing_json(out, &pass.name, pass.time, pass.start_rss, pass.end_rss);
+
+        let mut query_counts: Vec<_> = pass.query_counts.iter().collect();
+        query_counts.sort();
+        out.push_str(",\"queries\":{");
+        for (i, (name, count)) in query_counts.into_iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write!(out, "\"{name}\":{count}").unwrap();
+        }
+        out.push_str("},\"passes\":");
+        write_passes_json(out, &pass.passes);
+        out.push('}');
+    }
+    out.push(']');
+}
+
+/// Writes the fields shared by the passes and the total of the report.
+fn write_timing_json(
+    out: &mut String,
+    name: &str,
+    time: Duration,
+    start_rss: Option<usize>,
+    end_rss: Option<usize>,
+) {
+    let rss = |rss: Option<usize>| rss.map_or("null".to_owned(), |rss| rss.to_string());
+    out.push_str("\"name\":");
+    write_json_string(out, name);
+    write!(
+        out,
+        ",\"time\":{:.6},\"rss_start\":{},\"rss_end\":{}",
+        time.as_secs_f64(),
+        rss(start_rss),
+        rss(end_rss)
+    )
+    .unwrap();
+}
+
+/// Writes `s` as a JSON string. Query names don't need this, but pass names
+/// can contain arbitrary text, e.g. the name of a codegen unit.
+fn write_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
 fn should_print_passes(dur: Duration, start_rss: Option<usize>, end_rss: Option<usize>) -> bool {
     if dur.as_millis() > 5 {
         return true;
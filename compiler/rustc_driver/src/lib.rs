@@ -18,7 +18,9 @@
 
 use rustc_ast as ast;
 use rustc_codegen_ssa::{traits::CodegenBackend, CodegenErrors, CodegenResults};
-use rustc_data_structures::profiling::{get_resident_set_size, print_time_passes_entry};
+use rustc_data_structures::profiling::{
+    get_resident_set_size, print_time_passes_entry, TimePassesFormat,
+};
 use rustc_data_structures::sync::SeqCst;
 use rustc_errors::registry::{InvalidErrorCode, Registry};
 use rustc_errors::{ErrorGuaranteed, PResult};
@@ -57,6 +59,7 @@
 
 pub mod args;
 pub mod pretty;
+mod public_api;
 mod session_diagnostics;
 
 use crate::session_diagnostics::{
@@ -130,7 +133,10 @@ impl Callbacks for TimePassesCallbacks {
     fn config(&mut self, config: &mut interface::Config) {
         // If a --prints=... option has been given, we don't print the "total"
         // time because it will mess up the --prints output. See #64339.
-        self.time_passes = config.opts.prints.is_empty() && config.opts.time_passes();
+        // The JSON report of `-Z time-passes-format=json` has a total of its own.
+        self.time_passes = config.opts.prints.is_empty()
+            && config.opts.time_passes()
+            && config.opts.unstable_opts.time_passes_format == TimePassesFormat::Text;
         config.opts.trimmed_def_paths = TrimmedDefPaths::GoodPath;
     }
 }
@@ -405,6 +411,11 @@ fn run_compiler(
                 return early_exit();
             }
 
+            if sess.opts.prints.contains(&PrintRequest::PublicApi) {
+                queries.global_ctxt()?.peek_mut().enter(|tcx| public_api::print(tcx));
+                return early_exit();
+            }
+
             queries.ongoing_codegen()?;
 
             if sess.opts.unstable_opts.print_type_sizes {
@@ -420,6 +431,10 @@ fn run_compiler(
             linker.link()?
         }
 
+        if sess.opts.unstable_opts.print_cfg_eval {
+            sess.parse_sess.cfg_evals.print(sess.source_map());
+        }
+
         if sess.opts.unstable_opts.perf_stats {
             sess.print_perf_stats();
         }
@@ -656,9 +671,10 @@ fn print_crate_info(
     temps_dir: &Option<PathBuf>,
 ) -> Compilation {
     use rustc_session::config::PrintRequest::*;
-    // NativeStaticLibs and LinkArgs are special - printed during linking
+    // NativeStaticLibs and LinkArgs are special - printed during linking,
+    // and PublicApi is printed after analysis
     // (empty iterator returns true)
-    if sess.opts.prints.iter().all(|&p| p == NativeStaticLibs || p == LinkArgs) {
+    if sess.opts.prints.iter().all(|&p| p == NativeStaticLibs || p == LinkArgs || p == PublicApi) {
         return Compilation::Continue;
     }
 
@@ -757,9 +773,14 @@ fn print_crate_info(
             // Any output here interferes with Cargo's parsing of other printed output
             NativeStaticLibs => {}
             LinkArgs => {}
+            PublicApi => {}
         }
     }
-    Compilation::Stop
+    if sess.opts.prints.contains(&PublicApi) {
+        Compilation::Continue
+    } else {
+        Compilation::Stop
+    }
 }
 
 /// Prints version information
@@ -1353,6 +1374,10 @@ pub(super) fn install() {}
 }
 
 pub fn main() -> ! {
+    // Run out-of-process proc macros for the compiler that started this one, if
+    // that is what this process is for.
+    rustc_expand::proc_macro_process::install_child_entry();
+
     let start_time = Instant::now();
     let start_rss = get_resident_set_size();
     init_rustc_env_logger();
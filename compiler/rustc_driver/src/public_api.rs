@@ -0,0 +1,532 @@
+//! The `--print=public-api` listing of the items other crates can use.
+//!
+//! Every reachable item gets a line with its full signature, generics and
+//! bounds, in a normalized form: bounds are all moved to `where` clauses and
+//! sorted, and paths are never trimmed. The lines are sorted, so that diffing
+//! the listings of two versions of a crate shows what changed in its API.
+
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir as hir;
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_infer::infer::TyCtxtInferExt;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{self, GenericParamDefKind, ToPredicate, TyCtxt};
+use rustc_span::symbol::{kw, sym, Symbol};
+use rustc_span::DUMMY_SP;
+use rustc_target::spec::abi::Abi;
+use rustc_trait_selection::traits;
+
+use std::fmt::Write;
+
+pub fn print(tcx: TyCtxt<'_>) {
+    let mut lines = with_no_trimmed_paths!(public_api(tcx));
+    lines.sort();
+    lines.dedup();
+    for line in lines {
+        println!("{line}");
+    }
+}
+
+fn public_api(tcx: TyCtxt<'_>) -> Vec<String> {
+    let access_levels = tcx.privacy_access_levels(());
+    let crate_items = tcx.hir_crate_items(());
+    let mut lines = Vec::new();
+    for id in crate_items.items() {
+        if access_levels.is_exported(id.def_id.def_id) {
+            item_lines(tcx, tcx.hir().item(id), &mut lines);
+        }
+    }
+    for id in crate_items.foreign_items() {
+        let def_id = id.def_id.def_id;
+        if access_levels.is_exported(def_id) {
+            lines.push(value_decl(tcx, def_id.to_def_id(), &tcx.def_path_str(def_id.to_def_id())));
+        }
+    }
+    lines
+}
+
+fn item_lines<'tcx>(tcx: TyCtxt<'tcx>, item: &'tcx hir::Item<'tcx>, lines: &mut Vec<String>) {
+    let def_id = item.def_id.to_def_id();
+    let path = tcx.def_path_str(def_id);
+    match item.kind {
+        hir::ItemKind::Fn(..) | hir::ItemKind::Const(..) | hir::ItemKind::Static(..) => {
+            lines.push(value_decl(tcx, def_id, &path));
+        }
+        hir::ItemKind::Mod(..) => lines.push(format!("pub mod {path}")),
+        hir::ItemKind::Macro(ref macro_def, _) if macro_def.macro_rules => {
+            // Exported `macro_rules!` macros live at the crate root.
+            lines.push(format!("macro_rules! {}", item.ident));
+        }
+        hir::ItemKind::Macro(..) => lines.push(format!("pub macro {path}")),
+        hir::ItemKind::TyAlias(..) => {
+            lines.push(format!(
+                "pub type {path}{} = {}{}",
+                generic_params(tcx, def_id, &[]),
+                tcx.type_of(def_id),
+                where_clause(tcx, def_id),
+            ));
+        }
+        hir::ItemKind::Struct(..) | hir::ItemKind::Union(..) | hir::ItemKind::Enum(..) => {
+            adt_lines(tcx, def_id, &path, lines);
+        }
+        hir::ItemKind::Trait(..) => trait_lines(tcx, def_id, &path, lines),
+        hir::ItemKind::Impl(..) => impl_lines(tcx, item.def_id.def_id, lines),
+        hir::ItemKind::Use(use_path, kind) => {
+            let module = tcx.def_path_str(tcx.parent(def_id));
+            let prefix = if module.is_empty() { String::new() } else { format!("{module}::") };
+            let Res::Def(_, target) = use_path.res else { return };
+            match kind {
+                hir::UseKind::Single => lines.push(format!(
+                    "pub use {prefix}{} = {}",
+                    item.ident,
+                    tcx.def_path_str(target)
+                )),
+                hir::UseKind::Glob => {
+                    lines.push(format!("pub use {prefix}* = {}::*", tcx.def_path_str(target)))
+                }
+                hir::UseKind::ListStem => {}
+            }
+        }
+        _ => {}
+    }
+}
+
+fn non_exhaustive(tcx: TyCtxt<'_>, def_id: DefId) -> &'static str {
+    if tcx.has_attr(def_id, sym::non_exhaustive) {
+        "#[non_exhaustive] "
+    } else {
+        ""
+    }
+}
+
+fn adt_lines(tcx: TyCtxt<'_>, def_id: DefId, path: &str, lines: &mut Vec<String>) {
+    let adt = tcx.adt_def(def_id);
+    let header = format!("{path}{}", generic_params(tcx, def_id, &[]));
+    let where_clause = where_clause(tcx, def_id);
+    let descr = adt.descr();
+    if adt.is_enum() {
+        lines.push(format!("{}pub enum {header}{where_clause}", non_exhaustive(tcx, def_id)));
+        for variant in adt.variants() {
+            lines.push(format!(
+                "pub enum {header}{where_clause} {{ {}{}{} }}",
+                non_exhaustive(tcx, variant.def_id),
+                variant.name,
+                fields(tcx, variant),
+            ));
+        }
+    } else {
+        lines.push(format!(
+            "{}pub {descr} {header}{}{where_clause}",
+            non_exhaustive(tcx, def_id),
+            fields(tcx, adt.non_enum_variant()),
+        ));
+    }
+    auto_trait_lines(tcx, def_id, lines);
+}
+
+/// Returns the fields of a struct, union or variant, e.g. `(pub u32, _)` or
+/// `{ pub x: u32, .. }` if other crates can't name all of them.
+fn fields(tcx: TyCtxt<'_>, variant: &ty::VariantDef) -> String {
+    // Variant fields are as public as their enum.
+    let is_enum_variant = tcx.def_kind(variant.def_id) == DefKind::Variant;
+    let is_visible =
+        |field: &ty::FieldDef| is_enum_variant || tcx.visibility(field.did).is_public();
+    match variant.ctor_kind {
+        hir::def::CtorKind::Const => String::new(),
+        hir::def::CtorKind::Fn => {
+            let fields: Vec<_> = variant
+                .fields
+                .iter()
+                .map(|field| {
+                    if !is_visible(field) {
+                        "_".to_string()
+                    } else if is_enum_variant {
+                        tcx.type_of(field.did).to_string()
+                    } else {
+                        format!("pub {}", tcx.type_of(field.did))
+                    }
+                })
+                .collect();
+            format!("({})", fields.join(", "))
+        }
+        hir::def::CtorKind::Fictive => {
+            let mut fields: Vec<_> = variant
+                .fields
+                .iter()
+                .filter(|&field| is_visible(field))
+                .map(|field| {
+                    let vis = if is_enum_variant { "" } else { "pub " };
+                    format!("{vis}{}: {}", field.name, tcx.type_of(field.did))
+                })
+                .collect();
+            if variant.fields.iter().any(|field| !is_visible(field)) {
+                fields.push("..".to_string());
+            }
+            if fields.is_empty() {
+                " {}".to_string()
+            } else {
+                format!(" {{ {} }}", fields.join(", "))
+            }
+        }
+    }
+}
+
+/// Lists which auto traits a type implements, which other crates rely on
+/// without the type's author spelling it out.
+fn auto_trait_lines(tcx: TyCtxt<'_>, def_id: DefId, lines: &mut Vec<String>) {
+    let auto_traits = [
+        tcx.get_diagnostic_item(sym::Send),
+        tcx.get_diagnostic_item(sym::Sync),
+        tcx.lang_items().unpin_trait(),
+        tcx.get_diagnostic_item(sym::unwind_safe_trait),
+        tcx.get_diagnostic_item(sym::ref_unwind_safe_trait),
+    ];
+    let ty = tcx.type_of(def_id);
+    let param_env = tcx.param_env(def_id);
+    let generics = impl_generic_params(tcx, def_id);
+    let type_params: Vec<_> = tcx
+        .generics_of(def_id)
+        .params
+        .iter()
+        .filter(|param| matches!(param.kind, GenericParamDefKind::Type { .. }))
+        .collect();
+    let unsized_clauses: Vec<_> =
+        unsized_params(tcx, def_id).into_iter().map(|name| format!("{name}: ?Sized")).collect();
+    for trait_def_id in auto_traits.into_iter().flatten() {
+        let trait_path = tcx.def_path_str(trait_def_id);
+        let implements = |param_env| {
+            tcx.infer_ctxt().enter(|infcx| {
+                traits::type_known_to_meet_bound_modulo_regions(
+                    &infcx,
+                    param_env,
+                    ty,
+                    trait_def_id,
+                    DUMMY_SP,
+                )
+            })
+        };
+        // Whether the type implements the trait if all its type parameters do.
+        let implements_if_params_do = || {
+            let bounds = type_params.iter().map(|param| {
+                let trait_ref = ty::TraitRef::new(
+                    trait_def_id,
+                    tcx.mk_substs_trait(tcx.mk_ty_param(param.index, param.name), &[]),
+                );
+                ty::Binder::dummy(trait_ref).without_const().to_predicate(tcx)
+            });
+            let caller_bounds = tcx.mk_predicates(param_env.caller_bounds().iter().chain(bounds));
+            implements(ty::ParamEnv::new(caller_bounds, param_env.reveal(), param_env.constness()))
+        };
+        let mut clauses = unsized_clauses.clone();
+        let line = if implements(param_env) {
+            format!("impl{generics} {trait_path} for {ty}")
+        } else if !type_params.is_empty() && implements_if_params_do() {
+            clauses.extend(type_params.iter().map(|param| format!("{}: {trait_path}", param.name)));
+            format!("impl{generics} {trait_path} for {ty}")
+        } else {
+            format!("impl{generics} !{trait_path} for {ty}")
+        };
+        clauses.sort();
+        if clauses.is_empty() {
+            lines.push(line);
+        } else {
+            lines.push(format!("{line} where {}", clauses.join(", ")));
+        }
+    }
+}
+
+fn trait_lines(tcx: TyCtxt<'_>, def_id: DefId, path: &str, lines: &mut Vec<String>) {
+    let trait_def = tcx.trait_def(def_id);
+    let unsafety = match trait_def.unsafety {
+        hir::Unsafety::Unsafe => "unsafe ",
+        hir::Unsafety::Normal => "",
+    };
+    let auto = if trait_def.has_auto_impl { "auto " } else { "" };
+    let header = format!("pub {unsafety}{auto}trait {path}{}", generic_params(tcx, def_id, &[]));
+    lines.push(format!("{header}{}", where_clause(tcx, def_id)));
+    for item in tcx.associated_items(def_id).in_definition_order() {
+        let has_value = item.defaultness(tcx).has_value();
+        let decl = match item.kind {
+            ty::AssocKind::Fn => {
+                let body = if has_value { " { .. }" } else { ";" };
+                format!("{}{body}", value_decl(tcx, item.def_id, item.name.as_str()))
+            }
+            ty::AssocKind::Const => {
+                let value = if has_value { " = .." } else { "" };
+                format!("{}{value};", value_decl(tcx, item.def_id, item.name.as_str()))
+            }
+            ty::AssocKind::Type => {
+                let value = if has_value {
+                    format!(" = {}", tcx.type_of(item.def_id))
+                } else {
+                    String::new()
+                };
+                format!(
+                    "type {}{}{}{value};",
+                    item.name,
+                    generic_params(tcx, item.def_id, &[]),
+                    item_bounds(tcx, item.def_id),
+                )
+            }
+        };
+        lines.push(format!("{header} {{ {decl} }}"));
+    }
+}
+
+fn impl_lines(tcx: TyCtxt<'_>, def_id: LocalDefId, lines: &mut Vec<String>) {
+    let access_levels = tcx.privacy_access_levels(());
+    let def_id = def_id.to_def_id();
+    let generics = generic_params(tcx, def_id, &[]);
+    let where_clause = where_clause(tcx, def_id);
+    match tcx.impl_trait_ref(def_id) {
+        // The items of trait impls are the ones of the trait.
+        Some(trait_ref) => {
+            let polarity = match tcx.impl_polarity(def_id) {
+                ty::ImplPolarity::Negative => "!",
+                ty::ImplPolarity::Positive | ty::ImplPolarity::Reservation => "",
+            };
+            lines.push(format!(
+                "impl{generics} {polarity}{} for {}{where_clause}",
+                trait_ref.print_only_trait_path(),
+                trait_ref.self_ty(),
+            ));
+        }
+        None => {
+            let header = format!("impl{generics} {}{where_clause}", tcx.type_of(def_id));
+            for item in tcx.associated_items(def_id).in_definition_order() {
+                if access_levels.is_exported(item.def_id.expect_local()) {
+                    let decl = value_decl(tcx, item.def_id, item.name.as_str());
+                    lines.push(format!("{header} {{ pub {decl} }}"));
+                }
+            }
+        }
+    }
+}
+
+/// Returns the declaration of a function, constant or static, without a
+/// visibility for associated items.
+fn value_decl(tcx: TyCtxt<'_>, def_id: DefId, name: &str) -> String {
+    let vis = if tcx.opt_associated_item(def_id).is_some() { "" } else { "pub " };
+    match tcx.def_kind(def_id) {
+        DefKind::Const | DefKind::AssocConst => {
+            format!("{vis}const {name}: {}", tcx.type_of(def_id))
+        }
+        DefKind::Static(mutability) => {
+            format!("{vis}static {}{name}: {}", mutability.prefix_str(), tcx.type_of(def_id))
+        }
+        _ => format!("{vis}{}", fn_decl(tcx, def_id, name)),
+    }
+}
+
+fn fn_decl(tcx: TyCtxt<'_>, def_id: DefId, name: &str) -> String {
+    let poly_sig = tcx.fn_sig(def_id);
+    // Late-bound lifetimes aren't part of the generics of the function.
+    let late_bound: Vec<_> = poly_sig
+        .bound_vars()
+        .iter()
+        .filter_map(|var| match var {
+            ty::BoundVariableKind::Region(ty::BrNamed(_, name))
+                if name != kw::UnderscoreLifetime =>
+            {
+                Some(name)
+            }
+            _ => None,
+        })
+        .collect();
+    let sig = poly_sig.skip_binder();
+
+    let mut decl = String::new();
+    if tcx.is_const_fn_raw(def_id) {
+        decl.push_str("const ");
+    }
+    if sig.unsafety == hir::Unsafety::Unsafe {
+        decl.push_str("unsafe ");
+    }
+    if sig.abi != Abi::Rust {
+        write!(decl, "extern {} ", sig.abi).unwrap();
+    }
+    write!(decl, "fn {name}{}(", generic_params(tcx, def_id, &late_bound)).unwrap();
+    let arg_names = tcx.fn_arg_names(def_id);
+    for (i, input) in sig.inputs().iter().enumerate() {
+        if i > 0 {
+            decl.push_str(", ");
+        }
+        match arg_names.get(i) {
+            Some(arg) if arg.name != kw::Empty => write!(decl, "{arg}: {input}").unwrap(),
+            _ => write!(decl, "_: {input}").unwrap(),
+        }
+    }
+    if sig.c_variadic {
+        decl.push_str(if sig.inputs().is_empty() { "..." } else { ", ..." });
+    }
+    decl.push(')');
+    if !sig.output().is_unit() {
+        write!(decl, " -> {}", sig.output()).unwrap();
+    }
+    decl.push_str(&where_clause(tcx, def_id));
+    decl
+}
+
+/// Returns the generic parameters `def_id` itself declares, lifetimes first,
+/// e.g. `<'a, T = u8, const N: usize>`.
+fn generic_params(tcx: TyCtxt<'_>, def_id: DefId, late_bound: &[Symbol]) -> String {
+    let mut lifetimes = Vec::new();
+    let mut others = Vec::new();
+    for param in &tcx.generics_of(def_id).params {
+        match param.kind {
+            GenericParamDefKind::Lifetime => lifetimes.push(param.name.to_string()),
+            // `impl Trait` arguments show up in the argument types instead.
+            GenericParamDefKind::Type { synthetic: true, .. } => {}
+            GenericParamDefKind::Type { .. } if param.name == kw::SelfUpper => {}
+            GenericParamDefKind::Type { has_default, .. } => {
+                if has_default {
+                    others.push(format!("{} = {}", param.name, tcx.type_of(param.def_id)));
+                } else {
+                    others.push(param.name.to_string());
+                }
+            }
+            GenericParamDefKind::Const { has_default } => {
+                let mut param_decl = format!("const {}: {}", param.name, tcx.type_of(param.def_id));
+                if has_default {
+                    write!(param_decl, " = {}", tcx.const_param_default(param.def_id)).unwrap();
+                }
+                others.push(param_decl);
+            }
+        }
+    }
+    lifetimes.extend(late_bound.iter().map(|name| name.to_string()));
+    lifetimes.extend(others);
+    if lifetimes.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", lifetimes.join(", "))
+    }
+}
+
+/// Returns the generic parameters of an ADT as the ones of an impl for it,
+/// i.e. without their defaults.
+fn impl_generic_params(tcx: TyCtxt<'_>, def_id: DefId) -> String {
+    let generics = tcx.generics_of(def_id);
+    let (lifetimes, others): (Vec<_>, Vec<_>) = generics
+        .params
+        .iter()
+        .partition(|param| matches!(param.kind, GenericParamDefKind::Lifetime));
+    let params: Vec<_> = lifetimes
+        .into_iter()
+        .chain(others)
+        .map(|param| match param.kind {
+            GenericParamDefKind::Const { .. } => {
+                format!("const {}: {}", param.name, tcx.type_of(param.def_id))
+            }
+            _ => param.name.to_string(),
+        })
+        .collect();
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", params.join(", "))
+    }
+}
+
+/// Returns the type parameters of `def_id` without the implicit `Sized` bound.
+fn unsized_params(tcx: TyCtxt<'_>, def_id: DefId) -> Vec<Symbol> {
+    let sized_trait = tcx.lang_items().sized_trait();
+    let predicates = tcx.explicit_predicates_of(def_id).predicates;
+    let is_sized = |index| {
+        predicates.iter().any(|&(predicate, _)| match predicate.kind().skip_binder() {
+            ty::PredicateKind::Trait(trait_pred) => {
+                let self_ty = trait_pred.self_ty();
+                Some(trait_pred.def_id()) == sized_trait
+                    && matches!(*self_ty.kind(), ty::Param(param) if param.index == index)
+            }
+            _ => false,
+        })
+    };
+    tcx.generics_of(def_id)
+        .params
+        .iter()
+        .filter(|param| {
+            matches!(param.kind, GenericParamDefKind::Type { synthetic: false, .. })
+                && param.name != kw::SelfUpper
+                && !is_sized(param.index)
+        })
+        .map(|param| param.name)
+        .collect()
+}
+
+/// Returns the sorted `where` clause with all the bounds `def_id` itself
+/// declares, where type parameters without the implicit `Sized` bound get a
+/// `?Sized` one.
+fn where_clause(tcx: TyCtxt<'_>, def_id: DefId) -> String {
+    let sized_trait = tcx.lang_items().sized_trait();
+    let generics = tcx.generics_of(def_id);
+    let mut synthetic_params = FxHashSet::default();
+    for param in &generics.params {
+        if let GenericParamDefKind::Type { synthetic: true, .. } = param.kind {
+            synthetic_params.insert(param.index);
+        }
+    }
+    let is_param = |ty: ty::Ty<'_>, params: &FxHashSet<u32>| match *ty.kind() {
+        ty::Param(param) => params.contains(&param.index),
+        _ => false,
+    };
+
+    let mut clauses = Vec::new();
+    for &(predicate, _) in tcx.explicit_predicates_of(def_id).predicates {
+        match predicate.kind().skip_binder() {
+            ty::PredicateKind::Trait(trait_pred) => {
+                if is_param(trait_pred.self_ty(), &synthetic_params) {
+                    continue;
+                }
+                if Some(trait_pred.def_id()) == sized_trait
+                    && matches!(trait_pred.self_ty().kind(), ty::Param(_))
+                {
+                    continue;
+                }
+            }
+            ty::PredicateKind::Projection(proj_pred) => {
+                if is_param(proj_pred.projection_ty.self_ty(), &synthetic_params) {
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        clauses.push(predicate.to_string());
+    }
+    clauses.extend(unsized_params(tcx, def_id).into_iter().map(|name| format!("{name}: ?Sized")));
+
+    clauses.sort();
+    if clauses.is_empty() {
+        String::new()
+    } else {
+        format!(" where {}", clauses.join(", "))
+    }
+}
+
+/// Returns the bounds of an associated type, e.g. `: Iterator<Item = u8>`.
+fn item_bounds(tcx: TyCtxt<'_>, def_id: DefId) -> String {
+    let sized_trait = tcx.lang_items().sized_trait();
+    let mut is_sized = false;
+    let mut bounds = Vec::new();
+    for &(predicate, _) in tcx.explicit_item_bounds(def_id) {
+        match predicate.kind().skip_binder() {
+            ty::PredicateKind::Trait(trait_pred) if Some(trait_pred.def_id()) == sized_trait => {
+                is_sized = true;
+            }
+            ty::PredicateKind::Trait(trait_pred) => {
+                bounds.push(trait_pred.trait_ref.print_only_trait_path().to_string());
+            }
+            _ => bounds.push(predicate.to_string()),
+        }
+    }
+    if !is_sized {
+        bounds.push("?Sized".to_string());
+    }
+    bounds.sort();
+    if bounds.is_empty() {
+        String::new()
+    } else {
+        format!(": {}", bounds.join(" + "))
+    }
+}
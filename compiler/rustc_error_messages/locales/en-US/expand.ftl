@@ -20,3 +20,24 @@ expand_var_still_repeating =
     variable '{$ident}' is still repeating at this depth
 
 expand_meta_var_dif_seq_matchers = {$msg}
+
+expand_failed_to_write_macro_expansions =
+    failed to write macro expansions to `{$path}`: {$error}
+
+expand_failed_to_save_proc_macro_cache =
+    failed to save the proc macro cache to `{$path}`: {$error}
+
+expand_proc_macro_time_limit =
+    proc macro exceeded its time limit of {$limit}ms
+    .note = the process running the proc macro was stopped, see `-Z proc-macro-time-limit`
+
+expand_proc_macro_memory_limit =
+    proc macro may have exceeded its memory limit of {$limit} MiB
+    .note = the process running the proc macro aborted, likely because it ran out of memory, see `-Z proc-macro-memory-limit`
+
+expand_proc_macro_out_of_process_unsupported =
+    proc macros can't run in a separate process with this compiler driver, running them in-process
+    .note = only `rustc` supports `-Z proc-macro-execution-strategy=out-of-process`
+
+expand_proc_macro_process_failed =
+    failed to run proc macro in a separate process: {$error}
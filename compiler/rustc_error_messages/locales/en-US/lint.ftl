@@ -410,6 +410,8 @@ lint_node_source = `forbid` level set here
 
 lint_command_line_source = `forbid` lint level was set on command line
 
+lint_lint_config_source = `forbid` lint level was set by lint-config at line {$line}
+
 lint_malformed_attribute = malformed lint attribute input
 
 lint_bad_attribute_argument = bad attribute argument
@@ -433,3 +435,6 @@ lint_check_name_unknown_tool = unknown lint tool: `{$tool_name}`
 lint_check_name_warning = {$msg}
 
 lint_check_name_deprecated = lint name `{$lint_name}` is deprecated and does not have an effect anymore. Use: {$new_name}
+
+lint_lint_config_unknown = unknown lint: `{$lint_name}`
+    .note = requested by lint-config `{$path}` at line {$line}
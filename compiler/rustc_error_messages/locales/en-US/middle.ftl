@@ -15,3 +15,15 @@ middle_previous_use_here =
 middle_limit_invalid =
     `limit` must be a non-negative integer
     .label = {$error_str}
+
+middle_type_size_limit_file_unreadable =
+    failed to read type size limit file `{$path}`: {$error}
+
+middle_type_size_limit_file_invalid =
+    invalid line {$line} in type size limit file `{$path}`
+    .note = expected `<type> = <size in bytes>`
+
+middle_type_size_limit_exceeded =
+    `{$ty}` is {$size} bytes, which exceeds its size limit of {$limit} bytes
+    .note = the size limit is set in `{$path}`
+    .layout_note = the layout of `{$ty}` is:{$layout}
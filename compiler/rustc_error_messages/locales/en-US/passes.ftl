@@ -271,3 +271,19 @@ passes_collapse_debuginfo = `collapse_debuginfo` attribute should be applied to
 
 passes_deprecated_annotation_has_no_effect = this `#[deprecated]` annotation has no effect
     .suggestion = remove the unnecessary deprecation attribute
+
+passes_error_writing_dep_usage =
+    error writing dependency usage to `{$path}`: {$error}
+
+passes_error_writing_used_items =
+    error writing used items to `{$path}`: {$error}
+
+passes_error_reading_used_items =
+    error reading used items from `{$path}`: {$error}
+
+passes_unused_pub_item = {$descr} `{$name}` is public but never used
+    .note = neither this crate nor the crates whose `--emit=used-items` reports are in `{$dir}` use it
+
+passes_pub_item_only_used_locally = {$descr} `{$name}` is public but only used by this crate
+    .note = none of the crates whose `--emit=used-items` reports are in `{$dir}` use it
+    .help = consider making it `pub(crate)`
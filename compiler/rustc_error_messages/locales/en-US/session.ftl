@@ -66,3 +66,7 @@ session_crate_name_invalid = crate names cannot start with a `-`, but `{$s}` has
 session_crate_name_empty = crate name must not be empty
 
 session_invalid_character_in_create_name = invalid character `{$character}` in crate name: `{$crate_name}`
+
+session_failed_to_write_suggestions = failed to write the source file patched with suggestions to `{$path}`: {$err}
+
+session_failed_to_write_diagnostic_baseline = failed to write the diagnostic baseline to `{$path}`: {$err}
@@ -0,0 +1,257 @@
+//! Applies machine-applicable suggestions to the sources they were made for.
+//!
+//! This is what `-Z apply-suggestions` uses to fix code without going through
+//! `rustfix`. Suggestions are applied as a whole or not at all: if any part of a
+//! suggestion overlaps an edit of a suggestion that was accepted before it, the
+//! whole suggestion is dropped, like `rustfix` does.
+
+use crate::{CodeSuggestion, Substitution};
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::sync::Lrc;
+use rustc_span::source_map::SourceMap;
+use rustc_span::{BytePos, FileName, SourceFile};
+
+use std::fmt::Write;
+use std::ops::Range;
+
+#[cfg(test)]
+mod tests;
+
+/// Lines of unchanged context shown around each hunk of a unified diff.
+const DIFF_CONTEXT: usize = 3;
+
+/// A source file with suggestions applied to it.
+pub struct PatchedFile {
+    pub name: FileName,
+    /// The normalized source of the file, without byte order mark and with `\n`
+    /// line endings.
+    pub original: Lrc<String>,
+    pub patched: String,
+    /// Number of suggestions that were applied to this file.
+    pub applied: usize,
+    /// The edits that turn `original` into `patched`, sorted by position.
+    edits: Vec<Edit>,
+    file: Lrc<SourceFile>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct Edit {
+    /// Byte range in the original file.
+    range: Range<usize>,
+    replacement: String,
+}
+
+impl Edit {
+    fn overlaps(&self, other: &Edit) -> bool {
+        // Two insertions at the same position overlap as well, since there is no
+        // way to tell in which order they should be applied.
+        self.range.start == other.range.start
+            || (self.range.start < other.range.end && other.range.start < self.range.end)
+    }
+}
+
+/// Applies the first substitution of every suggestion in `suggestions` and
+/// returns the files that changed, in the order they were loaded into `sm`.
+///
+/// Suggestions are considered in the order given. Suggestions with several
+/// alternatives are skipped since there is no way to pick one of them, as are
+/// suggestions that touch code which can't be written back (e.g. code from
+/// other crates or from macro expansions without a real file). An exact
+/// duplicate of an applied suggestion is not applied again.
+pub fn apply_suggestions(sm: &SourceMap, suggestions: &[CodeSuggestion]) -> Vec<PatchedFile> {
+    // The accepted edits of every file and the number of suggestions they came from,
+    // keyed by the start position of the file.
+    let mut files: FxIndexMap<BytePos, (Lrc<SourceFile>, Vec<Edit>, usize)> = Default::default();
+
+    for suggestion in suggestions {
+        let [substitution] = &suggestion.substitutions[..] else { continue };
+        let Some(edits) = resolve_edits(sm, substitution) else { continue };
+        let accepted = |file: &SourceFile| {
+            files.get(&file.start_pos).map_or(&[][..], |(_, edits, _)| &edits[..])
+        };
+
+        if edits.iter().all(|(file, edit)| accepted(file).contains(edit)) {
+            continue;
+        }
+        let conflicts = edits.iter().enumerate().any(|(i, (file, edit))| {
+            edits[..i].iter().any(|(other_file, other)| {
+                other_file.start_pos == file.start_pos && other.overlaps(edit)
+            }) || accepted(file).iter().any(|other| other.overlaps(edit))
+        });
+        if conflicts {
+            debug!(?suggestion, "dropping suggestion that overlaps an applied one");
+            continue;
+        }
+
+        let mut touched = Vec::new();
+        for (file, edit) in edits {
+            let start_pos = file.start_pos;
+            let (_, file_edits, applied) =
+                files.entry(start_pos).or_insert_with(|| (file, Vec::new(), 0));
+            file_edits.push(edit);
+            if !touched.contains(&start_pos) {
+                touched.push(start_pos);
+                *applied += 1;
+            }
+        }
+    }
+
+    files.sort_keys();
+    files
+        .into_iter()
+        .map(|(_, (file, mut edits, applied))| {
+            edits.sort_by_key(|edit| (edit.range.start, edit.range.end));
+            let original = file.src.clone().unwrap();
+            let patched = splice(&original, 0..original.len(), &edits);
+            PatchedFile { name: file.name.clone(), original, patched, applied, edits, file }
+        })
+        .collect()
+}
+
+/// Maps the parts of `substitution` to edits of the files they are in, or
+/// returns `None` if any of them can't be applied.
+fn resolve_edits(
+    sm: &SourceMap,
+    substitution: &Substitution,
+) -> Option<Vec<(Lrc<SourceFile>, Edit)>> {
+    substitution
+        .parts
+        .iter()
+        .map(|part| {
+            if part.span.is_dummy() {
+                return None;
+            }
+            let lo = sm.lookup_byte_offset(part.span.lo());
+            let hi = sm.lookup_byte_offset(part.span.hi());
+            if !Lrc::ptr_eq(&lo.sf, &hi.sf)
+                || lo.sf.src.is_none()
+                || !matches!(lo.sf.name, FileName::Real(_))
+            {
+                return None;
+            }
+            let range = lo.pos.0 as usize..hi.pos.0 as usize;
+            Some((lo.sf, Edit { range, replacement: part.snippet.clone() }))
+        })
+        .collect()
+}
+
+/// Returns `src[range]` with `edits` applied. `edits` must be sorted, must not
+/// overlap and must lie within `range`.
+fn splice(src: &str, range: Range<usize>, edits: &[Edit]) -> String {
+    let mut out = String::with_capacity(range.len());
+    let mut pos = range.start;
+    for edit in edits {
+        out.push_str(&src[pos..edit.range.start]);
+        out.push_str(&edit.replacement);
+        pos = edit.range.end;
+    }
+    out.push_str(&src[pos..range.end]);
+    out
+}
+
+impl PatchedFile {
+    /// Applies the edits to `contents`, the text of the file on disk.
+    ///
+    /// The edits are positions in `original`, so they are mapped back onto the
+    /// byte order mark and `\r\n` line endings of `contents`, and line breaks
+    /// they insert into a file with `\r\n` line endings use them too. Returns
+    /// `None` if `contents` isn't the text the file was loaded from.
+    pub fn patch_contents(&self, contents: &str) -> Option<String> {
+        if !self.file.src_hash.matches(contents) {
+            return None;
+        }
+        let original_pos = |pos: usize| {
+            let pos = self.file.start_pos + BytePos::from_usize(pos);
+            self.file.original_relative_byte_pos(pos).to_usize()
+        };
+        let crlf = contents.contains("\r\n");
+        let edits: Vec<Edit> = self
+            .edits
+            .iter()
+            .map(|edit| Edit {
+                range: original_pos(edit.range.start)..original_pos(edit.range.end),
+                replacement: if crlf {
+                    edit.replacement.replace('\n', "\r\n")
+                } else {
+                    edit.replacement.clone()
+                },
+            })
+            .collect();
+        Some(splice(contents, 0..contents.len(), &edits))
+    }
+
+    /// Renders the changes to this file as a unified diff.
+    pub fn unified_diff(&self) -> String {
+        let src = &self.original[..];
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(src.match_indices('\n').map(|(i, _)| i + 1).filter(|&i| i < src.len()))
+            .collect();
+        let line_of = |pos: usize| line_starts.partition_point(|&start| start <= pos) - 1;
+        let last_line = line_starts.len() - 1;
+
+        // Group the edits into hunks of (first line, last line, edit indices), merging
+        // hunks whose context would overlap.
+        let mut hunks: Vec<(usize, usize, Range<usize>)> = Vec::new();
+        for (i, edit) in self.edits.iter().enumerate() {
+            let first = line_of(edit.range.start);
+            let last = line_of(edit.range.end.saturating_sub(1).max(edit.range.start));
+            let lo = first.saturating_sub(DIFF_CONTEXT);
+            let hi = (last + DIFF_CONTEXT).min(last_line);
+            match hunks.last_mut() {
+                Some((_, prev_hi, edits)) if lo <= *prev_hi + 1 => {
+                    *prev_hi = hi.max(*prev_hi);
+                    edits.end = i + 1;
+                }
+                _ => hunks.push((lo, hi, i..i + 1)),
+            }
+        }
+
+        let name = self.name.prefer_local();
+        let mut out = format!("--- {name}\n+++ {name}\n");
+        let mut offset = 0isize;
+        for (lo, hi, edits) in hunks {
+            let start = line_starts[lo];
+            let end = line_starts.get(hi + 1).copied().unwrap_or(src.len());
+            let old: Vec<&str> = src[start..end].split_inclusive('\n').collect();
+            let new_text = splice(src, start..end, &self.edits[edits]);
+            let new: Vec<&str> = new_text.split_inclusive('\n').collect();
+
+            let old_start = lo + 1;
+            let new_start = (old_start as isize + offset) as usize;
+            offset += new.len() as isize - old.len() as isize;
+            let header_start = |start: usize, len: usize| if len == 0 { start - 1 } else { start };
+            writeln!(
+                out,
+                "@@ -{},{} +{},{} @@",
+                header_start(old_start, old.len()),
+                old.len(),
+                header_start(new_start, new.len()),
+                new.len(),
+            )
+            .unwrap();
+
+            let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+            let suffix = old[prefix..]
+                .iter()
+                .rev()
+                .zip(new[prefix..].iter().rev())
+                .take_while(|(a, b)| a == b)
+                .count();
+            let lines = old[..prefix]
+                .iter()
+                .map(|line| (' ', line))
+                .chain(old[prefix..old.len() - suffix].iter().map(|line| ('-', line)))
+                .chain(new[prefix..new.len() - suffix].iter().map(|line| ('+', line)))
+                .chain(old[old.len() - suffix..].iter().map(|line| (' ', line)));
+            for (marker, line) in lines {
+                out.push(marker);
+                out.push_str(line);
+                if !line.ends_with('\n') {
+                    out.push_str("\n\\ No newline at end of file\n");
+                }
+            }
+        }
+        out
+    }
+}
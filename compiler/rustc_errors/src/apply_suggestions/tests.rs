@@ -0,0 +1,88 @@
+use super::*;
+
+use crate::{Applicability, SubstitutionPart, SuggestionStyle};
+use rustc_span::source_map::FilePathMapping;
+use rustc_span::Span;
+
+use std::path::Path;
+
+fn suggestion(parts: &[(u32, u32, &str)]) -> CodeSuggestion {
+    let parts = parts
+        .iter()
+        .map(|&(lo, hi, snippet)| SubstitutionPart {
+            span: Span::with_root_ctxt(BytePos(lo), BytePos(hi)),
+            snippet: snippet.to_owned(),
+        })
+        .collect();
+    CodeSuggestion {
+        substitutions: vec![Substitution { parts }],
+        msg: "fix it".into(),
+        style: SuggestionStyle::ShowCode,
+        applicability: Applicability::MachineApplicable,
+    }
+}
+
+fn apply(code: &str, suggestions: &[CodeSuggestion]) -> Vec<PatchedFile> {
+    rustc_span::create_default_session_globals_then(|| {
+        let sm = SourceMap::new(FilePathMapping::empty());
+        sm.new_source_file(Path::new("test.rs").to_owned().into(), code.to_owned());
+        apply_suggestions(&sm, suggestions)
+    })
+}
+
+#[test]
+fn overlapping_suggestions_are_dropped() {
+    let code = "fn main() {\n    let mut x = 1;\n}\n";
+    let patched = apply(
+        code,
+        &[
+            // Remove `mut `.
+            suggestion(&[(20, 24, "")]),
+            // Replace `mut x` with `y`, which overlaps the edit above.
+            suggestion(&[(20, 25, "y")]),
+            // Add a type annotation, twice.
+            suggestion(&[(25, 25, ": i32")]),
+            suggestion(&[(25, 25, ": i32")]),
+        ],
+    );
+
+    assert_eq!(patched.len(), 1);
+    assert_eq!(patched[0].applied, 2);
+    assert_eq!(patched[0].patched, "fn main() {\n    let x: i32 = 1;\n}\n");
+}
+
+#[test]
+fn unified_diff() {
+    let code = "fn a() {}\nfn b() {}\nfn c() {}\nfn d() {}\nfn e() {}\nfn f() {}\nfn g() {}\n\
+                fn h() {}\nfn i() {}\nfn j() {}";
+    let patched = apply(code, &[suggestion(&[(13, 14, "bb")]), suggestion(&[(93, 94, "jj")])]);
+
+    assert_eq!(
+        patched[0].unified_diff(),
+        "--- test.rs\n+++ test.rs\n\
+         @@ -1,5 +1,5 @@\n fn a() {}\n-fn b() {}\n+fn bb() {}\n fn c() {}\n fn d() {}\n fn e() {}\n\
+         @@ -7,4 +7,4 @@\n fn g() {}\n fn h() {}\n fn i() {}\n-fn j() {}\n\\ No newline at end of file\n\
+         +fn jj() {}\n\\ No newline at end of file\n"
+    );
+}
+
+#[test]
+fn contents_keep_bom_and_crlf() {
+    let contents = "\u{feff}fn main() {\r\n    let mut x = 1;\r\n}\r\n";
+    let patched = apply(
+        contents,
+        &[
+            // Add an import before `fn main`.
+            suggestion(&[(0, 0, "use std::fmt;\n")]),
+            // Remove `mut `.
+            suggestion(&[(20, 24, "")]),
+        ],
+    );
+
+    assert_eq!(patched[0].patched, "use std::fmt;\nfn main() {\n    let x = 1;\n}\n");
+    assert_eq!(
+        patched[0].patch_contents(contents).as_deref(),
+        Some("\u{feff}use std::fmt;\r\nfn main() {\r\n    let x = 1;\r\n}\r\n")
+    );
+    assert_eq!(patched[0].patch_contents("fn main() {}\n"), None);
+}
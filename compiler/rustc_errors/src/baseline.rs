@@ -0,0 +1,155 @@
+//! Diagnostic baselines, which allow enabling a lint on a crate that doesn't
+//! pass it yet.
+//!
+//! A baseline records the lint diagnostics a crate currently emits. Later
+//! compilations using the baseline suppress exactly those diagnostics while still
+//! reporting new ones (rustc: see `-Z diagnostic-baseline`). Diagnostics are
+//! identified by their lint, their file and a fingerprint of their message and of
+//! the code they point at, but not by their line, so that editing other code in
+//! the same file doesn't invalidate the baseline.
+
+use crate::emitter::Emitter;
+use crate::translation::Translate;
+use crate::{Diagnostic, DiagnosticId};
+
+use rustc_data_structures::stable_hasher::StableHasher;
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+use std::hash::Hash;
+use std::path::Path;
+use std::{fs, io};
+
+/// Version of the baseline file format, bumped whenever fingerprints change.
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+struct BaselineKey {
+    lint: String,
+    file: String,
+    fingerprint: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BaselineFile {
+    version: u32,
+    entries: Vec<BaselineEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BaselineEntry {
+    lint: String,
+    file: String,
+    fingerprint: String,
+    /// Number of diagnostics with this fingerprint, e.g. for the same mistake made
+    /// twice in one file.
+    count: usize,
+}
+
+pub struct DiagnosticBaseline {
+    /// Whether diagnostics are recorded instead of suppressed.
+    recording: bool,
+    /// When suppressing, the number of diagnostics with each key that are still
+    /// to be suppressed. When recording, the number of diagnostics seen so far.
+    counts: BTreeMap<BaselineKey, usize>,
+}
+
+impl DiagnosticBaseline {
+    /// Creates an empty baseline that records every lint diagnostic passed to it.
+    pub fn recording() -> Self {
+        DiagnosticBaseline { recording: true, counts: BTreeMap::new() }
+    }
+
+    /// Loads a baseline that was written by [`DiagnosticBaseline::write`].
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|err| err.to_string())?;
+        let file: BaselineFile = serde_json::from_str(&contents).map_err(|err| err.to_string())?;
+        if file.version != FORMAT_VERSION {
+            return Err(format!(
+                "unsupported version {} (expected {FORMAT_VERSION}), the baseline needs to be \
+                 recorded again",
+                file.version
+            ));
+        }
+
+        let mut counts = BTreeMap::new();
+        for BaselineEntry { lint, file, fingerprint, count } in file.entries {
+            *counts.entry(BaselineKey { lint, file, fingerprint }).or_insert(0) += count;
+        }
+        Ok(DiagnosticBaseline { recording: false, counts })
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Writes the recorded diagnostics to `path`. Entries are sorted, so the file
+    /// doesn't depend on the order the diagnostics were emitted in.
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let entries = self
+            .counts
+            .iter()
+            .map(|(BaselineKey { lint, file, fingerprint }, &count)| BaselineEntry {
+                lint: lint.clone(),
+                file: file.clone(),
+                fingerprint: fingerprint.clone(),
+                count,
+            })
+            .collect();
+        let file = BaselineFile { version: FORMAT_VERSION, entries };
+        fs::write(path, serde_json::to_string_pretty(&file).unwrap() + "\n")
+    }
+
+    /// Records `diagnostic` if this baseline is recording. Otherwise, returns
+    /// `true` if `diagnostic` is covered by the baseline and must not be emitted.
+    pub(crate) fn check(&mut self, diagnostic: &Diagnostic, emitter: &dyn Emitter) -> bool {
+        let Some(key) = baseline_key(diagnostic, emitter) else { return false };
+        if self.recording {
+            *self.counts.entry(key).or_insert(0) += 1;
+            return false;
+        }
+        match self.counts.get_mut(&key) {
+            Some(remaining) if *remaining > 0 => {
+                *remaining -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Computes the key identifying `diagnostic` in a baseline, or returns `None` if
+/// it can't be part of one: only lint diagnostics pointing at source code are,
+/// except for those of lints the user asked for with `--force-warn`.
+fn baseline_key(diagnostic: &Diagnostic, emitter: &dyn Emitter) -> Option<BaselineKey> {
+    let Some(DiagnosticId::Lint { name, .. }) = &diagnostic.code else { return None };
+    if diagnostic.is_force_warn() {
+        return None;
+    }
+    let sm = emitter.source_map()?;
+    let span = diagnostic.span.primary_span()?.source_callsite();
+    if span.is_dummy() {
+        return None;
+    }
+
+    let args = emitter.to_fluent_args(diagnostic.args());
+    let message = emitter.translate_messages(&diagnostic.message, &args);
+    let snippet = sm.span_to_snippet(span).unwrap_or_default();
+    let mut hasher = StableHasher::new();
+    name.hash(&mut hasher);
+    normalize_whitespace(&message).hash(&mut hasher);
+    normalize_whitespace(&snippet).hash(&mut hasher);
+    let fingerprint: u64 = hasher.finish();
+
+    Some(BaselineKey {
+        lint: name.clone(),
+        file: sm.lookup_source_file(span.lo()).name.prefer_local().to_string(),
+        fingerprint: format!("{fingerprint:016x}"),
+    })
+}
+
+/// Collapses all whitespace to single spaces, so that reindenting code doesn't
+/// change fingerprints.
+fn normalize_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -23,6 +23,7 @@
 use rustc_lint_defs::LintExpectationId;
 use Level::*;
 
+use baseline::DiagnosticBaseline;
 use emitter::{is_case_difference, Emitter, EmitterWriter};
 use registry::Registry;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap, FxIndexSet};
@@ -49,12 +50,15 @@
 use termcolor::{Color, ColorSpec};
 
 pub mod annotate_snippet_emitter_writer;
+pub mod apply_suggestions;
+pub mod baseline;
 mod diagnostic;
 mod diagnostic_builder;
 pub mod emitter;
 pub mod json;
 mod lock;
 pub mod registry;
+pub mod sarif;
 mod snippet;
 mod styled_buffer;
 pub mod translation;
@@ -430,6 +434,14 @@ struct HandlerInner {
 
     future_breakage_diagnostics: Vec<Diagnostic>,
 
+    /// Machine-applicable suggestions of the emitted diagnostics, only collected
+    /// if [`HandlerFlags::collect_machine_applicable_suggestions`] is set.
+    machine_applicable_suggestions: Vec<CodeSuggestion>,
+
+    /// The baseline that lint diagnostics are suppressed by or recorded into.
+    /// (rustc: see `-Z diagnostic-baseline`)
+    diagnostic_baseline: Option<DiagnosticBaseline>,
+
     /// The [`Self::unstable_expect_diagnostics`] should be empty when this struct is
     /// dropped. However, it can have values if the compilation is stopped early
     /// or is only partially executed. To avoid ICEs, like in rust#94953 we only
@@ -462,10 +474,14 @@ pub enum StashKey {
     EarlySyntaxWarning,
 }
 
-fn default_track_diagnostic(_: &Diagnostic) {}
+fn default_track_diagnostic(_: &Diagnostic) -> bool {
+    true
+}
 
-pub static TRACK_DIAGNOSTICS: AtomicRef<fn(&Diagnostic)> =
-    AtomicRef::new(&(default_track_diagnostic as fn(&_)));
+/// Called for every diagnostic before it is emitted. Returns `false` if the
+/// diagnostic was already emitted and must not be emitted again.
+pub static TRACK_DIAGNOSTICS: AtomicRef<fn(&Diagnostic) -> bool> =
+    AtomicRef::new(&(default_track_diagnostic as fn(&_) -> _));
 
 #[derive(Copy, Clone, Default)]
 pub struct HandlerFlags {
@@ -486,6 +502,10 @@ pub struct HandlerFlags {
     pub macro_backtrace: bool,
     /// If true, identical diagnostics are reported only once.
     pub deduplicate_diagnostics: bool,
+    /// If true, the machine-applicable suggestions of emitted diagnostics are
+    /// kept around so that they can be applied once compilation finished.
+    /// (rustc: see `-Z apply-suggestions`)
+    pub collect_machine_applicable_suggestions: bool,
 }
 
 impl Drop for HandlerInner {
@@ -590,6 +610,8 @@ pub fn with_emitter_and_flags(
                 emitted_diagnostics: Default::default(),
                 stashed_diagnostics: Default::default(),
                 future_breakage_diagnostics: Vec::new(),
+                machine_applicable_suggestions: Vec::new(),
+                diagnostic_baseline: None,
                 check_unstable_expect_diagnostics: false,
                 unstable_expect_diagnostics: Vec::new(),
                 fulfilled_expectations: Default::default(),
@@ -1008,6 +1030,20 @@ pub fn take_future_breakage_diagnostics(&self) -> Vec<Diagnostic> {
         std::mem::take(&mut self.inner.borrow_mut().future_breakage_diagnostics)
     }
 
+    pub fn take_machine_applicable_suggestions(&self) -> Vec<CodeSuggestion> {
+        std::mem::take(&mut self.inner.borrow_mut().machine_applicable_suggestions)
+    }
+
+    /// Makes this handler suppress the lint diagnostics covered by `baseline`, or
+    /// record all lint diagnostics into it if it is recording.
+    pub fn set_diagnostic_baseline(&self, baseline: DiagnosticBaseline) {
+        self.inner.borrow_mut().diagnostic_baseline = Some(baseline);
+    }
+
+    pub fn take_diagnostic_baseline(&self) -> Option<DiagnosticBaseline> {
+        self.inner.borrow_mut().diagnostic_baseline.take()
+    }
+
     pub fn abort_if_errors(&self) {
         self.inner.borrow_mut().abort_if_errors()
     }
@@ -1218,12 +1254,22 @@ fn emit_diagnostic(&mut self, diagnostic: &mut Diagnostic) -> Option<ErrorGuaran
             return None;
         }
 
-        (*TRACK_DIAGNOSTICS)(diagnostic);
+        if !(*TRACK_DIAGNOSTICS)(diagnostic) {
+            // It was emitted, and counted, when it was replayed from the
+            // incremental cache.
+            return diagnostic.is_error().then(ErrorGuaranteed::unchecked_claim_error_was_emitted);
+        }
 
         if matches!(diagnostic.level, Level::Expect(_) | Level::Allow) {
             return None;
         }
 
+        if let Some(baseline) = &mut self.diagnostic_baseline
+            && baseline.check(diagnostic, &*self.emitter)
+        {
+            return None;
+        }
+
         if let Some(ref code) = diagnostic.code {
             self.emitted_diagnostic_codes.insert(code.clone());
         }
@@ -1255,6 +1301,16 @@ fn emit_diagnostic(&mut self, diagnostic: &mut Diagnostic) -> Option<ErrorGuaran
             diagnostic.children.drain_filter(already_emitted_sub).for_each(|_| {});
 
             self.emitter.emit_diagnostic(&diagnostic);
+            if self.flags.collect_machine_applicable_suggestions
+                && let Ok(suggestions) = &diagnostic.suggestions
+            {
+                self.machine_applicable_suggestions.extend(
+                    suggestions
+                        .iter()
+                        .filter(|s| s.applicability == Applicability::MachineApplicable)
+                        .cloned(),
+                );
+            }
             if diagnostic.is_error() {
                 self.deduplicated_err_count += 1;
             } else if let Warning(_) = diagnostic.level {
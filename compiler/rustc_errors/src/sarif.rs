@@ -0,0 +1,503 @@
+//! A SARIF emitter for errors.
+//!
+//! [SARIF] (Static Analysis Results Interchange Format) is the format consumed
+//! by most code-scanning dashboards. Unlike the JSON emitter, which prints one
+//! object per diagnostic, a SARIF log is a single document, so this emitter
+//! buffers every diagnostic and writes the whole log when it is dropped.
+//!
+//! Each distinct `DiagnosticId` becomes a rule of the `rustc` tool driver:
+//! error codes get their description from the error code registry, and lints
+//! (including tool lints such as clippy's) are identified by their name and
+//! link to their documentation, as the lint store isn't available to emitters.
+//! Spans are mapped to regions and suggestions to `fixes`.
+//!
+//! The output targets SARIF 2.1.0 and, like the JSON output, should be
+//! considered *unstable*.
+//!
+//! [SARIF]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+
+use rustc_span::source_map::SourceMap;
+
+use crate::emitter::Emitter;
+use crate::registry::Registry;
+use crate::translation::Translate;
+use crate::{CodeSuggestion, DiagnosticId, FluentBundle, LazyFallbackBundle, Level, SubDiagnostic};
+use rustc_lint_defs::Applicability;
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::sync::Lrc;
+use rustc_error_messages::FluentArgs;
+use rustc_span::Span;
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+#[cfg(test)]
+mod tests;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+pub struct SarifEmitter {
+    dst: Box<dyn Write + Send>,
+    registry: Option<Registry>,
+    sm: Lrc<SourceMap>,
+    fluent_bundle: Option<Lrc<FluentBundle>>,
+    fallback_bundle: LazyFallbackBundle,
+    pretty: bool,
+    /// Rules referenced by `results`, in order of first appearance.
+    rules: Vec<ReportingDescriptor>,
+    rule_indices: FxHashMap<String, usize>,
+    results: Vec<SarifResult>,
+    has_errors: bool,
+}
+
+impl SarifEmitter {
+    pub fn stderr(
+        registry: Option<Registry>,
+        source_map: Lrc<SourceMap>,
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+        pretty: bool,
+    ) -> SarifEmitter {
+        SarifEmitter::new(
+            Box::new(io::BufWriter::new(io::stderr())),
+            registry,
+            source_map,
+            fluent_bundle,
+            fallback_bundle,
+            pretty,
+        )
+    }
+
+    pub fn new(
+        dst: Box<dyn Write + Send>,
+        registry: Option<Registry>,
+        source_map: Lrc<SourceMap>,
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+        pretty: bool,
+    ) -> SarifEmitter {
+        SarifEmitter {
+            dst,
+            registry,
+            sm: source_map,
+            fluent_bundle,
+            fallback_bundle,
+            pretty,
+            rules: Vec::new(),
+            rule_indices: FxHashMap::default(),
+            results: Vec::new(),
+            has_errors: false,
+        }
+    }
+
+    /// Returns the index of the rule describing `code`, registering it first
+    /// if this is the first diagnostic with that code.
+    fn rule_index(&mut self, code: &DiagnosticId) -> usize {
+        let id = match code {
+            DiagnosticId::Error(code) => code,
+            DiagnosticId::Lint { name, .. } => name,
+        };
+        if let Some(&index) = self.rule_indices.get(id) {
+            return index;
+        }
+
+        let rule = match code {
+            DiagnosticId::Error(code) => {
+                let explanation = self
+                    .registry
+                    .as_ref()
+                    .and_then(|registry| registry.try_find_description(code).ok())
+                    .flatten();
+                ReportingDescriptor {
+                    id: code.clone(),
+                    short_description: explanation
+                        .and_then(|text| text.lines().find(|line| !line.trim().is_empty()))
+                        .map(|line| Message::text(line.trim())),
+                    full_description: explanation
+                        .map(|text| Message { text: text.to_owned(), markdown: Some(text) }),
+                    help_uri: Some(format!("https://doc.rust-lang.org/error-index.html#{code}")),
+                }
+            }
+            DiagnosticId::Lint { name, .. } => {
+                let help_uri = match name.split_once("::") {
+                    Some(("clippy", lint)) => Some(format!(
+                        "https://rust-lang.github.io/rust-clippy/master/index.html#{lint}"
+                    )),
+                    Some(_) => None,
+                    None => Some(format!(
+                        "https://doc.rust-lang.org/rustc/lints/listing/index.html#{}",
+                        name.replace('_', "-")
+                    )),
+                };
+                ReportingDescriptor {
+                    id: name.clone(),
+                    short_description: None,
+                    full_description: None,
+                    help_uri,
+                }
+            }
+        };
+
+        let index = self.rules.len();
+        self.rules.push(rule);
+        self.rule_indices.insert(id.clone(), index);
+        index
+    }
+
+    /// Serializes every buffered result as a single SARIF log.
+    fn write_log(&mut self) -> io::Result<()> {
+        let log = SarifLog {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: [Run {
+                tool: Tool {
+                    driver: ToolComponent {
+                        name: "rustc",
+                        information_uri: "https://www.rust-lang.org/",
+                        rules: &self.rules,
+                    },
+                },
+                invocations: [Invocation { execution_successful: !self.has_errors }],
+                column_kind: "unicodeCodePoints",
+                results: &self.results,
+            }],
+        };
+        if self.pretty {
+            writeln!(&mut self.dst, "{}", serde_json::to_string_pretty(&log).unwrap())
+        } else {
+            writeln!(&mut self.dst, "{}", serde_json::to_string(&log).unwrap())
+        }
+        .and_then(|_| self.dst.flush())
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        if let Err(e) = self.write_log() {
+            // Don't turn an unwind (e.g. from a fatal error) into an abort.
+            if !std::thread::panicking() {
+                panic!("failed to print diagnostics: {:?}", e);
+            }
+        }
+    }
+}
+
+impl Translate for SarifEmitter {
+    fn fluent_bundle(&self) -> Option<&Lrc<FluentBundle>> {
+        self.fluent_bundle.as_ref()
+    }
+
+    fn fallback_fluent_bundle(&self) -> &FluentBundle {
+        &**self.fallback_bundle
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_diagnostic(&mut self, diag: &crate::Diagnostic) {
+        let Some(level) = sarif_level(diag.level) else { return };
+        self.has_errors |= diag.is_error();
+
+        let args = self.to_fluent_args(diag.args());
+        let mut text = self.translate_messages(&diag.message, &args).into_owned();
+        let mut related_locations = Vec::new();
+        for child in &diag.children {
+            let message = self.translate_messages(&child.message, &args);
+            let span = child.render_span.as_ref().unwrap_or(&child.span).primary_span();
+            match span.and_then(|span| self.physical_location(span)) {
+                Some(physical_location) => related_locations.push(Location {
+                    physical_location,
+                    message: Some(Message::text(format!("{}: {message}", child_level(child)))),
+                }),
+                None => {
+                    text.push('\n');
+                    text.push_str(child_level(child));
+                    text.push_str(": ");
+                    text.push_str(&message);
+                }
+            }
+        }
+
+        let locations = diag
+            .span
+            .span_labels()
+            .into_iter()
+            .filter(|label| label.is_primary)
+            .filter_map(|label| {
+                let physical_location = self.physical_location(label.span)?;
+                let message = label
+                    .label
+                    .as_ref()
+                    .map(|m| Message::text(self.translate_message(m, &args).into_owned()));
+                Some(Location { physical_location, message })
+            })
+            .collect();
+
+        let fixes = diag
+            .suggestions
+            .iter()
+            .flatten()
+            .flat_map(|sugg| self.fixes_from_suggestion(sugg, &args))
+            .collect();
+
+        let (rule_id, rule_index) = match &diag.code {
+            Some(code) => {
+                let index = self.rule_index(code);
+                (Some(self.rules[index].id.clone()), Some(index))
+            }
+            None => (None, None),
+        };
+
+        self.results.push(SarifResult {
+            rule_id,
+            rule_index,
+            level,
+            message: Message::text(text),
+            locations,
+            related_locations,
+            fixes,
+        });
+    }
+
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        Some(&self.sm)
+    }
+
+    fn should_show_explain(&self) -> bool {
+        false
+    }
+}
+
+impl SarifEmitter {
+    fn physical_location(&self, span: Span) -> Option<PhysicalLocation> {
+        if span.is_dummy() {
+            return None;
+        }
+        let start = self.sm.lookup_char_pos(span.lo());
+        // SARIF URIs always use forward slashes.
+        let uri = self.sm.filename_for_diagnostics(&start.file.name).to_string().replace('\\', "/");
+        Some(PhysicalLocation {
+            artifact_location: ArtifactLocation { uri },
+            region: self.region(span),
+        })
+    }
+
+    fn region(&self, span: Span) -> Region {
+        let start = self.sm.lookup_char_pos(span.lo());
+        let end = self.sm.lookup_char_pos(span.hi());
+        let byte_offset = start.file.original_relative_byte_pos(span.lo()).0;
+        let byte_end = start.file.original_relative_byte_pos(span.hi()).0;
+        Region {
+            start_line: start.line,
+            start_column: start.col.0 + 1,
+            end_line: end.line,
+            end_column: end.col.0 + 1,
+            byte_offset,
+            byte_length: byte_end - byte_offset,
+        }
+    }
+
+    /// Each substitution of a suggestion is an alternative way of fixing the
+    /// problem, so each of them becomes its own SARIF fix.
+    fn fixes_from_suggestion(&self, sugg: &CodeSuggestion, args: &FluentArgs<'_>) -> Vec<Fix> {
+        let description = self.translate_message(&sugg.msg, args).into_owned();
+        sugg.substitutions
+            .iter()
+            .filter_map(|substitution| {
+                let mut artifact_changes: Vec<ArtifactChange> = Vec::new();
+                for part in &substitution.parts {
+                    let location = self.physical_location(part.span)?;
+                    let replacement = Replacement {
+                        deleted_region: location.region,
+                        inserted_content: ArtifactContent { text: part.snippet.clone() },
+                    };
+                    match artifact_changes
+                        .iter_mut()
+                        .find(|change| change.artifact_location == location.artifact_location)
+                    {
+                        Some(change) => change.replacements.push(replacement),
+                        None => artifact_changes.push(ArtifactChange {
+                            artifact_location: location.artifact_location,
+                            replacements: vec![replacement],
+                        }),
+                    }
+                }
+                Some(Fix {
+                    description: Message::text(description.clone()),
+                    artifact_changes,
+                    properties: FixProperties { applicability: sugg.applicability },
+                })
+            })
+            .collect()
+    }
+}
+
+/// Maps a diagnostic level to a SARIF result level, or `None` if the
+/// diagnostic should not be reported at all.
+fn sarif_level(level: Level) -> Option<&'static str> {
+    match level {
+        Level::Bug | Level::DelayedBug | Level::Fatal | Level::Error { .. } => Some("error"),
+        Level::Warning(_) => Some("warning"),
+        Level::Note | Level::OnceNote | Level::Help => Some("note"),
+        Level::FailureNote | Level::Allow | Level::Expect(_) => None,
+    }
+}
+
+fn child_level(child: &SubDiagnostic) -> &'static str {
+    match child.level {
+        Level::Allow | Level::Expect(_) => "note",
+        level => level.to_str(),
+    }
+}
+
+// The following data types are provided just for serialisation. Field names
+// follow the SARIF 2.1.0 schema.
+
+#[derive(Serialize)]
+struct SarifLog<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: [Run<'a>; 1],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run<'a> {
+    tool: Tool<'a>,
+    invocations: [Invocation; 1],
+    /// Columns are computed by `SourceMap::lookup_char_pos`, which counts chars.
+    column_kind: &'static str,
+    results: &'a [SarifResult],
+}
+
+#[derive(Serialize)]
+struct Tool<'a> {
+    driver: ToolComponent<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolComponent<'a> {
+    name: &'static str,
+    information_uri: &'static str,
+    rules: &'a [ReportingDescriptor],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Invocation {
+    execution_successful: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptor {
+    /// The error code (`E0308`) or lint name (`unused_variables`).
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    short_description: Option<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    full_description: Option<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_uri: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    /// "error", "warning" or "note".
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<Fix>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    markdown: Option<&'static str>,
+}
+
+impl Message {
+    fn text(text: impl Into<String>) -> Message {
+        Message { text: text.into(), markdown: None }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize, PartialEq, Eq)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    /// 1-based.
+    start_line: usize,
+    /// 1-based, character offset.
+    start_column: usize,
+    end_line: usize,
+    /// 1-based, exclusive.
+    end_column: usize,
+    byte_offset: u32,
+    byte_length: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Fix {
+    description: Message,
+    artifact_changes: Vec<ArtifactChange>,
+    properties: FixProperties,
+}
+
+/// SARIF has no notion of applicability, so it is recorded in the fix's
+/// property bag for tools that only want to apply machine-applicable fixes.
+#[derive(Serialize)]
+struct FixProperties {
+    applicability: Applicability,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactChange {
+    artifact_location: ArtifactLocation,
+    replacements: Vec<Replacement>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Replacement {
+    deleted_region: Region,
+    inserted_content: ArtifactContent,
+}
+
+#[derive(Serialize)]
+struct ArtifactContent {
+    text: String,
+}
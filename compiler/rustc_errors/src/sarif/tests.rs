@@ -0,0 +1,112 @@
+use super::*;
+
+use crate::Handler;
+use rustc_span::source_map::FilePathMapping;
+use rustc_span::{BytePos, Span};
+
+use std::path::Path;
+use std::str;
+use std::sync::{Arc, Mutex};
+
+struct Shared<T> {
+    data: Arc<Mutex<T>>,
+}
+
+impl<T: Write> Write for Shared<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.data.lock().unwrap().flush()
+    }
+}
+
+/// Emits the diagnostics built by `f` through a `SarifEmitter` and returns the
+/// parsed log.
+fn emit_sarif(code: &str, f: impl FnOnce(&Handler)) -> serde_json::Value {
+    rustc_span::create_default_session_globals_then(|| {
+        let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+        sm.new_source_file(Path::new("test.rs").to_owned().into(), code.to_owned());
+        let fallback_bundle =
+            crate::fallback_fluent_bundle(rustc_error_messages::DEFAULT_LOCALE_RESOURCES, false);
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let se = SarifEmitter::new(
+            Box::new(Shared { data: output.clone() }),
+            Some(Registry::new(&[("E0999", Some("A test error.\n\nMore details.\n"))])),
+            sm,
+            None,
+            fallback_bundle,
+            false,
+        );
+
+        let handler = Handler::with_emitter(true, None, Box::new(se));
+        f(&handler);
+        // The log is only written once the emitter goes away.
+        drop(handler);
+
+        let bytes = output.lock().unwrap();
+        serde_json::from_str(str::from_utf8(&bytes).unwrap()).unwrap()
+    })
+}
+
+#[test]
+fn region_and_rule() {
+    let log = emit_sarif("fn main() {\n    let x = 1;\n}\n", |handler| {
+        let span = Span::with_root_ctxt(BytePos(20), BytePos(21));
+        handler
+            .struct_span_err_with_code(span, "foo", DiagnosticId::Error("E0999".to_owned()))
+            .emit();
+    });
+
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["invocations"][0]["executionSuccessful"], false);
+
+    let rule = &run["tool"]["driver"]["rules"][0];
+    assert_eq!(rule["id"], "E0999");
+    assert_eq!(rule["shortDescription"]["text"], "A test error.");
+
+    let result = &run["results"][0];
+    assert_eq!(result["ruleId"], "E0999");
+    assert_eq!(result["ruleIndex"], 0);
+    assert_eq!(result["level"], "error");
+    assert_eq!(result["message"]["text"], "foo");
+
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "test.rs");
+    let region = &location["region"];
+    assert_eq!(region["startLine"], 2);
+    assert_eq!(region["startColumn"], 9);
+    assert_eq!(region["endLine"], 2);
+    assert_eq!(region["endColumn"], 10);
+    assert_eq!(region["byteOffset"], 20);
+    assert_eq!(region["byteLength"], 1);
+}
+
+#[test]
+fn suggestion_becomes_fix() {
+    let log = emit_sarif("fn main() { let x = 1; }\n", |handler| {
+        let span = Span::with_root_ctxt(BytePos(16), BytePos(17));
+        handler
+            .struct_span_warn(span, "unused variable: `x`")
+            .span_suggestion(span, "prefix it", "_x", Applicability::MachineApplicable)
+            .emit();
+    });
+
+    let run = &log["runs"][0];
+    assert_eq!(run["invocations"][0]["executionSuccessful"], true);
+
+    let result = &run["results"][0];
+    assert_eq!(result["level"], "warning");
+    let fix = &result["fixes"][0];
+    assert_eq!(fix["description"]["text"], "prefix it");
+    assert_eq!(fix["properties"]["applicability"], "MachineApplicable");
+    let change = &fix["artifactChanges"][0];
+    assert_eq!(change["artifactLocation"]["uri"], "test.rs");
+    let replacement = &change["replacements"][0];
+    assert_eq!(replacement["insertedContent"]["text"], "_x");
+    assert_eq!(replacement["deletedRegion"]["byteOffset"], 16);
+    assert_eq!(replacement["deletedRegion"]["byteLength"], 1);
+}
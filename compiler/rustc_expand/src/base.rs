@@ -1,5 +1,7 @@
 use crate::expand::{self, AstFragment, Invocation};
+use crate::expansion_dump::MacroExpansionDump;
 use crate::module::DirOwnership;
+use crate::proc_macro_cache::ProcMacroCache;
 
 use rustc_ast::attr::MarkedAttrs;
 use rustc_ast::ptr::P;
@@ -1014,6 +1016,10 @@ pub struct ExtCtxt<'a> {
     /// in the AST, but insert it here so that we know
     /// not to expand it again.
     pub(super) expanded_inert_attrs: MarkedAttrs,
+    /// The expansions recorded for `-Z dump-macro-expansions`.
+    pub(crate) expansion_dump: Option<MacroExpansionDump>,
+    /// The derive outputs cached for `-Z cache-proc-macros`.
+    pub(crate) proc_macro_cache: Option<ProcMacroCache>,
 }
 
 impl<'a> ExtCtxt<'a> {
@@ -1043,6 +1049,17 @@ pub fn new(
             expansions: FxIndexMap::default(),
             expanded_inert_attrs: MarkedAttrs::new(),
             buffered_early_lint: vec![],
+            expansion_dump: sess
+                .opts
+                .unstable_opts
+                .dump_macro_expansions
+                .is_some()
+                .then(MacroExpansionDump::default),
+            proc_macro_cache: if sess.opts.unstable_opts.cache_proc_macros {
+                ProcMacroCache::load(sess)
+            } else {
+                None
+            },
         }
     }
 
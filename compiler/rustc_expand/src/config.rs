@@ -16,6 +16,7 @@
     ACCEPTED_FEATURES, ACTIVE_FEATURES, REMOVED_FEATURES, STABLE_REMOVED_FEATURES,
 };
 use rustc_parse::validate_attr;
+use rustc_session::cfg_eval::CfgEvalKind;
 use rustc_session::parse::feature_err;
 use rustc_session::Session;
 use rustc_span::edition::{Edition, ALL_EDITIONS};
@@ -366,6 +367,7 @@ pub(crate) fn expand_cfg_attr(&self, attr: Attribute, recursive: bool) -> Vec<At
             &self.sess.parse_sess,
             self.lint_node_id,
             self.features,
+            CfgEvalKind::CfgAttr,
         ) {
             return vec![];
         }
@@ -464,7 +466,13 @@ pub(crate) fn cfg_true(&self, attr: &Attribute) -> bool {
             }
         };
         parse_cfg(&meta_item, &self.sess).map_or(true, |meta_item| {
-            attr::cfg_matches(&meta_item, &self.sess.parse_sess, self.lint_node_id, self.features)
+            attr::cfg_matches(
+                &meta_item,
+                &self.sess.parse_sess,
+                self.lint_node_id,
+                self.features,
+                CfgEvalKind::Cfg,
+            )
         })
     }
 
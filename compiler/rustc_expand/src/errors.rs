@@ -1,6 +1,8 @@
 use rustc_macros::Diagnostic;
 use rustc_span::symbol::MacroRulesNormalizedIdent;
 use rustc_span::Span;
+use std::io;
+use std::path::Path;
 
 #[derive(Diagnostic)]
 #[diag(expand::expr_repeat_no_syntax_vars)]
@@ -46,3 +48,48 @@ pub(crate) struct MetaVarsDifSeqMatchers {
     pub span: Span,
     pub msg: String,
 }
+
+#[derive(Diagnostic)]
+#[diag(expand::failed_to_write_macro_expansions)]
+pub(crate) struct FailedToWriteMacroExpansions<'a> {
+    pub path: &'a Path,
+    pub error: io::Error,
+}
+
+#[derive(Diagnostic)]
+#[diag(expand::failed_to_save_proc_macro_cache)]
+pub(crate) struct FailedToSaveProcMacroCache<'a> {
+    pub path: &'a Path,
+    pub error: io::Error,
+}
+
+#[derive(Diagnostic)]
+#[diag(expand::proc_macro_time_limit)]
+#[note]
+pub(crate) struct ProcMacroTimeLimit {
+    #[primary_span]
+    pub span: Span,
+    pub limit: u64,
+}
+
+#[derive(Diagnostic)]
+#[diag(expand::proc_macro_memory_limit)]
+#[note]
+pub(crate) struct ProcMacroMemoryLimit {
+    #[primary_span]
+    pub span: Span,
+    pub limit: u64,
+}
+
+#[derive(Diagnostic)]
+#[diag(expand::proc_macro_out_of_process_unsupported)]
+#[note]
+pub(crate) struct ProcMacroOutOfProcessUnsupported;
+
+#[derive(Diagnostic)]
+#[diag(expand::proc_macro_process_failed)]
+pub(crate) struct ProcMacroProcessFailed {
+    #[primary_span]
+    pub span: Span,
+    pub error: String,
+}
@@ -1,5 +1,6 @@
 use crate::base::*;
 use crate::config::StripUnconfigured;
+use crate::errors::FailedToWriteMacroExpansions;
 use crate::hygiene::SyntaxContext;
 use crate::mbe::macro_rules::annotate_err_with_kind;
 use crate::module::{mod_dir_path, parse_external_mod, DirOwnership, ParsedExternalMod};
@@ -379,6 +380,15 @@ pub fn expand_crate(&mut self, krate: ast::Crate) -> ast::Crate {
         let krate = self.fully_expand_fragment(AstFragment::Crate(krate)).make_crate();
         assert_eq!(krate.id, ast::CRATE_NODE_ID);
         self.cx.trace_macros_diag();
+        if let Some(dump) = &self.cx.expansion_dump
+            && let Some(path) = &self.cx.sess.opts.unstable_opts.dump_macro_expansions
+            && let Err(error) = dump.write(path)
+        {
+            self.cx.sess.emit_err(FailedToWriteMacroExpansions { path, error });
+        }
+        if let Some(cache) = &self.cx.proc_macro_cache {
+            cache.save(self.cx.sess);
+        }
         krate
     }
 
@@ -632,12 +642,19 @@ fn expand_invoc(
         }
 
         let (fragment_kind, span) = (invoc.fragment_kind, invoc.span());
-        ExpandResult::Ready(match invoc.kind {
+        let expn_id = invoc.expansion_data.id;
+        if let Some(dump) = &mut self.cx.expansion_dump {
+            dump.record_invocation(&invoc, self.cx.sess.source_map());
+        }
+        let fragment = match invoc.kind {
             InvocationKind::Bang { mac, .. } => match ext {
                 SyntaxExtensionKind::Bang(expander) => {
                     let Ok(tok_result) = expander.expand(self.cx, span, mac.args.inner_tokens()) else {
                         return ExpandResult::Ready(fragment_kind.dummy(span));
                     };
+                    if let Some(dump) = &mut self.cx.expansion_dump {
+                        dump.record_output_tokens(expn_id, &tok_result);
+                    }
                     self.parse_ast_fragment(tok_result, fragment_kind, &mac.path, span)
                 }
                 SyntaxExtensionKind::LegacyBang(expander) => {
@@ -694,6 +711,9 @@ fn expand_invoc(
                     let Ok(tok_result) = expander.expand(self.cx, span, inner_tokens, tokens) else {
                         return ExpandResult::Ready(fragment_kind.dummy(span));
                     };
+                    if let Some(dump) = &mut self.cx.expansion_dump {
+                        dump.record_output_tokens(expn_id, &tok_result);
+                    }
                     self.parse_ast_fragment(tok_result, fragment_kind, &attr_item.path, span)
                 }
                 SyntaxExtensionKind::LegacyAttr(expander) => {
@@ -752,7 +772,12 @@ fn expand_invoc(
                 }
                 _ => unreachable!(),
             },
-        })
+        };
+
+        if let Some(dump) = &mut self.cx.expansion_dump {
+            dump.record_output_fragment(expn_id, &fragment);
+        }
+        ExpandResult::Ready(fragment)
     }
 
     fn gate_proc_macro_attr_item(&self, span: Span, item: &Annotatable) {
@@ -0,0 +1,190 @@
+//! Recording of macro expansions for `-Z dump-macro-expansions`.
+//!
+//! Every invocation expanded by the `MacroExpander` is recorded with its input,
+//! its output and, for `macro_rules!` macros, the index of the arm that matched.
+//! Invocations are written as a tree following their `ExpnId`s: the invocations
+//! found in the output of an expansion are nested in it.
+
+use crate::base::Annotatable;
+use crate::expand::{AstFragment, Invocation, InvocationKind};
+
+use rustc_ast::tokenstream::TokenStream;
+use rustc_ast_pretty::pprust;
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+use rustc_span::hygiene::LocalExpnId;
+use rustc_span::source_map::SourceMap;
+use serde::Serialize;
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+struct InvocationDump {
+    parent: LocalExpnId,
+    kind: &'static str,
+    path: String,
+    call_site: String,
+    /// The arguments of an attribute macro, e.g. `foo` in `#[attr(foo)]`.
+    args: Option<String>,
+    input: String,
+    /// The index of the arm of a `macro_rules!` macro that matched the input.
+    arm: Option<usize>,
+    output: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ExpansionNode<'a> {
+    id: u32,
+    kind: &'static str,
+    #[serde(rename = "macro")]
+    path: &'a str,
+    call_site: &'a str,
+    args: Option<&'a str>,
+    input: &'a str,
+    arm: Option<usize>,
+    output: Option<&'a str>,
+    expansions: Vec<ExpansionNode<'a>>,
+}
+
+#[derive(Default)]
+pub(crate) struct MacroExpansionDump {
+    invocations: FxIndexMap<LocalExpnId, InvocationDump>,
+}
+
+impl MacroExpansionDump {
+    /// Records `invoc` before it is expanded. Invocations that are expanded
+    /// again after being retried are only recorded once.
+    pub(crate) fn record_invocation(&mut self, invoc: &Invocation, source_map: &SourceMap) {
+        let id = invoc.expansion_data.id;
+        if self.invocations.contains_key(&id) {
+            return;
+        }
+
+        let (kind, path, args, input) = match &invoc.kind {
+            InvocationKind::Bang { mac, .. } => (
+                "bang",
+                pprust::path_to_string(&mac.path),
+                None,
+                pprust::tts_to_string(&mac.args.inner_tokens()),
+            ),
+            InvocationKind::Attr { attr, item, .. } => {
+                let attr_item = attr.get_normal_item();
+                (
+                    "attr",
+                    pprust::path_to_string(&attr_item.path),
+                    Some(pprust::tts_to_string(&attr_item.args.inner_tokens())),
+                    annotatable_to_string(item),
+                )
+            }
+            InvocationKind::Derive { path, item } => {
+                ("derive", pprust::path_to_string(path), None, annotatable_to_string(item))
+            }
+        };
+        self.invocations.insert(
+            id,
+            InvocationDump {
+                parent: id.parent(),
+                kind,
+                path,
+                call_site: source_map.span_to_diagnostic_string(invoc.span()),
+                args,
+                input,
+                arm: None,
+                output: None,
+            },
+        );
+    }
+
+    /// Records the index of the `macro_rules!` arm that matched the input of `id`.
+    pub(crate) fn record_arm(&mut self, id: LocalExpnId, arm: usize) {
+        if let Some(invocation) = self.invocations.get_mut(&id) {
+            invocation.arm = Some(arm);
+        }
+    }
+
+    /// Records the tokens produced by the expansion `id`.
+    pub(crate) fn record_output_tokens(&mut self, id: LocalExpnId, tokens: &TokenStream) {
+        if let Some(invocation) = self.invocations.get_mut(&id) {
+            invocation.output = Some(pprust::tts_to_string(tokens));
+        }
+    }
+
+    /// Records the output of the expansion `id` from the AST it produced, unless
+    /// the tokens it produced were already recorded. This is the case for
+    /// built-in macros, which produce AST directly.
+    pub(crate) fn record_output_fragment(&mut self, id: LocalExpnId, fragment: &AstFragment) {
+        if let Some(invocation) = self.invocations.get_mut(&id) && invocation.output.is_none() {
+            invocation.output = fragment_to_string(fragment);
+        }
+    }
+
+    /// Writes the recorded expansions to `path` as JSON.
+    pub(crate) fn write(&self, path: &Path) -> io::Result<()> {
+        let mut children: FxHashMap<LocalExpnId, Vec<LocalExpnId>> = FxHashMap::default();
+        let mut roots = Vec::new();
+        for (&id, invocation) in &self.invocations {
+            // Invocations written in the source code have the root expansion,
+            // which isn't recorded, as their parent.
+            if self.invocations.contains_key(&invocation.parent) {
+                children.entry(invocation.parent).or_default().push(id);
+            } else {
+                roots.push(id);
+            }
+        }
+
+        fn node<'a>(
+            dump: &'a MacroExpansionDump,
+            children: &FxHashMap<LocalExpnId, Vec<LocalExpnId>>,
+            id: LocalExpnId,
+        ) -> ExpansionNode<'a> {
+            let invocation = &dump.invocations[&id];
+            ExpansionNode {
+                id: id.as_u32(),
+                kind: invocation.kind,
+                path: &invocation.path,
+                call_site: &invocation.call_site,
+                args: invocation.args.as_deref(),
+                input: &invocation.input,
+                arm: invocation.arm,
+                output: invocation.output.as_deref(),
+                expansions: children
+                    .get(&id)
+                    .map_or(&[][..], |ids| ids)
+                    .iter()
+                    .map(|&child| node(dump, children, child))
+                    .collect(),
+            }
+        }
+
+        let expansions: Vec<_> = roots.into_iter().map(|id| node(self, &children, id)).collect();
+        fs::write(path, serde_json::to_string_pretty(&expansions).unwrap() + "\n")
+    }
+}
+
+fn annotatable_to_string(annotatable: &Annotatable) -> String {
+    match annotatable {
+        Annotatable::Item(item) => pprust::item_to_string(item),
+        Annotatable::Expr(expr) => pprust::expr_to_string(expr),
+        Annotatable::Crate(krate) => pprust::crate_to_string_for_macros(krate),
+        // Attributes on these only invoke macros in code that was parsed with
+        // its tokens collected.
+        _ => pprust::tts_to_string(&annotatable.to_tokens()),
+    }
+}
+
+/// Prints the AST produced by an expansion, for the kinds of fragments built-in
+/// macros produce.
+fn fragment_to_string(fragment: &AstFragment) -> Option<String> {
+    match fragment {
+        AstFragment::OptExpr(expr) => {
+            Some(expr.as_ref().map_or_else(String::new, |expr| pprust::expr_to_string(expr)))
+        }
+        AstFragment::Expr(expr) => Some(pprust::expr_to_string(expr)),
+        AstFragment::Pat(pat) => Some(pprust::pat_to_string(pat)),
+        AstFragment::Ty(ty) => Some(pprust::ty_to_string(ty)),
+        AstFragment::Items(items) => Some(
+            items.iter().map(|item| pprust::item_to_string(item)).collect::<Vec<_>>().join("\n"),
+        ),
+        _ => None,
+    }
+}
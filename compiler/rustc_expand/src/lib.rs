@@ -19,7 +19,9 @@
 
 extern crate proc_macro as pm;
 
+mod expansion_dump;
 mod placeholders;
+mod proc_macro_cache;
 mod proc_macro_server;
 
 pub use mbe::macro_rules::compile_declarative_macro;
@@ -32,6 +34,7 @@
 pub mod expand;
 pub mod module;
 pub mod proc_macro;
+pub mod proc_macro_process;
 
 pub(crate) mod mbe;
 
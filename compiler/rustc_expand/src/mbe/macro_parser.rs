@@ -76,6 +76,7 @@
 use crate::mbe::{KleeneOp, TokenTree};
 
 use rustc_ast::token::{self, DocComment, Nonterminal, NonterminalKind, Token};
+use rustc_ast_pretty::pprust;
 use rustc_lint_defs::pluralize;
 use rustc_parse::parser::{NtOrTt, Parser};
 use rustc_span::symbol::MacroRulesNormalizedIdent;
@@ -380,6 +381,10 @@ pub struct TtParser {
     /// Pre-allocate an empty match array, so it can be cloned cheaply for macros with many rules
     /// that have no metavars.
     empty_matches: Lrc<Vec<NamedMatch>>,
+
+    /// If failures are explained (`-Z macro-match-explain`), the indices of the matcher locations
+    /// that didn't accept the token last processed by `parse_tt_inner`.
+    expected: Option<Vec<usize>>,
 }
 
 impl TtParser {
@@ -390,9 +395,39 @@ pub(super) fn new(macro_name: Ident) -> TtParser {
             next_mps: vec![],
             bb_mps: vec![],
             empty_matches: Lrc::new(vec![]),
+            expected: None,
         }
     }
 
+    /// Makes the parser keep track of what it expected, for `expected_at_failure`.
+    pub(super) fn explain_failures(&mut self) {
+        self.expected = Some(vec![]);
+    }
+
+    /// Describes what `matcher` expected instead of the token at which the last call to
+    /// `parse_tt` returned `Failure`, e.g. `` `,` `` or `` `$name:ident` ``. Only available if
+    /// `explain_failures` was called.
+    pub(super) fn expected_at_failure(&self, matcher: &[MatcherLoc]) -> Vec<String> {
+        let mut idxs = self.expected.clone().unwrap_or_default();
+        idxs.sort_unstable();
+        idxs.dedup();
+        let mut expected: Vec<_> = idxs
+            .into_iter()
+            .map(|idx| match &matcher[idx] {
+                MatcherLoc::Token { token } | MatcherLoc::SequenceSep { separator: token } => {
+                    format!("`{}`", pprust::token_to_string(token))
+                }
+                MatcherLoc::MetaVarDecl { bind, kind: Some(kind), .. } => {
+                    format!("`${}:{}`", bind, kind)
+                }
+                MatcherLoc::Eof => "the end of the macro input".to_string(),
+                _ => unreachable!(),
+            })
+            .collect();
+        expected.dedup();
+        expected
+    }
+
     /// Process the matcher positions of `cur_mps` until it is empty. In the process, this will
     /// produce more mps in `next_mps` and `bb_mps`.
     ///
@@ -409,6 +444,10 @@ fn parse_tt_inner(
         // modified if `token == Eof`.
         let mut eof_mps = EofMatcherPositions::None;
 
+        if let Some(expected) = &mut self.expected {
+            expected.clear();
+        }
+
         while let Some(mut mp) = self.cur_mps.pop() {
             match &matcher[mp.idx] {
                 MatcherLoc::Token { token: t } => {
@@ -427,6 +466,8 @@ fn parse_tt_inner(
                     } else if token_name_eq(&t, token) {
                         mp.idx += 1;
                         self.next_mps.push(mp);
+                    } else if let Some(expected) = &mut self.expected {
+                        expected.push(mp.idx);
                     }
                 }
                 MatcherLoc::Delimited => {
@@ -488,6 +529,8 @@ fn parse_tt_inner(
                         // The separator matches the current token. Advance past it.
                         mp.idx += 1;
                         self.next_mps.push(mp);
+                    } else if let Some(expected) = &mut self.expected {
+                        expected.push(mp.idx);
                     }
                 }
                 &MatcherLoc::SequenceKleeneOpAfterSep { idx_first } => {
@@ -503,6 +546,8 @@ fn parse_tt_inner(
                     if let Some(kind) = kind {
                         if Parser::nonterminal_may_begin_with(kind, token) {
                             self.bb_mps.push(mp);
+                        } else if let Some(expected) = &mut self.expected {
+                            expected.push(mp.idx);
                         }
                     } else {
                         // E.g. `$e` instead of `$e:expr`, reported as a hard error if actually used.
@@ -520,6 +565,8 @@ fn parse_tt_inner(
                                 EofMatcherPositions::Multiple
                             }
                         }
+                    } else if let Some(expected) = &mut self.expected {
+                        expected.push(mp.idx);
                     }
                 }
             }
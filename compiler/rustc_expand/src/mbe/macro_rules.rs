@@ -14,7 +14,7 @@
 use rustc_ast_pretty::pprust;
 use rustc_attr::{self as attr, TransparencyError};
 use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
-use rustc_errors::{Applicability, Diagnostic, DiagnosticBuilder};
+use rustc_errors::{Applicability, Diagnostic, DiagnosticBuilder, MultiSpan};
 use rustc_feature::Features;
 use rustc_lint_defs::builtin::{
     RUST_2021_INCOMPATIBLE_OR_PATTERNS, SEMICOLON_IN_EXPRESSIONS_FROM_MACROS,
@@ -161,6 +161,7 @@ struct MacroRulesMacroExpander {
     span: Span,
     transparency: Transparency,
     lhses: Vec<Vec<MatcherLoc>>,
+    lhs_spans: Vec<Span>,
     rhses: Vec<mbe::TokenTree>,
     valid: bool,
 }
@@ -184,6 +185,7 @@ fn expand<'cx>(
             self.transparency,
             input,
             &self.lhses,
+            &self.lhs_spans,
             &self.rhses,
         )
     }
@@ -213,6 +215,7 @@ fn expand_macro<'cx>(
     transparency: Transparency,
     arg: TokenStream,
     lhses: &[Vec<MatcherLoc>],
+    lhs_spans: &[Span],
     rhses: &[mbe::TokenTree],
 ) -> Box<dyn MacResult + 'cx> {
     let sess = &cx.sess.parse_sess;
@@ -227,6 +230,9 @@ fn expand_macro<'cx>(
 
     // Which arm's failure should we report? (the one furthest along)
     let mut best_failure: Option<(Token, &str)> = None;
+    // With `-Z macro-match-explain`, the failure of every arm, with what it expected instead.
+    let explain = cx.sess.opts.unstable_opts.macro_match_explain;
+    let mut arm_failures = Vec::new();
 
     // We create a base parser that can be used for the "black box" parts.
     // Every iteration needs a fresh copy of that parser. However, the parser
@@ -251,6 +257,9 @@ fn expand_macro<'cx>(
 
     // Try each arm's matchers.
     let mut tt_parser = TtParser::new(name);
+    if explain {
+        tt_parser.explain_failures();
+    }
     for (i, lhs) in lhses.iter().enumerate() {
         // Take a snapshot of the state of pre-expansion gating at this point.
         // This is used so that if a matcher is not `Success(..)`ful,
@@ -297,6 +306,11 @@ fn expand_macro<'cx>(
                     trace_macros_note(&mut cx.expansions, sp, msg);
                 }
 
+                if let Some(dump) = &mut cx.expansion_dump {
+                    dump.record_arm(cx.current_expansion.id, i);
+                    dump.record_output_tokens(cx.current_expansion.id, &tts);
+                }
+
                 let mut p = Parser::new(sess, tts, false, None);
                 p.last_type_ascription = cx.current_expansion.prior_type_ascription;
 
@@ -320,10 +334,15 @@ fn expand_macro<'cx>(
                     is_local,
                 });
             }
-            Failure(token, msg) => match best_failure {
-                Some((ref best_token, _)) if best_token.span.lo() >= token.span.lo() => {}
-                _ => best_failure = Some((token, msg)),
-            },
+            Failure(token, msg) => {
+                if explain {
+                    arm_failures.push((i, token.clone(), tt_parser.expected_at_failure(lhs)));
+                }
+                match best_failure {
+                    Some((ref best_token, _)) if best_token.span.lo() >= token.span.lo() => {}
+                    _ => best_failure = Some((token, msg)),
+                }
+            }
             Error(err_sp, ref msg) => {
                 let span = err_sp.substitute_dummy(sp);
                 cx.struct_span_err(span, &msg).emit();
@@ -346,6 +365,9 @@ fn expand_macro<'cx>(
         err.span_label(cx.source_map().guess_head_span(def_span), "when calling this macro");
     }
     annotate_doc_comment(&mut err, sess.source_map(), span);
+    for (i, token, expected) in arm_failures {
+        explain_arm_failure(&mut err, i, lhs_spans[i], &token, sp, &expected);
+    }
     // Check whether there's a missing comma in this macro call, like `println!("{}" a);`
     if let Some((arg, comma_span)) = arg.add_comma() {
         for lhs in lhses {
@@ -369,6 +391,30 @@ fn expand_macro<'cx>(
     DummyResult::any(sp)
 }
 
+/// Adds a note to `err` showing where the input stopped matching arm `i` of a macro, whose
+/// matcher is at `lhs_span`, and what the arm `expected` there instead of `token`.
+fn explain_arm_failure(
+    err: &mut Diagnostic,
+    i: usize,
+    lhs_span: Span,
+    token: &Token,
+    call_span: Span,
+    expected: &[String],
+) {
+    let found = match token.kind {
+        token::Eof => "the end of the macro input".to_string(),
+        _ => format!("`{}`", pprust::token_to_string(token)),
+    };
+    let label = match expected {
+        [] => "the rule stopped matching here".to_string(),
+        [expected] => format!("expected {}", expected),
+        [expected @ .., last] => format!("expected {} or {}", expected.join(", "), last),
+    };
+    let mut spans = MultiSpan::from_span(lhs_span);
+    spans.push_span_label(token.span.substitute_dummy(call_span), label);
+    err.span_note(spans, format!("rule #{} stopped matching at {}", i + 1, found));
+}
+
 // Note that macro-by-example's input is also matched against a token tree:
 //                   $( $lhs:tt => $rhs:tt );+
 //
@@ -562,6 +608,8 @@ pub fn compile_declarative_macro(
         Vec::new()
     };
 
+    let lhs_spans = lhses.iter().map(|lhs| lhs.span()).collect();
+
     // Convert the lhses into `MatcherLoc` form, which is better for doing the
     // actual matching. Unless the matcher is invalid.
     let lhses = if valid {
@@ -587,6 +635,7 @@ pub fn compile_declarative_macro(
         node_id: def.id,
         transparency,
         lhses,
+        lhs_spans,
         rhses,
         valid,
     });
@@ -1,15 +1,22 @@
 use crate::base::{self, *};
+use crate::errors;
+use crate::proc_macro_cache::CacheKey;
+use crate::proc_macro_process::{
+    child_entry_installed, OutOfProcess, ProcMacroDylib, ProcessFailure,
+};
 use crate::proc_macro_server;
 
 use rustc_ast as ast;
 use rustc_ast::ptr::P;
 use rustc_ast::token;
 use rustc_ast::tokenstream::TokenStream;
+use rustc_data_structures::svh::Svh;
 use rustc_data_structures::sync::Lrc;
 use rustc_errors::ErrorGuaranteed;
 use rustc_parse::parser::ForceCollect;
 use rustc_session::config::ProcMacroExecutionStrategy;
 use rustc_span::profiling::SpannedEventArgRecorder;
+use rustc_span::symbol::Symbol;
 use rustc_span::{Span, DUMMY_SP};
 
 struct CrossbeamMessagePipe<T> {
@@ -33,15 +40,89 @@ fn recv(&mut self) -> Option<T> {
     }
 }
 
-fn exec_strategy(ecx: &ExtCtxt<'_>) -> impl pm::bridge::server::ExecutionStrategy {
-    pm::bridge::server::MaybeCrossThread::<CrossbeamMessagePipe<_>>::new(
-        ecx.sess.opts.unstable_opts.proc_macro_execution_strategy
-            == ProcMacroExecutionStrategy::CrossThread,
-    )
+/// The execution strategy of a proc macro, `OutOfProcess` being only available
+/// to proc macros loaded from a dylib.
+enum ExecStrategy<'a> {
+    InProcess(pm::bridge::server::MaybeCrossThread<CrossbeamMessagePipe<pm::bridge::Buffer>>),
+    OutOfProcess(OutOfProcess<'a>),
+}
+
+impl ExecStrategy<'_> {
+    /// Reports the failure of a proc macro which returned `e`, using `msg` as the
+    /// message of the error unless the failure is due to the process running the
+    /// proc macro.
+    fn report_failure(
+        &self,
+        ecx: &ExtCtxt<'_>,
+        span: Span,
+        msg: &str,
+        e: pm::bridge::PanicMessage,
+    ) -> ErrorGuaranteed {
+        let failure = match self {
+            ExecStrategy::InProcess(_) => None,
+            ExecStrategy::OutOfProcess(strategy) => strategy.take_failure(),
+        };
+        match failure {
+            Some(ProcessFailure::TimeLimit(limit)) => {
+                ecx.emit_err(errors::ProcMacroTimeLimit { span, limit })
+            }
+            Some(ProcessFailure::MemoryLimit(limit)) => {
+                ecx.emit_err(errors::ProcMacroMemoryLimit { span, limit })
+            }
+            Some(ProcessFailure::Failed(error)) => {
+                ecx.emit_err(errors::ProcMacroProcessFailed { span, error })
+            }
+            None => {
+                let mut err = ecx.struct_span_err(span, msg);
+                if let Some(s) = e.as_str() {
+                    err.help(&format!("message: {}", s));
+                }
+                err.emit()
+            }
+        }
+    }
+}
+
+impl pm::bridge::server::ExecutionStrategy for ExecStrategy<'_> {
+    fn run_bridge_and_client(
+        &self,
+        dispatcher: &mut impl pm::bridge::server::DispatcherTrait,
+        input: pm::bridge::Buffer,
+        run_client: extern "C" fn(pm::bridge::BridgeConfig<'_>) -> pm::bridge::Buffer,
+        force_show_panics: bool,
+    ) -> pm::bridge::Buffer {
+        match self {
+            ExecStrategy::InProcess(strategy) => {
+                strategy.run_bridge_and_client(dispatcher, input, run_client, force_show_panics)
+            }
+            ExecStrategy::OutOfProcess(strategy) => {
+                strategy.run_bridge_and_client(dispatcher, input, run_client, force_show_panics)
+            }
+        }
+    }
+}
+
+fn exec_strategy<'a>(ecx: &ExtCtxt<'_>, dylib: Option<&'a ProcMacroDylib>) -> ExecStrategy<'a> {
+    let opts = &ecx.sess.opts.unstable_opts;
+    match (opts.proc_macro_execution_strategy, dylib) {
+        (ProcMacroExecutionStrategy::OutOfProcess, Some(_)) if !child_entry_installed() => {
+            ecx.sess.emit_warning(errors::ProcMacroOutOfProcessUnsupported);
+            ExecStrategy::InProcess(pm::bridge::server::MaybeCrossThread::new(false))
+        }
+        (ProcMacroExecutionStrategy::OutOfProcess, Some(dylib)) => ExecStrategy::OutOfProcess(
+            OutOfProcess::new(dylib, opts.proc_macro_time_limit, opts.proc_macro_memory_limit),
+        ),
+        (strategy, _) => ExecStrategy::InProcess(pm::bridge::server::MaybeCrossThread::new(
+            strategy == ProcMacroExecutionStrategy::CrossThread,
+        )),
+    }
 }
 
 pub struct BangProcMacro {
     pub client: pm::bridge::client::Client<pm::TokenStream, pm::TokenStream>,
+    /// The dylib the proc macro was loaded from, if any, for running it in a
+    /// separate process.
+    pub dylib: Option<ProcMacroDylib>,
 }
 
 impl base::BangProcMacro for BangProcMacro {
@@ -57,20 +138,19 @@ fn expand<'cx>(
             });
 
         let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
-        let strategy = exec_strategy(ecx);
+        let strategy = exec_strategy(ecx, self.dylib.as_ref());
         let server = proc_macro_server::Rustc::new(ecx);
-        self.client.run(&strategy, server, input, proc_macro_backtrace).map_err(|e| {
-            let mut err = ecx.struct_span_err(span, "proc macro panicked");
-            if let Some(s) = e.as_str() {
-                err.help(&format!("message: {}", s));
-            }
-            err.emit()
-        })
+        self.client
+            .run(&strategy, server, input, proc_macro_backtrace)
+            .map_err(|e| strategy.report_failure(ecx, span, "proc macro panicked", e))
     }
 }
 
 pub struct AttrProcMacro {
     pub client: pm::bridge::client::Client<(pm::TokenStream, pm::TokenStream), pm::TokenStream>,
+    /// The dylib the proc macro was loaded from, if any, for running it in a
+    /// separate process.
+    pub dylib: Option<ProcMacroDylib>,
 }
 
 impl base::AttrProcMacro for AttrProcMacro {
@@ -87,22 +167,23 @@ fn expand<'cx>(
             });
 
         let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
-        let strategy = exec_strategy(ecx);
+        let strategy = exec_strategy(ecx, self.dylib.as_ref());
         let server = proc_macro_server::Rustc::new(ecx);
-        self.client.run(&strategy, server, annotation, annotated, proc_macro_backtrace).map_err(
-            |e| {
-                let mut err = ecx.struct_span_err(span, "custom attribute panicked");
-                if let Some(s) = e.as_str() {
-                    err.help(&format!("message: {}", s));
-                }
-                err.emit()
-            },
-        )
+        self.client
+            .run(&strategy, server, annotation, annotated, proc_macro_backtrace)
+            .map_err(|e| strategy.report_failure(ecx, span, "custom attribute panicked", e))
     }
 }
 
 pub struct DeriveProcMacro {
     pub client: pm::bridge::client::Client<pm::TokenStream, pm::TokenStream>,
+    /// The dylib the proc macro was loaded from, if any, for running it in a
+    /// separate process.
+    pub dylib: Option<ProcMacroDylib>,
+    /// The hash of the proc macro crate and the name of the derive, which
+    /// identify the derive in the cache of `-Z cache-proc-macros`.
+    pub crate_hash: Svh,
+    pub name: Symbol,
 }
 
 impl MultiItemModifier for DeriveProcMacro {
@@ -133,18 +214,37 @@ fn expand(
                 ecx.sess.prof.generic_activity_with_arg_recorder("expand_proc_macro", |recorder| {
                     recorder.record_arg_with_span(ecx.expansion_descr(), span);
                 });
-            let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
-            let strategy = exec_strategy(ecx);
-            let server = proc_macro_server::Rustc::new(ecx);
-            match self.client.run(&strategy, server, input, proc_macro_backtrace) {
-                Ok(stream) => stream,
-                Err(e) => {
-                    let mut err = ecx.struct_span_err(span, "proc-macro derive panicked");
-                    if let Some(s) = e.as_str() {
-                        err.help(&format!("message: {}", s));
+            let cache_key = match ecx.proc_macro_cache {
+                Some(_) => CacheKey::new(ecx, self.crate_hash, self.name, &input),
+                None => None,
+            };
+            let cached = match (&cache_key, &mut ecx.proc_macro_cache) {
+                (Some(key), Some(cache)) => cache.get(key),
+                _ => None,
+            };
+            if let Some(stream) = cached {
+                stream
+            } else {
+                if let Some(cache) = &mut ecx.proc_macro_cache {
+                    cache.take_side_effects();
+                }
+                let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
+                let strategy = exec_strategy(ecx, self.dylib.as_ref());
+                let server = proc_macro_server::Rustc::new(ecx);
+                match self.client.run(&strategy, server, input, proc_macro_backtrace) {
+                    Ok(stream) => {
+                        if let Some(key) = &cache_key
+                            && let Some(cache) = &mut ecx.proc_macro_cache
+                            && !cache.take_side_effects()
+                        {
+                            cache.insert(key, &stream);
+                        }
+                        stream
+                    }
+                    Err(e) => {
+                        strategy.report_failure(ecx, span, "proc-macro derive panicked", e);
+                        return ExpandResult::Ready(vec![]);
                     }
-                    err.emit();
-                    return ExpandResult::Ready(vec![]);
                 }
             }
         };
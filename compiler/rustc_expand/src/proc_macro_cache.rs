@@ -0,0 +1,271 @@
+//! Caching of the output of proc macro derives across builds, for
+//! `-Z cache-proc-macros`.
+//!
+//! The output of a derive is cached in the incremental directory, keyed by the
+//! crate hash of the proc macro crate, the name of the derive and the tokens of
+//! its input, ignoring their spans. The spans of the output are recorded
+//! relative to the input: each span is either the span of one of the input
+//! tokens, or one of the spans a proc macro gets from `Span::call_site`,
+//! `Span::def_site` and `Span::mixed_site`. This allows reusing the output of
+//! a derive whose input moved, e.g. because lines were added above it. Outputs
+//! with other spans are not cached.
+
+use crate::base::ExtCtxt;
+use crate::errors::FailedToSaveProcMacroCache;
+
+use rustc_ast::token::{Delimiter, Token, TokenKind};
+use rustc_ast::tokenstream::{DelimSpan, Spacing, TokenStream, TokenTree};
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_data_structures::svh::Svh;
+use rustc_serialize::opaque::{MemDecoder, MemEncoder};
+use rustc_serialize::{Decodable, Decoder, Encodable, Encoder};
+use rustc_session::Session;
+use rustc_span::symbol::Symbol;
+use rustc_span::Span;
+
+use std::fs;
+use std::hash::Hash;
+use std::io;
+use std::path::PathBuf;
+
+/// The name of the cache file in the incremental directory.
+const CACHE_FILE_NAME: &str = "proc-macro-cache.bin";
+
+/// The first bytes of the cache file.
+const FILE_MAGIC: &[u8] = b"RSPM";
+
+/// The outputs are only reused by the same compiler version, as the encoding of
+/// tokens may change between versions.
+const RUSTC_VERSION: Option<&str> = option_env!("CFG_VERSION");
+
+#[derive(Encodable, Decodable)]
+enum CachedSpan {
+    /// The span of the input token with this index, see `flatten_spans`.
+    Input(u32),
+    CallSite,
+    DefSite,
+    MixedSite,
+}
+
+#[derive(Encodable, Decodable)]
+enum CachedTree {
+    Token(TokenKind, CachedSpan, Spacing),
+    Delimited(CachedSpan, CachedSpan, Delimiter, Vec<CachedTree>),
+}
+
+/// The spans a proc macro gets from `Span::{call_site, def_site, mixed_site}`.
+struct SiteSpans {
+    call_site: Span,
+    def_site: Span,
+    mixed_site: Span,
+}
+
+impl SiteSpans {
+    /// Returns the spans of the current expansion, as the proc macro server
+    /// computes them.
+    fn new(ecx: &ExtCtxt<'_>) -> Self {
+        let expn_data = ecx.current_expansion.id.expn_data();
+        SiteSpans {
+            call_site: ecx.with_call_site_ctxt(expn_data.call_site),
+            def_site: ecx.with_def_site_ctxt(expn_data.def_site),
+            mixed_site: ecx.with_mixed_site_ctxt(expn_data.call_site),
+        }
+    }
+}
+
+/// Identifies the output of a derive in the cache.
+pub(crate) struct CacheKey {
+    fingerprint: Fingerprint,
+    /// The spans of the input tokens, in the order of `flatten_spans`.
+    input_spans: Vec<Span>,
+    sites: SiteSpans,
+}
+
+impl CacheKey {
+    /// Returns the key of the output of the derive `name` of the proc macro
+    /// crate with hash `crate_hash` for `input` in the current expansion, or
+    /// `None` if the input contains interpolated tokens, which cannot be cached.
+    pub(crate) fn new(
+        ecx: &ExtCtxt<'_>,
+        crate_hash: Svh,
+        name: Symbol,
+        input: &TokenStream,
+    ) -> Option<Self> {
+        let mut input_spans = Vec::new();
+        flatten_spans(input, &mut input_spans);
+        // The spans are not part of the key, `input_spans` is.
+        let trees = encode_trees(input, &mut |_| Some(CachedSpan::Input(0)))?;
+
+        let mut encoder = MemEncoder::new();
+        trees.encode(&mut encoder);
+        let mut hasher = StableHasher::new();
+        crate_hash.as_u64().hash(&mut hasher);
+        name.as_str().hash(&mut hasher);
+        encoder.finish().hash(&mut hasher);
+        Some(CacheKey { fingerprint: hasher.finish(), input_spans, sites: SiteSpans::new(ecx) })
+    }
+}
+
+pub(crate) struct ProcMacroCache {
+    path: PathBuf,
+    /// The outputs cached by the previous build.
+    previous: FxHashMap<Fingerprint, Vec<u8>>,
+    /// The outputs used or produced by this build, which are the ones that are
+    /// saved for the next build.
+    current: FxIndexMap<Fingerprint, Vec<u8>>,
+    /// Whether a proc macro emitted diagnostics or tracked dependencies since
+    /// `take_side_effects` was last called.
+    side_effects: bool,
+}
+
+impl ProcMacroCache {
+    /// Loads the outputs cached by the previous build. Returns `None` if
+    /// incremental compilation is disabled.
+    pub(crate) fn load(sess: &Session) -> Option<Self> {
+        let path = sess.incr_comp_session_dir_opt()?.join(CACHE_FILE_NAME);
+        let previous = fs::read(&path).ok().and_then(|data| decode_file(&data)).unwrap_or_default();
+        Some(ProcMacroCache { path, previous, current: Default::default(), side_effects: false })
+    }
+
+    pub(crate) fn record_side_effect(&mut self) {
+        self.side_effects = true;
+    }
+
+    /// Returns whether a proc macro emitted diagnostics or tracked dependencies
+    /// since the last call. Outputs produced with side effects are not cached,
+    /// as reusing them would skip the side effects.
+    pub(crate) fn take_side_effects(&mut self) -> bool {
+        std::mem::take(&mut self.side_effects)
+    }
+
+    /// Returns the cached output for `key`, with the spans of the current input.
+    pub(crate) fn get(&mut self, key: &CacheKey) -> Option<TokenStream> {
+        let data = self.previous.remove(&key.fingerprint)?;
+        let trees = Vec::<CachedTree>::decode(&mut MemDecoder::new(&data, 0));
+        let output = decode_trees(trees, key)?;
+        self.current.insert(key.fingerprint, data);
+        Some(output)
+    }
+
+    /// Caches `output` for `key`, unless some of its spans are neither spans of
+    /// the input nor spans of the expansion.
+    pub(crate) fn insert(&mut self, key: &CacheKey, output: &TokenStream) {
+        let sites = &key.sites;
+        let mut input_indices = FxHashMap::default();
+        for (i, &span) in key.input_spans.iter().enumerate() {
+            input_indices.entry(span).or_insert(i as u32);
+        }
+        let Some(trees) = encode_trees(output, &mut |span| {
+            if let Some(&i) = input_indices.get(&span) {
+                Some(CachedSpan::Input(i))
+            } else if span == sites.call_site {
+                Some(CachedSpan::CallSite)
+            } else if span == sites.def_site {
+                Some(CachedSpan::DefSite)
+            } else if span == sites.mixed_site {
+                Some(CachedSpan::MixedSite)
+            } else {
+                None
+            }
+        }) else {
+            return;
+        };
+        let mut encoder = MemEncoder::new();
+        trees.encode(&mut encoder);
+        self.current.insert(key.fingerprint, encoder.finish());
+    }
+
+    /// Saves the outputs used or produced by this build for the next one.
+    pub(crate) fn save(&self, sess: &Session) {
+        let mut encoder = MemEncoder::new();
+        encoder.emit_raw_bytes(FILE_MAGIC);
+        encoder.emit_str(RUSTC_VERSION.unwrap_or("unknown version"));
+        encoder.emit_usize(self.current.len());
+        for (fingerprint, data) in &self.current {
+            fingerprint.encode(&mut encoder);
+            data.encode(&mut encoder);
+        }
+
+        // The file may be a hard link to the file of the previous session, which
+        // must not be modified, so it is replaced rather than overwritten.
+        let result = match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => fs::write(&self.path, encoder.finish()),
+        };
+        if let Err(error) = result {
+            sess.emit_warning(FailedToSaveProcMacroCache { path: &self.path, error });
+        }
+    }
+}
+
+fn decode_file(data: &[u8]) -> Option<FxHashMap<Fingerprint, Vec<u8>>> {
+    let data = data.strip_prefix(FILE_MAGIC)?;
+    let mut decoder = MemDecoder::new(data, 0);
+    if decoder.read_str() != RUSTC_VERSION.unwrap_or("unknown version") {
+        return None;
+    }
+    let len = decoder.read_usize();
+    Some((0..len).map(|_| Decodable::decode(&mut decoder)).collect())
+}
+
+/// Pushes the spans of the tokens of `stream` to `spans`: the span of each
+/// token, and the spans of the opening and closing delimiters of each
+/// delimited group around the spans of its tokens.
+fn flatten_spans(stream: &TokenStream, spans: &mut Vec<Span>) {
+    for tree in stream.trees() {
+        match tree {
+            TokenTree::Token(token, _) => spans.push(token.span),
+            TokenTree::Delimited(delim_span, _, stream) => {
+                spans.push(delim_span.open);
+                flatten_spans(stream, spans);
+                spans.push(delim_span.close);
+            }
+        }
+    }
+}
+
+fn encode_trees(
+    stream: &TokenStream,
+    encode_span: &mut impl FnMut(Span) -> Option<CachedSpan>,
+) -> Option<Vec<CachedTree>> {
+    stream
+        .trees()
+        .map(|tree| match tree {
+            TokenTree::Token(Token { kind: TokenKind::Interpolated(_), .. }, _) => None,
+            TokenTree::Token(token, spacing) => {
+                Some(CachedTree::Token(token.kind.clone(), encode_span(token.span)?, *spacing))
+            }
+            TokenTree::Delimited(delim_span, delim, stream) => Some(CachedTree::Delimited(
+                encode_span(delim_span.open)?,
+                encode_span(delim_span.close)?,
+                *delim,
+                encode_trees(stream, encode_span)?,
+            )),
+        })
+        .collect()
+}
+
+fn decode_trees(trees: Vec<CachedTree>, key: &CacheKey) -> Option<TokenStream> {
+    let decode_span = |span| match span {
+        CachedSpan::Input(i) => key.input_spans.get(i as usize).copied(),
+        CachedSpan::CallSite => Some(key.sites.call_site),
+        CachedSpan::DefSite => Some(key.sites.def_site),
+        CachedSpan::MixedSite => Some(key.sites.mixed_site),
+    };
+    trees
+        .into_iter()
+        .map(|tree| match tree {
+            CachedTree::Token(kind, span, spacing) => {
+                Some(TokenTree::Token(Token::new(kind, decode_span(span)?), spacing))
+            }
+            CachedTree::Delimited(open, close, delim, trees) => Some(TokenTree::Delimited(
+                DelimSpan { open: decode_span(open)?, close: decode_span(close)? },
+                delim,
+                decode_trees(trees, key)?,
+            )),
+        })
+        .collect::<Option<Vec<_>>>()
+        .map(TokenStream::new)
+}
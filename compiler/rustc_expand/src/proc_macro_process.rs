@@ -0,0 +1,467 @@
+//! Running proc macros in a child process, for
+//! `-Z proc-macro-execution-strategy=out-of-process`.
+//!
+//! The child is another instance of the compiler executable, started with
+//! `CHILD_ENV_VAR` set, which makes `install_child_entry` call `run_child`
+//! instead of compiling anything. Drivers which don't call `install_child_entry`
+//! can't run proc macros out-of-process, and run them in-process instead.
+//!
+//! Each dylib gets one child, started by the first out-of-process expansion of
+//! one of its proc macros and reused by the next ones. The child loads the dylib
+//! and, for each input it receives, runs the client of the requested proc macro,
+//! sending the requests the client makes over the bridge to the parent, which
+//! dispatches them to its server as the in-process strategies do. Running the
+//! clients in their own process allows the parent to stop them once they exceed
+//! their time limit, and to give them a memory limit without affecting the
+//! compiler. A child that failed is not reused, the next expansion starts a new
+//! one.
+//!
+//! Messages are exchanged over the standard input and output of the child. Each
+//! message is a tag byte, followed by the length of the buffer it carries as a
+//! little-endian `u64`, followed by the buffer. The buffer of an input starts
+//! with the index of the proc macro as a little-endian `u64` and a byte which is
+//! 1 if panics must be shown, 0 otherwise.
+
+use pm::bridge::client::ProcMacro;
+use pm::bridge::server::{DispatcherTrait, ExecutionStrategy};
+use pm::bridge::{BridgeConfig, Buffer, PanicMessage};
+use rustc_data_structures::sync::{Lock, Lrc};
+
+use std::cell::RefCell;
+use std::env;
+use std::ffi::OsStr;
+use std::io::{self, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::process::{self, ChildStdin, ChildStdout, Command, ExitStatus, Stdio};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// The environment variable that makes the compiler executable run as the child
+/// process of out-of-process proc macros.
+pub const CHILD_ENV_VAR: &str = "RUSTC_PROC_MACRO_CHILD";
+
+/// Whether the driver called `install_child_entry`, i.e. whether its executable
+/// can run as a child process.
+static CHILD_ENTRY_INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// The input of a client, sent by the parent.
+const INPUT: u8 = 0;
+/// A request of a client, sent by the child.
+const REQUEST: u8 = 1;
+/// The reply of the server to a request, sent by the parent.
+const REPLY: u8 = 2;
+/// The output of a client, sent by the child.
+const OUTPUT: u8 = 3;
+
+/// Makes out-of-process proc macros available. Drivers call this at the start
+/// of `main`: if the process was started as the child process of out-of-process
+/// proc macros, this runs them and exits instead of returning.
+pub fn install_child_entry() {
+    if env::var_os(CHILD_ENV_VAR).is_some() {
+        process::exit(run_child());
+    }
+    CHILD_ENTRY_INSTALLED.store(true, Ordering::Relaxed);
+}
+
+/// Whether proc macros can be run out-of-process, see `install_child_entry`.
+pub(crate) fn child_entry_installed() -> bool {
+    CHILD_ENTRY_INSTALLED.load(Ordering::Relaxed)
+}
+
+/// A dylib proc macros were loaded from, and the child process running them.
+#[derive(Debug)]
+pub struct ProcMacroProcess {
+    /// The path of the dylib.
+    path: PathBuf,
+    /// The symbol of the proc macro declarations of the dylib.
+    symbol: String,
+    /// The child process, once a proc macro of the dylib ran out-of-process.
+    child: Lock<Option<Child>>,
+}
+
+impl ProcMacroProcess {
+    pub fn new(path: PathBuf, symbol: String) -> Self {
+        ProcMacroProcess { path, symbol, child: Lock::new(None) }
+    }
+
+    fn spawn(&self, memory_limit: Option<u64>) -> io::Result<Child> {
+        let mut process = Command::new(env::current_exe()?)
+            .env(CHILD_ENV_VAR, "1")
+            .arg(&self.path)
+            .arg(&self.symbol)
+            .arg(memory_limit.unwrap_or(0).to_string())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()?;
+        let stdin = process.stdin.take().unwrap();
+        let stdout = BufReader::new(process.stdout.take().unwrap());
+        Ok(Child { process: Arc::new(Mutex::new(process)), stdin, stdout })
+    }
+}
+
+/// Where a proc macro loaded from a dylib can be found, to run it in the child
+/// process of the dylib.
+#[derive(Clone, Debug)]
+pub struct ProcMacroDylib {
+    /// The dylib, shared by all of its proc macros.
+    pub process: Lrc<ProcMacroProcess>,
+    /// The index of the proc macro in the declarations.
+    pub index: usize,
+}
+
+/// A running child process, waiting for an input.
+#[derive(Debug)]
+struct Child {
+    process: Arc<Mutex<process::Child>>,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Child {
+    fn wait(&self) -> io::Result<ExitStatus> {
+        self.process.lock().unwrap().wait()
+    }
+}
+
+impl Drop for Child {
+    fn drop(&mut self) {
+        let mut process = self.process.lock().unwrap();
+        let _ = process.kill();
+        let _ = process.wait();
+    }
+}
+
+/// Why an out-of-process proc macro could not produce its output.
+pub(crate) enum ProcessFailure {
+    /// The child was stopped once the time limit, in milliseconds, was reached.
+    TimeLimit(u64),
+    /// The child ran out of the memory it was allowed to use, in MiB.
+    MemoryLimit(u64),
+    /// The child could not be started, or exited without producing an output.
+    Failed(String),
+}
+
+pub(crate) struct OutOfProcess<'a> {
+    dylib: &'a ProcMacroDylib,
+    time_limit: Option<u64>,
+    memory_limit: Option<u64>,
+    failure: RefCell<Option<ProcessFailure>>,
+}
+
+impl<'a> OutOfProcess<'a> {
+    pub(crate) fn new(
+        dylib: &'a ProcMacroDylib,
+        time_limit: Option<u64>,
+        memory_limit: Option<u64>,
+    ) -> Self {
+        OutOfProcess { dylib, time_limit, memory_limit, failure: RefCell::new(None) }
+    }
+
+    /// Returns why the last run of the proc macro failed, if it did not fail
+    /// because the proc macro panicked.
+    pub(crate) fn take_failure(&self) -> Option<ProcessFailure> {
+        self.failure.borrow_mut().take()
+    }
+
+    fn run(
+        &self,
+        dispatcher: &mut impl DispatcherTrait,
+        input: Buffer,
+        force_show_panics: bool,
+    ) -> Result<Buffer, ProcessFailure> {
+        let process = &self.dylib.process;
+        let mut slot = process.child.lock();
+        if slot.is_none() {
+            let child = process
+                .spawn(self.memory_limit)
+                .map_err(|e| ProcessFailure::Failed(e.to_string()))?;
+            *slot = Some(child);
+        }
+        let child = slot.as_mut().unwrap();
+
+        // Kill the child once the time limit is reached, which makes the
+        // communication with it below fail.
+        let timed_out = Arc::new(AtomicBool::new(false));
+        let (done_tx, done_rx) = crossbeam_channel::bounded::<()>(1);
+        let watchdog = self.time_limit.map(|limit| {
+            let process = Arc::clone(&child.process);
+            let timed_out = Arc::clone(&timed_out);
+            thread::spawn(move || {
+                if let Err(crossbeam_channel::RecvTimeoutError::Timeout) =
+                    done_rx.recv_timeout(Duration::from_millis(limit))
+                {
+                    timed_out.store(true, Ordering::SeqCst);
+                    let _ = process.lock().unwrap().kill();
+                }
+            })
+        });
+
+        let header = Header { index: self.dylib.index, force_show_panics };
+        let result = communicate(&mut child.stdin, &mut child.stdout, dispatcher, header, input);
+
+        drop(done_tx);
+        if let Some(watchdog) = watchdog {
+            watchdog.join().unwrap();
+        }
+
+        if timed_out.load(Ordering::SeqCst) {
+            *slot = None;
+            return Err(ProcessFailure::TimeLimit(self.time_limit.unwrap()));
+        }
+        let error = match result {
+            Ok(output) => return Ok(output),
+            Err(error) => error,
+        };
+        let child = slot.take().unwrap();
+        if error.kind() == io::ErrorKind::InvalidData {
+            // The child is still running, but can't be trusted anymore, and is
+            // killed when dropped.
+            return Err(ProcessFailure::Failed(error.to_string()));
+        }
+        // Otherwise the child closed its end of the pipes, so it exited.
+        let status = child.wait().map_err(|e| ProcessFailure::Failed(e.to_string()))?;
+        match (exit_signal(status), self.memory_limit) {
+            (Some(SIGABRT), Some(limit)) => return Err(ProcessFailure::MemoryLimit(limit)),
+            (Some(signal), _) => {
+                return Err(ProcessFailure::Failed(format!(
+                    "the process was killed by signal {signal}"
+                )));
+            }
+            (None, _) => {}
+        }
+        if status.success() {
+            Err(ProcessFailure::Failed(error.to_string()))
+        } else {
+            Err(ProcessFailure::Failed(format!("the process exited with {}", status)))
+        }
+    }
+}
+
+impl ExecutionStrategy for OutOfProcess<'_> {
+    fn run_bridge_and_client(
+        &self,
+        dispatcher: &mut impl DispatcherTrait,
+        input: Buffer,
+        _run_client: extern "C" fn(BridgeConfig<'_>) -> Buffer,
+        force_show_panics: bool,
+    ) -> Buffer {
+        match self.run(dispatcher, input, force_show_panics) {
+            Ok(output) => output,
+            Err(failure) => {
+                *self.failure.borrow_mut() = Some(failure);
+                pm::bridge::server::client_panicked(PanicMessage::Unknown)
+            }
+        }
+    }
+}
+
+/// The signal that killed the child, if any. Once its address space is limited,
+/// `SIGABRT` is how running out of memory likely ends: the allocation error
+/// handler of the standard library, which proc macro dylibs link their own copy
+/// of, aborts the process. A proc macro built with `panic=abort` ends the same
+/// way though, so that is only reported as possibly exceeding the limit.
+#[cfg(unix)]
+fn exit_signal(status: ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn exit_signal(_status: ExitStatus) -> Option<i32> {
+    None
+}
+
+#[cfg(unix)]
+const SIGABRT: i32 = libc::SIGABRT;
+
+#[cfg(not(unix))]
+const SIGABRT: i32 = 6;
+
+/// What the child needs to know besides the input of the client to run it.
+struct Header {
+    index: usize,
+    force_show_panics: bool,
+}
+
+impl Header {
+    const LEN: usize = 9;
+
+    fn encode(&self) -> [u8; Self::LEN] {
+        let mut buf = [0; Self::LEN];
+        buf[..8].copy_from_slice(&(self.index as u64).to_le_bytes());
+        buf[8] = self.force_show_panics as u8;
+        buf
+    }
+
+    fn decode(buf: &[u8]) -> io::Result<Header> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid proc macro input");
+        let index = buf.get(..8).ok_or_else(invalid)?;
+        let index = u64::from_le_bytes(index.try_into().unwrap()) as usize;
+        let force_show_panics = *buf.get(8).ok_or_else(invalid)? == 1;
+        Ok(Header { index, force_show_panics })
+    }
+}
+
+/// Sends `input` to the child, then dispatches its requests until it sends its
+/// output.
+fn communicate(
+    stdin: &mut impl Write,
+    stdout: &mut impl Read,
+    dispatcher: &mut impl DispatcherTrait,
+    header: Header,
+    input: Buffer,
+) -> io::Result<Buffer> {
+    write_message(stdin, INPUT, &[&header.encode(), &input])?;
+    loop {
+        match read_message(stdout)? {
+            (REQUEST, request) => {
+                let reply = dispatcher.dispatch(request.into());
+                write_message(stdin, REPLY, &[&reply])?;
+            }
+            (OUTPUT, output) => return Ok(output.into()),
+            (tag, _) => return Err(unexpected_message(tag)),
+        }
+    }
+}
+
+/// Writes a message carrying the concatenation of `bufs`.
+fn write_message(w: &mut impl Write, tag: u8, bufs: &[&[u8]]) -> io::Result<()> {
+    let len: usize = bufs.iter().map(|buf| buf.len()).sum();
+    w.write_all(&[tag])?;
+    w.write_all(&(len as u64).to_le_bytes())?;
+    for buf in bufs {
+        w.write_all(buf)?;
+    }
+    w.flush()
+}
+
+fn read_message(r: &mut impl Read) -> io::Result<(u8, Vec<u8>)> {
+    let mut tag = [0; 1];
+    r.read_exact(&mut tag)?;
+    let mut len = [0; 8];
+    r.read_exact(&mut len)?;
+    let mut buf = vec![0; u64::from_le_bytes(len) as usize];
+    r.read_exact(&mut buf)?;
+    Ok((tag[0], buf))
+}
+
+fn parse_arg<T: FromStr>(arg: &OsStr) -> Result<T, String> {
+    arg.to_str()
+        .and_then(|arg| arg.parse().ok())
+        .ok_or_else(|| format!("invalid argument {:?}", arg))
+}
+
+fn unexpected_message(tag: u8) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("unexpected proc macro message {}", tag))
+}
+
+/// The entry point of the child process, which runs the proc macros of the dylib
+/// described by its arguments until the parent closes its standard input.
+/// Returns the exit code of the process.
+fn run_child() -> i32 {
+    match try_run_child() {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("error: failed to run proc macro: {}", e);
+            1
+        }
+    }
+}
+
+fn try_run_child() -> Result<(), String> {
+    // Proc macros may start the compiler themselves, which must not run as a
+    // child process.
+    env::remove_var(CHILD_ENV_VAR);
+
+    let args: Vec<_> = env::args_os().skip(1).collect();
+    let [path, symbol, memory_limit] = &args[..] else {
+        return Err(format!("expected 3 arguments, found {}", args.len()));
+    };
+    let memory_limit: u64 = parse_arg(memory_limit)?;
+    if memory_limit != 0 {
+        limit_memory(memory_limit);
+    }
+
+    // The library is never unloaded, as the compiler itself does when running
+    // proc macros in-process.
+    let lib = unsafe { libloading::Library::new(path) }.map_err(|e| e.to_string())?;
+    let decls = unsafe { lib.get::<*const &[ProcMacro]>(symbol.to_string_lossy().as_bytes()) }
+        .map_err(|e| e.to_string())?;
+    let decls: &[ProcMacro] = unsafe { **decls.into_raw() };
+    std::mem::forget(lib);
+
+    let mut input = io::stdin().lock();
+    let mut output = protocol_output();
+    loop {
+        let mut buf = match read_message(&mut input) {
+            Ok((INPUT, buf)) => buf,
+            Ok((tag, _)) => return Err(unexpected_message(tag).to_string()),
+            // The parent no longer needs this process.
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(e) => return Err(e.to_string()),
+        };
+        let header = Header::decode(&buf).map_err(|e| e.to_string())?;
+        let buf = Buffer::from(buf.split_off(Header::LEN));
+        let proc_macro =
+            decls.get(header.index).ok_or_else(|| format!("no proc macro {}", header.index))?;
+
+        let dispatch = |request: Buffer| -> Buffer {
+            let reply = write_message(&mut output, REQUEST, &[&request])
+                .and_then(|()| read_message(&mut input));
+            match reply {
+                Ok((REPLY, reply)) => reply.into(),
+                // The parent is gone, or can't be understood anymore, so nobody
+                // is waiting for the output either.
+                _ => process::exit(1),
+            }
+        };
+        let force_show_panics = header.force_show_panics;
+        let buf = match *proc_macro {
+            ProcMacro::CustomDerive { client, .. } => {
+                client.run_with_dispatch(buf, dispatch, force_show_panics)
+            }
+            ProcMacro::Attr { client, .. } => {
+                client.run_with_dispatch(buf, dispatch, force_show_panics)
+            }
+            ProcMacro::Bang { client, .. } => {
+                client.run_with_dispatch(buf, dispatch, force_show_panics)
+            }
+        };
+        write_message(&mut output, OUTPUT, &[&buf]).map_err(|e| e.to_string())?;
+    }
+}
+
+/// Limits the address space of the process to `limit` MiB. Allocations failing
+/// past it abort the process, which the parent reports as running out of memory,
+/// see `killed_by_signal`.
+#[cfg(unix)]
+fn limit_memory(limit: u64) {
+    let bytes = limit.saturating_mul(1024 * 1024) as libc::rlim_t;
+    let rlimit = libc::rlimit { rlim_cur: bytes, rlim_max: bytes };
+    unsafe { libc::setrlimit(libc::RLIMIT_AS, &rlimit) };
+}
+
+#[cfg(not(unix))]
+fn limit_memory(_limit: u64) {}
+
+/// Returns the stream the messages of the child are written to. Proc macros may
+/// print to the standard output, so on Unix the messages are written to a copy
+/// of it, and the standard output is redirected to the standard error.
+#[cfg(unix)]
+fn protocol_output() -> impl Write {
+    use std::os::unix::io::FromRawFd;
+    unsafe {
+        let fd = libc::dup(libc::STDOUT_FILENO);
+        libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO);
+        std::fs::File::from_raw_fd(fd)
+    }
+}
+
+#[cfg(not(unix))]
+fn protocol_output() -> impl Write {
+    io::stdout()
+}
@@ -362,6 +362,14 @@ pub fn new(ecx: &'a mut ExtCtxt<'b>) -> Self {
     fn sess(&self) -> &ParseSess {
         self.ecx.parse_sess()
     }
+
+    /// Records that the proc macro had effects besides producing its output,
+    /// which would be lost if its output was reused.
+    fn record_side_effect(&mut self) {
+        if let Some(cache) = &mut self.ecx.proc_macro_cache {
+            cache.record_side_effect();
+        }
+    }
 }
 
 impl server::Types for Rustc<'_, '_> {
@@ -374,6 +382,7 @@ impl server::Types for Rustc<'_, '_> {
 
 impl server::FreeFunctions for Rustc<'_, '_> {
     fn track_env_var(&mut self, var: &str, value: Option<&str>) {
+        self.record_side_effect();
         self.sess()
             .env_depinfo
             .borrow_mut()
@@ -381,6 +390,7 @@ fn track_env_var(&mut self, var: &str, value: Option<&str>) {
     }
 
     fn track_path(&mut self, path: &str) {
+        self.record_side_effect();
         self.sess().file_depinfo.borrow_mut().insert(Symbol::intern(path));
     }
 
@@ -436,6 +446,7 @@ fn literal_from_str(&mut self, s: &str) -> Result<Literal<Self::Span, Self::Symb
     }
 
     fn emit_diagnostic(&mut self, diagnostic: Diagnostic<Self::Span>) {
+        self.record_side_effect();
         let mut diag =
             rustc_errors::Diagnostic::new(diagnostic.level.to_internal(), diagnostic.message);
         diag.set_span(MultiSpan::from_spans(diagnostic.spans));
@@ -116,7 +116,7 @@
 use rustc_middle::ty::{InternalSubsts, SubstsRef};
 use rustc_session::config;
 use rustc_session::parse::feature_err;
-use rustc_session::Session;
+use rustc_session::{CostPhase, Session};
 use rustc_span::source_map::DUMMY_SP;
 use rustc_span::symbol::{kw, Ident};
 use rustc_span::{self, BytePos, Span, Symbol};
@@ -325,12 +325,14 @@ fn typeck_const_arg<'tcx>(
 }
 
 fn typeck<'tcx>(tcx: TyCtxt<'tcx>, def_id: LocalDefId) -> &ty::TypeckResults<'tcx> {
-    if let Some(param_did) = tcx.opt_const_param_of(def_id) {
-        tcx.typeck_const_arg((def_id, param_did))
-    } else {
-        let fallback = move || tcx.type_of(def_id.to_def_id());
-        typeck_with_fallback(tcx, def_id, fallback)
-    }
+    tcx.sess.time_item(def_id.to_def_id(), CostPhase::Typeck, || {
+        if let Some(param_did) = tcx.opt_const_param_of(def_id) {
+            tcx.typeck_const_arg((def_id, param_did))
+        } else {
+            let fallback = move || tcx.type_of(def_id.to_def_id());
+            typeck_with_fallback(tcx, def_id, fallback)
+        }
+    })
 }
 
 /// Used only to get `TypeckResults` for type inference during error recovery.
@@ -25,15 +25,19 @@ fn track_span_parent(def_id: rustc_span::def_id::LocalDefId) {
 
 /// This is a callback from `rustc_ast` as it cannot access the implicit state
 /// in `rustc_middle` otherwise. It is used when diagnostic messages are
-/// emitted and stores them in the current query, if there is one.
-fn track_diagnostic(diagnostic: &Diagnostic) {
+/// emitted and stores them in the current query, if there is one. Returns
+/// `false` when the current query recomputes a green query, whose diagnostics
+/// were already replayed from the previous session.
+fn track_diagnostic(diagnostic: &Diagnostic) -> bool {
     tls::with_context_opt(|icx| {
         if let Some(icx) = icx {
             if let Some(diagnostics) = icx.diagnostics {
                 let mut diagnostics = diagnostics.lock();
                 diagnostics.extend(Some(diagnostic.clone()));
             }
+            return !icx.diagnostics_replayed;
         }
+        true
     })
 }
 
@@ -55,5 +59,5 @@ fn def_id_debug(def_id: rustc_hir::def_id::DefId, f: &mut fmt::Formatter<'_>) ->
 pub fn setup_callbacks() {
     rustc_span::SPAN_TRACK.swap(&(track_span_parent as fn(_)));
     rustc_hir::def_id::DEF_ID_DEBUG.swap(&(def_id_debug as fn(_, &mut fmt::Formatter<'_>) -> _));
-    TRACK_DIAGNOSTICS.swap(&(track_diagnostic as fn(&_)));
+    TRACK_DIAGNOSTICS.swap(&(track_diagnostic as fn(&_) -> _));
 }
@@ -325,6 +325,7 @@ pub fn create_compiler_and_run<R>(config: Config, f: impl FnOnce(&Compiler) -> R
 
         let prof = compiler.sess.prof.clone();
         prof.generic_activity("drop_compiler").run(move || drop(compiler));
+        prof.print_time_passes_report();
         r
     })
 }
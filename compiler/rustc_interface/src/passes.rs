@@ -996,6 +996,10 @@ pub fn start_codegen<'tcx>(
 
     info!("Post-codegen\n{:?}", tcx.debug_stats());
 
+    if let Some(format) = tcx.sess.opts.unstable_opts.item_compile_cost {
+        tcx.sess.item_compile_costs.print(format, |def_id| tcx.def_path_str(def_id));
+    }
+
     if tcx.sess.opts.output_types.contains_key(&OutputType::Mir) {
         if let Err(error) = rustc_mir_transform::dump_mir::emit_mir(tcx, outputs) {
             tcx.sess.emit_err(CantEmitMIR { error });
@@ -1003,6 +1007,9 @@ pub fn start_codegen<'tcx>(
         }
     }
 
+    rustc_passes::used_items::check_used_items(tcx, outputs);
+    rustc_passes::dep_usage::write_dep_usage(tcx, outputs);
+
     codegen
 }
 
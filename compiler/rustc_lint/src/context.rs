@@ -38,6 +38,7 @@
 use rustc_middle::ty::layout::{LayoutError, LayoutOfHelpers, TyAndLayout};
 use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::{self, print::Printer, subst::GenericArg, RegisteredTools, Ty, TyCtxt};
+use rustc_session::lint::builtin::UNUSED_IMPORTS;
 use rustc_session::lint::{BuiltinLintDiagnostics, LintExpectationId};
 use rustc_session::lint::{FutureIncompatibleInfo, Level, Lint, LintBuffer, LintId};
 use rustc_session::Session;
@@ -686,6 +687,26 @@ fn lookup_with_diagnostics(
                         );
                     }
                 }
+                BuiltinLintDiagnostics::UnorganizedImports(replaces, without_unused) => {
+                    db.tool_only_multipart_suggestion(
+                        "organize the imports",
+                        replaces,
+                        Applicability::MachineApplicable,
+                    );
+                    // Imports that look unused may still be used by `#[cfg(test)]` code.
+                    if let Some(without_unused) = without_unused
+                        && !matches!(
+                            self.get_lint_level(UNUSED_IMPORTS),
+                            Level::Allow | Level::Expect(_)
+                        )
+                    {
+                        db.tool_only_multipart_suggestion(
+                            "organize the imports and remove the unused ones",
+                            without_unused,
+                            Applicability::MaybeIncorrect,
+                        );
+                    }
+                }
                 BuiltinLintDiagnostics::RedundantImport(spans, ident) => {
                     for (span, is_imported) in spans {
                         let introduced = if is_imported { "imported" } else { "defined" };
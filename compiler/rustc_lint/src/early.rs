@@ -15,6 +15,7 @@
 //! for all lint attributes.
 
 use crate::context::{EarlyContext, LintContext, LintStore};
+use crate::levels::lint_config_module_file;
 use crate::passes::{EarlyLintPass, EarlyLintPassObject};
 use rustc_ast::ptr::P;
 use rustc_ast::visit::{self as ast_visit, Visitor};
@@ -59,6 +60,9 @@ fn with_lint_attrs<F>(&mut self, id: ast::NodeId, attrs: &'a [ast::Attribute], f
         F: FnOnce(&mut Self),
     {
         let is_crate_node = id == ast::CRATE_NODE_ID;
+        let crate_file =
+            if is_crate_node { self.context.sess().local_crate_source_file.clone() } else { None };
+        let overrides = self.context.builder.push_lint_config_overrides(crate_file.as_deref());
         let push = self.context.builder.push(attrs, is_crate_node, None);
 
         self.check_id(id);
@@ -68,6 +72,7 @@ fn with_lint_attrs<F>(&mut self, id: ast::NodeId, attrs: &'a [ast::Attribute], f
         debug!("early context: exit_attrs({:?})", attrs);
         run_early_pass!(self, exit_lint_attrs, attrs);
         self.context.builder.pop(push);
+        self.context.builder.pop(overrides);
     }
 }
 
@@ -80,11 +85,19 @@ fn visit_param(&mut self, param: &'a ast::Param) {
     }
 
     fn visit_item(&mut self, it: &'a ast::Item) {
+        let file = match &it.kind {
+            ast::ItemKind::Mod(_, ast::ModKind::Loaded(_, ast::Inline::No, spans)) => {
+                lint_config_module_file(self.context.sess(), it.span, spans.inner_span)
+            }
+            _ => None,
+        };
+        let overrides = self.context.builder.push_lint_config_overrides(file.as_deref());
         self.with_lint_attrs(it.id, &it.attrs, |cx| {
             run_early_pass!(cx, check_item, it);
             ast_visit::walk_item(cx, it);
             run_early_pass!(cx, check_item_post, it);
-        })
+        });
+        self.context.builder.pop(overrides);
     }
 
     fn visit_foreign_item(&mut self, it: &'a ast::ForeignItem) {
@@ -20,6 +20,7 @@ pub enum OverruledAttributeSub {
     DefaultSource { id: String },
     NodeSource { span: Span, reason: Option<Symbol> },
     CommandLineSource,
+    LintConfigSource { line: u32 },
 }
 
 impl AddToDiagnostic for OverruledAttributeSub {
@@ -38,6 +39,10 @@ fn add_to_diagnostic(self, diag: &mut rustc_errors::Diagnostic) {
             OverruledAttributeSub::CommandLineSource => {
                 diag.note(fluent::lint::command_line_source);
             }
+            OverruledAttributeSub::LintConfigSource { line } => {
+                diag.note(fluent::lint::lint_config_source);
+                diag.set_arg("line", line);
+            }
         }
     }
 }
@@ -160,3 +165,12 @@ pub struct CheckNameDeprecated {
     #[subdiagnostic]
     pub sub: RequestedLevel,
 }
+
+#[derive(Diagnostic)]
+#[diag(lint::lint_config_unknown, code = "E0602")]
+#[note]
+pub struct LintConfigUnknown {
+    pub lint_name: String,
+    pub path: String,
+    pub line: u32,
+}
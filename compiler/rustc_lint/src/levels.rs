@@ -13,6 +13,7 @@
 };
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::{RegisteredTools, TyCtxt};
+use rustc_session::config::lint_config::LintConfig;
 use rustc_session::lint::{
     builtin::{self, FORBIDDEN_LINT_GROUPS, SINGLE_USE_LIFETIMES, UNFULFILLED_LINT_EXPECTATIONS},
     Level, Lint, LintExpectationId, LintId,
@@ -20,11 +21,13 @@
 use rustc_session::parse::{add_feature_diagnostics, feature_err};
 use rustc_session::Session;
 use rustc_span::symbol::{sym, Symbol};
-use rustc_span::{Span, DUMMY_SP};
+use rustc_span::{FileName, Span, DUMMY_SP};
+
+use std::path::{Path, PathBuf};
 
 use crate::errors::{
-    MalformedAttribute, MalformedAttributeSub, OverruledAttribute, OverruledAttributeSub,
-    UnknownToolInScopedLint,
+    LintConfigUnknown, MalformedAttribute, MalformedAttributeSub, OverruledAttribute,
+    OverruledAttributeSub, UnknownToolInScopedLint,
 };
 
 fn lint_levels(tcx: TyCtxt<'_>, (): ()) -> LintLevelMap {
@@ -36,12 +39,15 @@ fn lint_levels(tcx: TyCtxt<'_>, (): ()) -> LintLevelMap {
 
     builder.levels.id_to_set.reserve(krate.owners.len() + 1);
 
+    let overrides =
+        builder.levels.push_lint_config_overrides(tcx.sess.local_crate_source_file.as_deref());
     let push =
         builder.levels.push(tcx.hir().attrs(hir::CRATE_HIR_ID), true, Some(hir::CRATE_HIR_ID));
 
     builder.levels.register_id(hir::CRATE_HIR_ID);
     tcx.hir().walk_toplevel_module(&mut builder);
     builder.levels.pop(push);
+    builder.levels.pop(overrides);
 
     builder.levels.update_unstable_expectation_ids();
     builder.levels.build_map()
@@ -110,6 +116,26 @@ fn process_command_line(&mut self, sess: &Session, store: &LintStore) {
 
         self.cur =
             self.sets.list.push(LintSet { specs: FxHashMap::default(), parent: COMMAND_LINE });
+
+        // Levels from the `--lint-config` file go first so that they can be
+        // overridden from the command line.
+        if let Some(config) = &sess.opts.lint_config {
+            for level in &config.lints {
+                let src = LintLevelSource::LintConfig(Symbol::intern(&level.name), level.line);
+                for id in self.find_lint_config_lints(config, &level.name, level.line) {
+                    if let Some((Level::ForceWarn(_) | Level::Forbid, _)) =
+                        self.current_specs().get(&id)
+                    {
+                        continue;
+                    }
+
+                    if self.check_gated_lint(id, DUMMY_SP) {
+                        self.current_specs_mut().insert(id, (level.level, src));
+                    }
+                }
+            }
+        }
+
         for &(ref lint_name, level) in &sess.opts.lint_opts {
             store.check_lint_name_cmdline(sess, &lint_name, level, self.registered_tools);
             let orig_level = level;
@@ -135,6 +161,61 @@ fn process_command_line(&mut self, sess: &Session, store: &LintStore) {
         }
     }
 
+    /// Returns the lints that `name`, as written at `line` of the `--lint-config`
+    /// file, refers to. `name` may be a lint group defined by the file.
+    fn find_lint_config_lints(&self, config: &LintConfig, name: &str, line: u32) -> Vec<LintId> {
+        let find_lints = |name: &str, line: u32| match self.store.find_lints(name) {
+            Ok(ids) => ids,
+            Err(_) => {
+                // Lints of tools that are not running can't be found, which is fine.
+                if self.warn_about_weird_lints && !name.contains("::") {
+                    self.sess.emit_err(LintConfigUnknown {
+                        lint_name: name.to_string(),
+                        path: config.path.display().to_string(),
+                        line,
+                    });
+                }
+                vec![]
+            }
+        };
+
+        match config.group(name) {
+            Some(group) => {
+                group.lints.iter().flat_map(|lint| find_lints(lint, group.line)).collect()
+            }
+            None => find_lints(name, line),
+        }
+    }
+
+    /// Pushes the lint levels that the overrides of the `--lint-config` file set
+    /// for `file`, the source file of a module.
+    ///
+    /// Like for `push`, don't forget to call `pop`!
+    pub(crate) fn push_lint_config_overrides(&mut self, file: Option<&Path>) -> BuilderPush {
+        let prev = self.cur;
+        let sess = self.sess;
+        let (Some(config), Some(file)) = (&sess.opts.lint_config, file) else {
+            return BuilderPush { prev, changed: false };
+        };
+
+        self.cur = self.sets.list.push(LintSet { specs: FxHashMap::default(), parent: prev });
+        for level in config.overrides_for(file) {
+            let src = LintLevelSource::LintConfig(Symbol::intern(&level.name), level.line);
+            for id in self.find_lint_config_lints(config, &level.name, level.line) {
+                if self.check_gated_lint(id, DUMMY_SP) {
+                    self.insert_spec(id, (level.level, src));
+                }
+            }
+        }
+
+        if self.current_specs().is_empty() {
+            self.sets.list.pop();
+            self.cur = prev;
+        }
+
+        BuilderPush { prev, changed: prev != self.cur }
+    }
+
     /// Attempts to insert the `id` to `level_src` map entry. If unsuccessful
     /// (e.g. if a forbid was already inserted on the same scope), then emits a
     /// diagnostic with no change to `specs`.
@@ -160,6 +241,7 @@ fn insert_spec(&mut self, id: LintId, (level, src): LevelAndSource) {
                     LintLevelSource::Default => false,
                     LintLevelSource::Node(symbol, _, _) => self.store.is_lint_group(symbol),
                     LintLevelSource::CommandLine(symbol, _) => self.store.is_lint_group(symbol),
+                    LintLevelSource::LintConfig(symbol, _) => self.store.is_lint_group(symbol),
                 };
                 debug!(
                     "fcw_warning={:?}, specs.get(&id) = {:?}, old_src={:?}, id_name={:?}",
@@ -187,6 +269,12 @@ fn insert_spec(&mut self, id: LintId, (level, src): LevelAndSource) {
                         LintLevelSource::CommandLine(_, _) => {
                             diag.note("`forbid` lint level was set on command line");
                         }
+                        LintLevelSource::LintConfig(_, line) => {
+                            diag.note(&format!(
+                                "`forbid` lint level was set by lint-config at line {}",
+                                line
+                            ));
+                        }
                     }
                 };
                 if !fcw_warning {
@@ -208,6 +296,9 @@ fn insert_spec(&mut self, id: LintId, (level, src): LevelAndSource) {
                             LintLevelSource::CommandLine(_, _) => {
                                 OverruledAttributeSub::CommandLineSource
                             }
+                            LintLevelSource::LintConfig(_, line) => {
+                                OverruledAttributeSub::LintConfigSource { line }
+                            }
                         },
                     });
                 } else {
@@ -755,9 +846,17 @@ fn visit_param(&mut self, param: &'tcx hir::Param<'tcx>) {
     }
 
     fn visit_item(&mut self, it: &'tcx hir::Item<'tcx>) {
+        let file = match it.kind {
+            hir::ItemKind::Mod(module) => {
+                lint_config_module_file(self.tcx.sess, it.span, module.spans.inner_span)
+            }
+            _ => None,
+        };
+        let overrides = self.levels.push_lint_config_overrides(file.as_deref());
         self.with_lint_attrs(it.hir_id(), |builder| {
             intravisit::walk_item(builder, it);
         });
+        self.levels.pop(overrides);
     }
 
     fn visit_foreign_item(&mut self, it: &'tcx hir::ForeignItem<'tcx>) {
@@ -834,6 +933,29 @@ fn visit_generic_param(&mut self, p: &'tcx hir::GenericParam<'tcx>) {
     }
 }
 
+/// Returns the source file of a module declared with `mod foo;`, given the span
+/// of the module item and the span of its contents, if `--lint-config` overrides
+/// may apply to it.
+pub(crate) fn lint_config_module_file(
+    sess: &Session,
+    span: Span,
+    inner_span: Span,
+) -> Option<PathBuf> {
+    if sess.opts.lint_config.as_ref().map_or(true, |config| config.overrides.is_empty()) {
+        return None;
+    }
+    let sm = sess.source_map();
+    let file = sm.lookup_source_file(inner_span.lo());
+    if sm.lookup_source_file(span.lo()).start_pos == file.start_pos {
+        // Inline modules are covered by the overrides of the file they are in.
+        return None;
+    }
+    match &file.name {
+        FileName::Real(name) => name.local_path().map(Path::to_path_buf),
+        _ => None,
+    }
+}
+
 pub fn provide(providers: &mut Providers) {
     providers.lint_levels = lint_levels;
 }
@@ -316,6 +316,45 @@
     "imports that are never used"
 }
 
+declare_lint! {
+    /// The `unorganized_imports` lint detects modules whose `use` items are not
+    /// a single sorted and merged list of the imports that are needed.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,compile_fail
+    /// #![deny(unorganized_imports)]
+    /// use std::fmt::Display;
+    /// use std::collections::HashMap;
+    /// use std::fmt::Debug;
+    ///
+    /// pub fn f(_: &dyn Debug, _: &dyn Display) {}
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// The lint suggests replacing the private `use` items of a module with
+    /// one `use` item per crate, merging common prefixes, sorting the paths
+    /// and dropping imports that a glob import of the same module already
+    /// provides. Unlike formatters, the compiler knows which imports are
+    /// redundant.
+    ///
+    /// Unused imports are kept by that suggestion, as they may be used by
+    /// `#[cfg(test)]` code. Unless `unused_imports` is allowed, a second
+    /// suggestion that also removes them is offered, but it is not applied
+    /// automatically.
+    ///
+    /// Public imports and imports with attributes are left alone.
+    ///
+    /// This lint is "allow" by default because the layout of imports is a
+    /// stylistic choice.
+    pub UNORGANIZED_IMPORTS,
+    Allow,
+    "detects `use` items that are not sorted, merged and minimal"
+}
+
 declare_lint! {
     /// The `must_not_suspend` lint guards against values that shouldn't be held across suspend points
     /// (`.await`)
@@ -3271,6 +3310,7 @@
         ARITHMETIC_OVERFLOW,
         UNCONDITIONAL_PANIC,
         UNUSED_IMPORTS,
+        UNORGANIZED_IMPORTS,
         UNUSED_EXTERN_CRATES,
         UNUSED_CRATE_DEPENDENCIES,
         UNUSED_QUALIFICATIONS,
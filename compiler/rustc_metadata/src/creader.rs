@@ -31,7 +31,7 @@
 
 use proc_macro::bridge::client::ProcMacro;
 use std::ops::Fn;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::{cmp, env};
 
 #[derive(Clone)]
@@ -400,7 +400,7 @@ fn register_crate(
 
         let cnum_map = self.resolve_crate_deps(root, &crate_root, &metadata, cnum, dep_kind)?;
 
-        let raw_proc_macros = if crate_root.is_proc_macro_crate() {
+        let (raw_proc_macros, proc_macro_dylib) = if crate_root.is_proc_macro_crate() {
             let temp_root;
             let (dlsym_source, dlsym_root) = match &host_lib {
                 Some(host_lib) => (&host_lib.source, {
@@ -410,9 +410,11 @@ fn register_crate(
                 None => (&source, &crate_root),
             };
             let dlsym_dylib = dlsym_source.dylib.as_ref().expect("no dylib for a proc-macro crate");
-            Some(self.dlsym_proc_macros(&dlsym_dylib.0, dlsym_root.stable_crate_id())?)
+            let (raw_proc_macros, proc_macro_dylib) =
+                self.dlsym_proc_macros(&dlsym_dylib.0, dlsym_root.stable_crate_id())?;
+            (Some(raw_proc_macros), Some(proc_macro_dylib))
         } else {
-            None
+            (None, None)
         };
 
         // Perform some verification *after* resolve_crate_deps() above is
@@ -429,6 +431,7 @@ fn register_crate(
             metadata,
             crate_root,
             raw_proc_macros,
+            proc_macro_dylib,
             cnum,
             cnum_map,
             dep_kind,
@@ -663,14 +666,17 @@ fn resolve_crate_deps(
         Ok(crate_num_map)
     }
 
+    /// Loads the proc macros of the dylib at `path`. Also returns the absolute
+    /// path of the dylib and the symbol of its proc macro declarations, which
+    /// out-of-process proc macros use to load it again.
     fn dlsym_proc_macros(
         &self,
         path: &Path,
         stable_crate_id: StableCrateId,
-    ) -> Result<&'static [ProcMacro], CrateError> {
+    ) -> Result<(&'static [ProcMacro], (PathBuf, String)), CrateError> {
         // Make sure the path contains a / or the linker will search for it.
         let path = env::current_dir().unwrap().join(path);
-        let lib = unsafe { libloading::Library::new(path) }
+        let lib = unsafe { libloading::Library::new(&path) }
             .map_err(|err| CrateError::DlOpen(err.to_string()))?;
 
         let sym_name = self.sess.generate_proc_macro_decls_symbol(stable_crate_id);
@@ -682,7 +688,7 @@ fn dlsym_proc_macros(
         let sym = unsafe { sym.into_raw() };
         std::mem::forget(lib);
 
-        Ok(unsafe { **sym })
+        Ok((unsafe { **sym }, (path, sym_name)))
     }
 
     fn inject_panic_runtime(&mut self, krate: &ast::Crate) {
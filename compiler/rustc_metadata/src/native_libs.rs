@@ -4,6 +4,7 @@
 use rustc_hir as hir;
 use rustc_hir::def::DefKind;
 use rustc_middle::ty::{List, ParamEnv, ParamEnvAnd, Ty, TyCtxt};
+use rustc_session::cfg_eval::CfgEvalKind;
 use rustc_session::config::CrateType;
 use rustc_session::cstore::{DllCallingConvention, DllImport, NativeLib, PeImportNameType};
 use rustc_session::parse::feature_err;
@@ -86,7 +87,9 @@ pub(crate) fn collect(tcx: TyCtxt<'_>) -> Vec<NativeLib> {
 
 pub(crate) fn relevant_lib(sess: &Session, lib: &NativeLib) -> bool {
     match lib.cfg {
-        Some(ref cfg) => attr::cfg_matches(cfg, &sess.parse_sess, CRATE_NODE_ID, None),
+        Some(ref cfg) => {
+            attr::cfg_matches(cfg, &sess.parse_sess, CRATE_NODE_ID, None, CfgEvalKind::Link)
+        }
         None => true,
     }
 }
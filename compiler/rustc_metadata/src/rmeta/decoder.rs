@@ -11,6 +11,7 @@
 use rustc_data_structures::unhash::UnhashMap;
 use rustc_expand::base::{SyntaxExtension, SyntaxExtensionKind};
 use rustc_expand::proc_macro::{AttrProcMacro, BangProcMacro, DeriveProcMacro};
+use rustc_expand::proc_macro_process::{ProcMacroDylib, ProcMacroProcess};
 use rustc_hir::def::{CtorKind, CtorOf, DefKind, Res};
 use rustc_hir::def_id::{CrateNum, DefId, DefIndex, CRATE_DEF_INDEX, LOCAL_CRATE};
 use rustc_hir::definitions::{DefKey, DefPath, DefPathData, DefPathHash};
@@ -40,7 +41,7 @@
 use std::iter::TrustedLen;
 use std::mem;
 use std::num::NonZeroUsize;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub(super) use cstore_impl::provide;
 pub use cstore_impl::provide_extern;
@@ -96,6 +97,9 @@ pub(crate) struct CrateMetadata {
     incoherent_impls: FxHashMap<SimplifiedType, LazyArray<DefIndex>>,
     /// Proc macro descriptions for this crate, if it's a proc macro crate.
     raw_proc_macros: Option<&'static [ProcMacro]>,
+    /// The dylib the proc macros were loaded from, and the process running them
+    /// out-of-process.
+    proc_macro_process: Option<Lrc<ProcMacroProcess>>,
     /// Source maps for code from the crate.
     source_map_import_info: Lock<Vec<Option<ImportedSourceFile>>>,
     /// For every definition in this crate, maps its `DefPathHash` to its `DefIndex`.
@@ -758,18 +762,25 @@ pub(crate) fn decode_crate_deps<'a>(
 
 impl<'a, 'tcx> CrateMetadataRef<'a> {
     fn raw_proc_macro(self, id: DefIndex) -> &'a ProcMacro {
+        &self.raw_proc_macros.unwrap()[self.proc_macro_index(id)]
+    }
+
+    fn proc_macro_dylib(self, id: DefIndex) -> ProcMacroDylib {
+        let process = Lrc::clone(self.proc_macro_process.as_ref().unwrap());
+        ProcMacroDylib { process, index: self.proc_macro_index(id) }
+    }
+
+    fn proc_macro_index(self, id: DefIndex) -> usize {
         // DefIndex's in root.proc_macro_data have a one-to-one correspondence
         // with items in 'raw_proc_macros'.
-        let pos = self
-            .root
+        self.root
             .proc_macro_data
             .as_ref()
             .unwrap()
             .macros
             .decode(self)
             .position(|i| i == id)
-            .unwrap();
-        &self.raw_proc_macros.unwrap()[pos]
+            .unwrap()
     }
 
     fn opt_item_name(self, item_index: DefIndex) -> Option<Symbol> {
@@ -817,22 +828,25 @@ fn get_span(self, index: DefIndex, sess: &Session) -> Span {
     }
 
     fn load_proc_macro(self, id: DefIndex, sess: &Session) -> SyntaxExtension {
+        let dylib = Some(self.proc_macro_dylib(id));
         let (name, kind, helper_attrs) = match *self.raw_proc_macro(id) {
             ProcMacro::CustomDerive { trait_name, attributes, client } => {
                 let helper_attrs =
                     attributes.iter().cloned().map(Symbol::intern).collect::<Vec<_>>();
-                (
-                    trait_name,
-                    SyntaxExtensionKind::Derive(Box::new(DeriveProcMacro { client })),
-                    helper_attrs,
-                )
-            }
-            ProcMacro::Attr { name, client } => {
-                (name, SyntaxExtensionKind::Attr(Box::new(AttrProcMacro { client })), Vec::new())
-            }
-            ProcMacro::Bang { name, client } => {
-                (name, SyntaxExtensionKind::Bang(Box::new(BangProcMacro { client })), Vec::new())
+                let crate_hash = self.root.hash();
+                let derive = DeriveProcMacro { client, dylib, crate_hash, name: trait_name };
+                (trait_name, SyntaxExtensionKind::Derive(Box::new(derive)), helper_attrs)
             }
+            ProcMacro::Attr { name, client } => (
+                name,
+                SyntaxExtensionKind::Attr(Box::new(AttrProcMacro { client, dylib })),
+                Vec::new(),
+            ),
+            ProcMacro::Bang { name, client } => (
+                name,
+                SyntaxExtensionKind::Bang(Box::new(BangProcMacro { client, dylib })),
+                Vec::new(),
+            ),
         };
 
         let attrs: Vec<_> = self.get_item_attrs(id, sess).collect();
@@ -1667,6 +1681,7 @@ pub(crate) fn new(
         blob: MetadataBlob,
         root: CrateRoot,
         raw_proc_macros: Option<&'static [ProcMacro]>,
+        proc_macro_dylib: Option<(PathBuf, String)>,
         cnum: CrateNum,
         cnum_map: CrateNumMap,
         dep_kind: CrateDepKind,
@@ -1693,6 +1708,8 @@ pub(crate) fn new(
             trait_impls,
             incoherent_impls: Default::default(),
             raw_proc_macros,
+            proc_macro_process: proc_macro_dylib
+                .map(|(path, symbol)| Lrc::new(ProcMacroProcess::new(path, symbol))),
             source_map_import_info: Lock::new(Vec::new()),
             def_path_hash_map,
             expn_hash_map: Default::default(),
@@ -1,5 +1,6 @@
 use rustc_macros::Diagnostic;
 use rustc_span::Span;
+use std::path::Path;
 
 use crate::ty::Ty;
 
@@ -48,3 +49,32 @@ pub struct LimitInvalid<'a> {
     pub value_span: Span,
     pub error_str: &'a str,
 }
+
+#[derive(Diagnostic)]
+#[diag(middle::type_size_limit_file_unreadable)]
+pub struct TypeSizeLimitFileUnreadable<'a> {
+    pub path: &'a Path,
+    pub error: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(middle::type_size_limit_file_invalid)]
+#[note]
+pub struct TypeSizeLimitFileInvalid<'a> {
+    pub path: &'a Path,
+    pub line: usize,
+}
+
+#[derive(Diagnostic)]
+#[diag(middle::type_size_limit_exceeded)]
+#[note]
+#[note(middle::layout_note)]
+pub struct TypeSizeLimitExceeded<'a> {
+    #[primary_span]
+    pub span: Span,
+    pub ty: &'a str,
+    pub size: u64,
+    pub limit: u64,
+    pub path: &'a Path,
+    pub layout: String,
+}
@@ -29,6 +29,10 @@ pub enum LintLevelSource {
     /// The provided `Level` is the level specified on the command line.
     /// (The actual level may be lower due to `--cap-lints`.)
     CommandLine(Symbol, Level),
+
+    /// Lint level was set by the `--lint-config` file, for the provided lint or
+    /// lint group, at the provided line of the file.
+    LintConfig(Symbol, u32),
 }
 
 impl LintLevelSource {
@@ -37,6 +41,7 @@ pub fn name(&self) -> Symbol {
             LintLevelSource::Default => symbol::kw::Default,
             LintLevelSource::Node(name, _, _) => name,
             LintLevelSource::CommandLine(name, _) => name,
+            LintLevelSource::LintConfig(name, _) => name,
         }
     }
 
@@ -45,6 +50,7 @@ pub fn span(&self) -> Span {
             LintLevelSource::Default => DUMMY_SP,
             LintLevelSource::Node(_, span, _) => span,
             LintLevelSource::CommandLine(_, _) => DUMMY_SP,
+            LintLevelSource::LintConfig(_, _) => DUMMY_SP,
         }
     }
 }
@@ -261,6 +267,20 @@ pub fn explain_lint_level_source(
                 ));
             }
         }
+        LintLevelSource::LintConfig(lint_config_name, line) => {
+            let level_str = level.as_str();
+            if lint_config_name.as_str() == name {
+                err.note_once(&format!(
+                    "`{}({})` set by lint-config at line {}",
+                    level_str, name, line
+                ));
+            } else {
+                err.note_once(&format!(
+                    "`{}({})` implied by `{}`, set by lint-config at line {}",
+                    level_str, name, lint_config_name, line
+                ));
+            }
+        }
         LintLevelSource::Node(lint_attr_name, src, reason) => {
             if let Some(rationale) = reason {
                 err.note(rationale.as_str());
@@ -121,6 +121,12 @@ fn phase_change(&self) -> Option<MirPhase> {
         None
     }
 
+    /// Returns `true` if this pass has to be applied whenever it is enabled, e.g. because
+    /// codegen relies on it, so that `-Z mir-opt-bisect-limit` never skips it.
+    fn is_required(&self) -> bool {
+        false
+    }
+
     fn is_mir_dump_enabled(&self) -> bool {
         true
     }
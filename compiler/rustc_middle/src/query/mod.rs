@@ -1314,6 +1314,14 @@
         remap_env_constness
     }
 
+    /// The size limits of `-Z type-size-limit-file` in bytes, by the name of the
+    /// type as printed by `-Z print-type-sizes`.
+    query type_size_limits(_: ()) -> rustc_data_structures::fx::FxHashMap<String, u64> {
+        arena_cache
+        eval_always
+        desc { "loading the type size limits" }
+    }
+
     /// Compute a `FnAbi` suitable for indirect calls, i.e. to `fn` pointers.
     ///
     /// NB: this doesn't handle virtual calls - those should use `fn_abi_of_instance`
@@ -1856,6 +1856,10 @@ pub struct ImplicitCtxt<'a, 'tcx> {
         /// This is updated by `JobOwner::start` in `ty::query::plumbing` when executing a query.
         pub diagnostics: Option<&'a Lock<ThinVec<Diagnostic>>>,
 
+        /// Whether the current query job recomputes the result of a green query, the
+        /// diagnostics of which were already replayed from the previous session.
+        pub diagnostics_replayed: bool,
+
         /// Used to prevent queries from calling too deeply.
         pub query_depth: usize,
 
@@ -1871,6 +1875,7 @@ pub fn new(gcx: &'tcx GlobalCtxt<'tcx>) -> Self {
                 tcx,
                 query: None,
                 diagnostics: None,
+                diagnostics_replayed: false,
                 query_depth: 0,
                 task_deps: TaskDepsRef::Ignore,
             }
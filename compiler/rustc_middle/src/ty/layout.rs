@@ -1,3 +1,4 @@
+use crate::error::{TypeSizeLimitExceeded, TypeSizeLimitFileInvalid, TypeSizeLimitFileUnreadable};
 use crate::middle::codegen_fn_attrs::CodegenFnAttrFlags;
 use crate::mir::{GeneratorLayout, GeneratorSavedLocal};
 use crate::ty::normalize_erasing_regions::NormalizationError;
@@ -7,12 +8,15 @@
 };
 use rustc_ast as ast;
 use rustc_attr as attr;
+use rustc_data_structures::fx::FxHashMap;
 use rustc_hir as hir;
 use rustc_hir::def_id::DefId;
 use rustc_hir::lang_items::LangItem;
 use rustc_index::bit_set::BitSet;
 use rustc_index::vec::{Idx, IndexVec};
-use rustc_session::{config::OptLevel, DataTypeKind, FieldInfo, SizeKind, VariantInfo};
+use rustc_session::{
+    config::OptLevel, DataTypeKind, FieldInfo, SizeKind, TypeSizeInfo, VariantInfo,
+};
 use rustc_span::symbol::Symbol;
 use rustc_span::{Span, DUMMY_SP};
 use rustc_target::abi::call::{
@@ -31,8 +35,13 @@
 use rand_xoshiro::Xoshiro128StarStar;
 
 pub fn provide(providers: &mut ty::query::Providers) {
-    *providers =
-        ty::query::Providers { layout_of, fn_abi_of_fn_ptr, fn_abi_of_instance, ..*providers };
+    *providers = ty::query::Providers {
+        layout_of,
+        type_size_limits,
+        fn_abi_of_fn_ptr,
+        fn_abi_of_instance,
+        ..*providers
+    };
 }
 
 pub trait IntegerExt {
@@ -262,6 +271,41 @@ fn layout_of<'tcx>(
     Ok(layout)
 }
 
+/// Loads the file given with `-Z type-size-limit-file`. Each of its lines sets the
+/// maximum size of a type in bytes as `<type> = <size>`, naming the type the way
+/// `-Z print-type-sizes` does. Empty lines and lines starting with `#` are ignored.
+fn type_size_limits(tcx: TyCtxt<'_>, (): ()) -> FxHashMap<String, u64> {
+    let mut limits = FxHashMap::default();
+    let Some(path) = &tcx.sess.opts.unstable_opts.type_size_limit_file else { return limits };
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) => {
+            tcx.sess.emit_err(TypeSizeLimitFileUnreadable { path, error: error.to_string() });
+            return limits;
+        }
+    };
+
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let limit = line
+            .rsplit_once('=')
+            .and_then(|(ty, limit)| Some((ty.trim(), limit.trim().parse().ok()?)))
+            .filter(|(ty, _)| !ty.is_empty());
+        match limit {
+            Some((ty, limit)) => {
+                limits.insert(ty.to_string(), limit);
+            }
+            None => {
+                tcx.sess.emit_err(TypeSizeLimitFileInvalid { path, line: i + 1 });
+            }
+        }
+    }
+    limits
+}
+
 #[derive(Clone, Copy)]
 pub struct LayoutCx<'tcx, C> {
     pub tcx: C,
@@ -1897,8 +1941,10 @@ fn generator_layout(
     #[inline(always)]
     fn record_layout_for_printing(&self, layout: TyAndLayout<'tcx>) {
         // If we are running with `-Zprint-type-sizes`, maybe record layouts
-        // for dumping later.
-        if self.tcx.sess.opts.unstable_opts.print_type_sizes {
+        // for dumping later. With `-Ztype-size-limit-file`, check them against
+        // their limits.
+        let opts = &self.tcx.sess.opts.unstable_opts;
+        if opts.print_type_sizes || opts.type_size_limit_file.is_some() {
             self.record_layout_for_printing_outlined(layout)
         }
     }
@@ -1914,7 +1960,7 @@ fn record_layout_for_printing_outlined(&self, layout: TyAndLayout<'tcx>) {
         // (delay format until we actually need it)
         let record = |kind, packed, opt_discr_size, variants| {
             let type_desc = format!("{:?}", layout.ty);
-            self.tcx.sess.code_stats.record_type_size(
+            let info = TypeSizeInfo::new(
                 kind,
                 type_desc,
                 layout.align.abi,
@@ -1923,6 +1969,10 @@ fn record_layout_for_printing_outlined(&self, layout: TyAndLayout<'tcx>) {
                 opt_discr_size,
                 variants,
             );
+            self.check_type_size_limit(layout, &info);
+            if self.tcx.sess.opts.unstable_opts.print_type_sizes {
+                self.tcx.sess.code_stats.record_type_size(info);
+            }
         };
 
         let adt_def = match *layout.ty.kind() {
@@ -2029,6 +2079,32 @@ fn record_layout_for_printing_outlined(&self, layout: TyAndLayout<'tcx>) {
             }
         }
     }
+
+    /// Reports an error if `layout` is larger than the limit set for its type
+    /// with `-Ztype-size-limit-file`, showing where its bytes went.
+    fn check_type_size_limit(&self, layout: TyAndLayout<'tcx>, info: &TypeSizeInfo) {
+        let Some(path) = &self.tcx.sess.opts.unstable_opts.type_size_limit_file else { return };
+        let Some(&limit) = self.tcx.type_size_limits(()).get(&info.type_description) else {
+            return;
+        };
+        if info.overall_size <= limit {
+            return;
+        }
+
+        let def_id = match *layout.ty.kind() {
+            ty::Adt(adt_def, _) => adt_def.did(),
+            ty::Closure(def_id, _) => def_id,
+            _ => bug!("unexpected type with a recorded size: `{:?}`", layout.ty),
+        };
+        self.tcx.sess.emit_err(TypeSizeLimitExceeded {
+            span: self.tcx.def_span(def_id),
+            ty: &info.type_description,
+            size: info.overall_size,
+            limit,
+            path,
+            layout: info.layout_lines().iter().map(|line| format!("\n{line}")).collect(),
+        });
+    }
 }
 
 /// Type size "skeleton", i.e., the only information determining a type's size.
@@ -168,6 +168,12 @@ pub struct ResolverOutputs {
     /// exist under `std`. For example, wrote `str::from_utf8` instead of `std::str::from_utf8`.
     pub confused_type_with_std_module: FxHashMap<Span, Span>,
     pub registered_tools: RegisteredTools,
+    /// Names of the extern prelude that resolved paths start with, with the crates
+    /// they refer to and their spans. Only collected for `--emit=dep-usage`.
+    pub extern_prelude_uses: Vec<(Symbol, CrateNum, Span)>,
+    /// Macros of other crates that were invoked, with the spans of their paths.
+    /// Only collected for `--emit=dep-usage`.
+    pub extern_macro_uses: Vec<(DefId, Span)>,
 }
 
 /// Resolutions that should only be used for lowering.
@@ -34,6 +34,10 @@ impl<'tcx> MirPass<'tcx> for AddCallGuards {
     fn run_pass(&self, _tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
         self.add_call_guards(body);
     }
+
+    fn is_required(&self) -> bool {
+        true
+    }
 }
 
 impl AddCallGuards {
@@ -18,6 +18,11 @@ fn name(&self) -> Cow<'_, str> {
     }
 
     fn run_pass(&self, _tcx: TyCtxt<'tcx>, _body: &mut Body<'tcx>) {}
+
+    fn is_required(&self) -> bool {
+        // Keep the dumps when bisecting, this pass doesn't change the MIR anyway.
+        true
+    }
 }
 
 pub fn emit_mir(tcx: TyCtxt<'_>, outputs: &OutputFilenames) -> io::Result<()> {
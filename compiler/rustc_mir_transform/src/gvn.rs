@@ -0,0 +1,367 @@
+//! Global value numbering (GVN) replaces the computations whose result is already held by a
+//! local with a copy of that local.
+//!
+//! Values are only tracked for the locals that are assigned exactly once and never borrowed,
+//! since those hold the value of their assignment wherever they are initialized. The value of
+//! such a local is a symbolic expression of the values its assignment reads, so after
+//!
+//! ```text
+//! _3 = _1;
+//! _4 = Add(move _3, const 1_u32);
+//! _5 = _1;
+//! _6 = Add(move _5, const 1_u32);
+//! ```
+//!
+//! `_4` and `_6` have the same value, `Add(value of _1, 1)`, and `_6` can be assigned a copy of
+//! `_4` instead. This is only done when the assignment of `_4` dominates the computation, so
+//! that both read the same values, and when `_4` is still initialized, which the
+//! `AvailableLocals` analysis tracks across moves and storage statements.
+
+use rustc_data_structures::fx::{FxHashMap, FxIndexSet};
+use rustc_data_structures::graph::dominators::Dominators;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+use rustc_middle::mir::visit::{MutatingUseContext, NonMutatingUseContext, PlaceContext, Visitor};
+use rustc_middle::mir::*;
+use rustc_middle::ty::{Ty, TyCtxt};
+use rustc_mir_dataflow::lattice::Dual;
+use rustc_mir_dataflow::{Analysis, AnalysisDomain, CallReturnPlaces, GenKill, GenKillAnalysis};
+
+use crate::MirPass;
+
+pub struct GlobalValueNumbering;
+
+impl<'tcx> MirPass<'tcx> for GlobalValueNumbering {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.mir_opt_level() >= 2
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        let defs = ssa_definitions(body);
+        let mut numbering = ValueNumbering::new(body, &defs);
+        debug!(values = ?numbering.values);
+
+        // The locals holding each value, to replace computations of that value with.
+        let mut holders: FxHashMap<VnIndex, Vec<Local>> = FxHashMap::default();
+        for (local, value) in numbering.locals.iter_enumerated() {
+            if let Some(value) = value {
+                holders.entry(*value).or_default().push(local);
+            }
+        }
+
+        let dominators = body.basic_blocks.dominators();
+        let mut available = AvailableLocals { defs: &defs }
+            .into_engine(tcx, body)
+            .iterate_to_fixpoint()
+            .into_results_cursor(body);
+
+        // Only the reachable blocks have dominators, and the unreachable ones are removed later.
+        let mut replacements = Vec::new();
+        for (block, data) in traversal::reverse_postorder(body) {
+            for (statement_index, statement) in data.statements.iter().enumerate() {
+                let StatementKind::Assign(box (place, ref rvalue)) = statement.kind else {
+                    continue;
+                };
+                let Some(value) = numbering.computed_value(rvalue) else { continue };
+                let Some(holders) = holders.get(&value) else { continue };
+
+                let location = Location { block, statement_index };
+                available.seek_before_primary_effect(location);
+                let holder = holders.iter().copied().find(|&holder| {
+                    holder != place.local
+                        && defs[holder].unwrap().dominates(location, &dominators)
+                        && available.get().0.contains(holder)
+                });
+                if let Some(holder) = holder {
+                    debug!(?location, ?value, ?holder);
+                    replacements.push((location, holder));
+                }
+            }
+        }
+
+        let basic_blocks = body.basic_blocks.as_mut_preserves_cfg();
+        for (location, holder) in replacements {
+            let statement = &mut basic_blocks[location.block].statements[location.statement_index];
+            if let StatementKind::Assign(box (_, ref mut rvalue)) = statement.kind {
+                *rvalue = Rvalue::Use(Operand::Copy(holder.into()));
+            }
+        }
+    }
+}
+
+/// The only assignment of a local that is assigned exactly once.
+#[derive(Copy, Clone, Debug)]
+enum Def {
+    /// The local is an argument, that is never assigned in the body.
+    Argument,
+    /// The local is assigned by the statement or the call at this location.
+    At(Location),
+}
+
+impl Def {
+    /// Whether the assignment is executed before `location` on every path reaching it.
+    fn dominates(self, location: Location, dominators: &Dominators<BasicBlock>) -> bool {
+        match self {
+            Def::Argument => true,
+            Def::At(def) => def != location && def.dominates(location, dominators),
+        }
+    }
+}
+
+/// Finds the locals that are assigned exactly once and never borrowed, along with their
+/// assignment. The return place is left out, since it is read by the caller.
+fn ssa_definitions(body: &Body<'_>) -> IndexVec<Local, Option<Def>> {
+    let mut finder = SsaFinder {
+        defs: IndexVec::from_elem(None, &body.local_decls),
+        not_ssa: BitSet::new_empty(body.local_decls.len()),
+    };
+    for arg in body.args_iter() {
+        finder.defs[arg] = Some(Def::Argument);
+    }
+    finder.not_ssa.insert(RETURN_PLACE);
+    finder.visit_body(body);
+
+    let SsaFinder { mut defs, not_ssa } = finder;
+    for local in not_ssa.iter() {
+        defs[local] = None;
+    }
+    defs
+}
+
+struct SsaFinder {
+    defs: IndexVec<Local, Option<Def>>,
+    not_ssa: BitSet<Local>,
+}
+
+impl<'tcx> Visitor<'tcx> for SsaFinder {
+    fn visit_local(&mut self, local: Local, context: PlaceContext, location: Location) {
+        match context {
+            PlaceContext::MutatingUse(MutatingUseContext::Store | MutatingUseContext::Call) => {
+                if self.defs[local].is_some() {
+                    self.not_ssa.insert(local);
+                } else {
+                    self.defs[local] = Some(Def::At(location));
+                }
+            }
+            PlaceContext::MutatingUse(_)
+            | PlaceContext::NonMutatingUse(
+                NonMutatingUseContext::SharedBorrow
+                | NonMutatingUseContext::ShallowBorrow
+                | NonMutatingUseContext::UniqueBorrow
+                | NonMutatingUseContext::AddressOf,
+            ) => {
+                self.not_ssa.insert(local);
+            }
+            PlaceContext::NonMutatingUse(_) | PlaceContext::NonUse(_) => {}
+        }
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        if let Rvalue::Ref(_, _, place) | Rvalue::AddressOf(_, place) = rvalue
+            && !place.is_indirect()
+        {
+            // Borrowing a field borrows the local, but is only seen as a projection.
+            self.not_ssa.insert(place.local);
+        }
+        self.super_rvalue(rvalue, location);
+    }
+}
+
+rustc_index::newtype_index! {
+    struct VnIndex {
+        DEBUG_FORMAT = "_v{}"
+    }
+}
+
+/// A symbolic value, computed from the values of other locals.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+enum Value<'tcx> {
+    /// The value of a local that isn't computed from other values in the body, like the
+    /// arguments and the results of calls.
+    Opaque(Local),
+    Constant(ConstantKind<'tcx>),
+    Len(VnIndex),
+    Discriminant(VnIndex),
+    Cast(VnIndex, Ty<'tcx>),
+    NullaryOp(NullOp, Ty<'tcx>),
+    UnaryOp(UnOp, VnIndex),
+    BinaryOp(BinOp, VnIndex, VnIndex),
+    CheckedBinaryOp(BinOp, VnIndex, VnIndex),
+}
+
+struct ValueNumbering<'tcx> {
+    values: FxIndexSet<Value<'tcx>>,
+    /// The value of each local that is assigned once, once its assignment has been numbered.
+    locals: IndexVec<Local, Option<VnIndex>>,
+}
+
+impl<'tcx> ValueNumbering<'tcx> {
+    fn new(body: &Body<'tcx>, defs: &IndexVec<Local, Option<Def>>) -> Self {
+        let mut numbering = ValueNumbering {
+            values: FxIndexSet::default(),
+            locals: IndexVec::from_elem(None, &body.local_decls),
+        };
+        for arg in body.args_iter() {
+            if defs[arg].is_some() {
+                numbering.locals[arg] = Some(numbering.insert(Value::Opaque(arg)));
+            }
+        }
+
+        // The assignment of a local dominates its uses, so the values read by an assignment
+        // are numbered before it in reverse postorder.
+        for (block, data) in traversal::reverse_postorder(body) {
+            for statement in &data.statements {
+                if let StatementKind::Assign(box (place, ref rvalue)) = statement.kind
+                    && let Some(local) = place.as_local()
+                    && defs[local].is_some()
+                {
+                    let value = match rvalue {
+                        Rvalue::Use(operand) => numbering.operand_value(operand),
+                        _ => numbering.computed_value(rvalue),
+                    };
+                    let value = value.unwrap_or_else(|| numbering.insert(Value::Opaque(local)));
+                    numbering.locals[local] = Some(value);
+                }
+            }
+            if let TerminatorKind::Call { destination, .. } = data.terminator().kind
+                && let Some(local) = destination.as_local()
+                && let Some(Def::At(def)) = defs[local]
+                && def.block == block
+            {
+                numbering.locals[local] = Some(numbering.insert(Value::Opaque(local)));
+            }
+        }
+        numbering
+    }
+
+    fn insert(&mut self, value: Value<'tcx>) -> VnIndex {
+        let (index, _) = self.values.insert_full(value);
+        VnIndex::from_usize(index)
+    }
+
+    /// The value of a place that is a local with a value, or its pointee if `deref`.
+    fn place_value(&self, place: Place<'tcx>, deref: bool) -> Option<VnIndex> {
+        match place.projection[..] {
+            [] => self.locals[place.local],
+            [PlaceElem::Deref] if deref => self.locals[place.local],
+            _ => None,
+        }
+    }
+
+    fn operand_value(&mut self, operand: &Operand<'tcx>) -> Option<VnIndex> {
+        match operand {
+            Operand::Copy(place) | Operand::Move(place) => self.place_value(*place, false),
+            Operand::Constant(constant) => Some(self.insert(Value::Constant(constant.literal))),
+        }
+    }
+
+    /// The value computed by `rvalue`, if it is a side-effect free computation of values.
+    fn computed_value(&mut self, rvalue: &Rvalue<'tcx>) -> Option<VnIndex> {
+        let value = match *rvalue {
+            // The length of a slice behind a pointer is part of the pointer.
+            Rvalue::Len(place) => Value::Len(self.place_value(place, true)?),
+            Rvalue::Discriminant(place) => Value::Discriminant(self.place_value(place, false)?),
+            Rvalue::Cast(CastKind::Misc, ref operand, ty) => {
+                Value::Cast(self.operand_value(operand)?, ty)
+            }
+            Rvalue::NullaryOp(op, ty) => Value::NullaryOp(op, ty),
+            Rvalue::UnaryOp(op, ref operand) => Value::UnaryOp(op, self.operand_value(operand)?),
+            Rvalue::BinaryOp(op, box (ref lhs, ref rhs)) => {
+                Value::BinaryOp(op, self.operand_value(lhs)?, self.operand_value(rhs)?)
+            }
+            Rvalue::CheckedBinaryOp(op, box (ref lhs, ref rhs)) => {
+                Value::CheckedBinaryOp(op, self.operand_value(lhs)?, self.operand_value(rhs)?)
+            }
+            _ => return None,
+        };
+        Some(self.insert(value))
+    }
+}
+
+/// Computes the locals that are assigned exactly once and hold the value of their assignment:
+/// the assignment was executed on every path, and they were not moved out of nor marked as
+/// dead since.
+struct AvailableLocals<'a> {
+    defs: &'a IndexVec<Local, Option<Def>>,
+}
+
+impl<'tcx> AnalysisDomain<'tcx> for AvailableLocals<'_> {
+    /// Use set intersection as the join operator.
+    type Domain = Dual<BitSet<Local>>;
+
+    const NAME: &'static str = "available_locals";
+
+    fn bottom_value(&self, body: &Body<'tcx>) -> Self::Domain {
+        // bottom = available (initialize_start_block counters this at outset)
+        Dual(BitSet::new_filled(body.local_decls.len()))
+    }
+
+    fn initialize_start_block(&self, body: &Body<'tcx>, state: &mut Self::Domain) {
+        state.0.clear();
+        for arg in body.args_iter() {
+            if self.defs[arg].is_some() {
+                state.0.insert(arg);
+            }
+        }
+    }
+}
+
+impl<'tcx> GenKillAnalysis<'tcx> for AvailableLocals<'_> {
+    type Idx = Local;
+
+    fn statement_effect(
+        &self,
+        trans: &mut impl GenKill<Self::Idx>,
+        statement: &Statement<'tcx>,
+        location: Location,
+    ) {
+        MoveVisitor { trans }.visit_statement(statement, location);
+        match statement.kind {
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local) => {
+                trans.kill(local);
+            }
+            StatementKind::Assign(box (place, _)) => {
+                if let Some(local) = place.as_local() && self.defs[local].is_some() {
+                    trans.gen(local);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn terminator_effect(
+        &self,
+        trans: &mut impl GenKill<Self::Idx>,
+        terminator: &Terminator<'tcx>,
+        location: Location,
+    ) {
+        MoveVisitor { trans }.visit_terminator(terminator, location);
+    }
+
+    fn call_return_effect(
+        &self,
+        trans: &mut impl GenKill<Self::Idx>,
+        _block: BasicBlock,
+        return_places: CallReturnPlaces<'_, 'tcx>,
+    ) {
+        return_places.for_each(|place| {
+            if let Some(local) = place.as_local() && self.defs[local].is_some() {
+                trans.gen(local);
+            }
+        });
+    }
+}
+
+/// Kills the locals that are moved out of.
+struct MoveVisitor<'a, T> {
+    trans: &'a mut T,
+}
+
+impl<'tcx, T: GenKill<Local>> Visitor<'tcx> for MoveVisitor<'_, T> {
+    fn visit_operand(&mut self, operand: &Operand<'tcx>, location: Location) {
+        if let Operand::Move(place) = operand {
+            self.trans.kill(place.local);
+        }
+        self.super_operand(operand, location);
+    }
+}
@@ -0,0 +1,312 @@
+//! Jump threading redirects the predecessors of a `SwitchInt` to the target the switch
+//! would take when the value it switches on is already known along the edge they take.
+//!
+//! The value of the switched on place can be known from an assignment of a constant or
+//! `SetDiscriminant` in the predecessor, or from an earlier switch over the same value that
+//! the predecessor is only reachable from, like the repeated discriminant tests of `?` and
+//! iterator adapters:
+//!
+//! ```text
+//! bb0: {                                      bb0: {
+//!     _2 = discriminant(_1);                      _2 = discriminant(_1);
+//!     switchInt(move _2) -> [0: bb1, ...];        switchInt(move _2) -> [0: bb1, ...];
+//! }                                           }
+//! bb1: {                                      bb1: {
+//!     ...                                         ...
+//!     goto -> bb3;                     =>         goto -> bb4;
+//! }                                           }
+//! bb3: {                                      bb4: {
+//!     _5 = discriminant(_1);                      _5 = discriminant(_1);
+//!     switchInt(move _5) -> [0: bb5, ...];        goto -> bb5;
+//! }                                           }
+//! ```
+//!
+//! The statements of the switch block are copied into a new block for each redirected
+//! predecessor, which jumps to the known target instead of switching, so switch blocks with
+//! more than a few statements are not threaded. Only locals that are never borrowed are
+//! followed, so that the statements and terminators between the known value and the switch
+//! can be checked not to modify them.
+
+use rustc_index::bit_set::BitSet;
+use rustc_middle::mir::*;
+use rustc_middle::ty::{ParamEnv, TyCtxt};
+use rustc_mir_dataflow::impls::borrowed_locals;
+
+use crate::MirPass;
+
+/// The number of blocks to look through for the value of a switch.
+const MAX_DEPTH: usize = 8;
+
+/// The number of statements a switch block may have to be copied for each threaded
+/// predecessor.
+const MAX_COPIED_STATEMENTS: usize = 8;
+
+pub struct JumpThreading;
+
+impl<'tcx> MirPass<'tcx> for JumpThreading {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.mir_opt_level() >= 2
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        let param_env = tcx.param_env_reveal_all_normalized(body.source.def_id());
+        let finder = ThreadingFinder { tcx, param_env, body, borrowed: borrowed_locals(body) };
+        let opportunities = finder.find_opportunities();
+        if opportunities.is_empty() {
+            return;
+        }
+
+        let blocks = body.basic_blocks_mut();
+        for ThreadingOpportunity { predecessor, switch_block, target } in opportunities {
+            debug!(?predecessor, ?switch_block, ?target);
+            // The statements of the switch block still have to be executed, so they are
+            // copied into a new block, unless there are none.
+            let new_target = if blocks[switch_block].statements.is_empty() {
+                target
+            } else {
+                let switch_data = &blocks[switch_block];
+                let mut new_block = BasicBlockData::new(Some(Terminator {
+                    source_info: switch_data.terminator().source_info,
+                    kind: TerminatorKind::Goto { target },
+                }));
+                new_block.statements = switch_data.statements.clone();
+                blocks.push(new_block)
+            };
+
+            let terminator = blocks[predecessor].terminator_mut();
+            match terminator.kind {
+                TerminatorKind::Goto { ref mut target } => *target = new_target,
+                TerminatorKind::SwitchInt { ref mut targets, .. } => {
+                    for target in targets.all_targets_mut() {
+                        if *target == switch_block {
+                            *target = new_target;
+                        }
+                    }
+                }
+                _ => {
+                    bug!("unexpected terminator for a threaded predecessor: {:?}", terminator.kind)
+                }
+            }
+        }
+    }
+}
+
+/// The redirection of the edge from `predecessor` to `switch_block` to `target`.
+#[derive(Debug)]
+struct ThreadingOpportunity {
+    predecessor: BasicBlock,
+    switch_block: BasicBlock,
+    target: BasicBlock,
+}
+
+/// The value a switch depends on, at some point of the body.
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Tracked {
+    Value(Local),
+    Discriminant(Local),
+}
+
+impl Tracked {
+    fn local(self) -> Local {
+        match self {
+            Tracked::Value(local) | Tracked::Discriminant(local) => local,
+        }
+    }
+}
+
+/// The effect of a statement on a tracked value, when looking backwards from its use.
+enum Step {
+    /// Before the statement, the value is the one of this tracked value.
+    Tracked(Tracked),
+    /// The statement sets the value to these bits.
+    Known(u128),
+}
+
+struct ThreadingFinder<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: &'a Body<'tcx>,
+    /// The locals that may be modified behind a pointer, which are never tracked.
+    borrowed: BitSet<Local>,
+}
+
+impl<'tcx> ThreadingFinder<'_, 'tcx> {
+    fn find_opportunities(&self) -> Vec<ThreadingOpportunity> {
+        let predecessors = self.body.basic_blocks.predecessors();
+        let mut opportunities = Vec::new();
+        'blocks: for (switch_block, data) in self.body.basic_blocks.iter_enumerated() {
+            if data.is_cleanup || data.statements.len() > MAX_COPIED_STATEMENTS {
+                continue;
+            }
+            let TerminatorKind::SwitchInt { ref discr, ref targets, .. } = data.terminator().kind
+            else {
+                continue;
+            };
+            let Some(mut tracked) = discr.place().and_then(|place| self.trackable(place)) else {
+                continue;
+            };
+            // Find what the switch depends on at the start of its block. A switch on a value
+            // that is known in its own block is left to `SimplifyConstCondition`.
+            for statement in data.statements.iter().rev() {
+                match self.step_back(statement, tracked) {
+                    Some(Step::Tracked(new_tracked)) => tracked = new_tracked,
+                    Some(Step::Known(_)) | None => continue 'blocks,
+                }
+            }
+
+            for &predecessor in predecessors[switch_block].iter() {
+                let successors = self.body.basic_blocks[predecessor].terminator().successors();
+                if successors.filter(|&successor| successor == switch_block).count() != 1 {
+                    continue;
+                }
+                if let Some(value) = self.known_value(predecessor, switch_block, tracked) {
+                    opportunities.push(ThreadingOpportunity {
+                        predecessor,
+                        switch_block,
+                        target: targets.target_for_value(value),
+                    });
+                }
+            }
+        }
+        opportunities
+    }
+
+    /// Finds the value of `tracked` when going from `block` to `successor`, by looking back
+    /// through `block` and the chain of blocks that are the only predecessor of the next.
+    fn known_value(
+        &self,
+        mut block: BasicBlock,
+        mut successor: BasicBlock,
+        mut tracked: Tracked,
+    ) -> Option<u128> {
+        let predecessors = self.body.basic_blocks.predecessors();
+        let threadable = matches!(
+            self.body.basic_blocks[block].terminator().kind,
+            TerminatorKind::Goto { .. } | TerminatorKind::SwitchInt { .. }
+        );
+        if !threadable {
+            return None;
+        }
+
+        for _ in 0..MAX_DEPTH {
+            let data = &self.body.basic_blocks[block];
+            // The value a switch of `block` has when it goes to `successor`, if it is the only
+            // edge to `successor` with that value.
+            let mut edge = match data.terminator().kind {
+                TerminatorKind::SwitchInt { ref discr, ref targets, .. } => {
+                    let mut values = targets.iter().filter(|&(_, target)| target == successor);
+                    match (values.next(), values.next()) {
+                        (Some((value, _)), None) if targets.otherwise() != successor => discr
+                            .place()
+                            .and_then(|place| self.trackable(place))
+                            .map(|switched| (Tracked::Value(switched), value)),
+                        _ => None,
+                    }
+                }
+                TerminatorKind::Goto { .. }
+                | TerminatorKind::Assert { .. }
+                | TerminatorKind::FalseEdge { .. }
+                | TerminatorKind::FalseUnwind { .. } => None,
+                _ => return None,
+            };
+
+            for statement in data.statements.iter().rev() {
+                if let Some((switched, value)) = edge
+                    && switched == tracked
+                {
+                    return Some(value);
+                }
+                match self.step_back(statement, tracked)? {
+                    Step::Tracked(new_tracked) => tracked = new_tracked,
+                    Step::Known(value) => return Some(value),
+                }
+                edge =
+                    edge.and_then(|(switched, value)| match self.step_back(statement, switched) {
+                        Some(Step::Tracked(switched)) => Some((switched, value)),
+                        Some(Step::Known(_)) | None => None,
+                    });
+            }
+            if let Some((switched, value)) = edge
+                && switched == tracked
+            {
+                return Some(value);
+            }
+
+            // Along a chain of blocks with a single predecessor, the value at the end of the
+            // predecessor is the value at the start of the block.
+            let [predecessor] = predecessors[block][..] else { return None };
+            if self.body.basic_blocks[predecessor].is_cleanup {
+                return None;
+            }
+            successor = block;
+            block = predecessor;
+        }
+        None
+    }
+
+    fn trackable(&self, place: Place<'tcx>) -> Option<Local> {
+        place.as_local().filter(|&local| !self.borrowed.contains(local))
+    }
+
+    /// Finds what `tracked` is before `statement`, or returns `None` if it can't be followed.
+    fn step_back(&self, statement: &Statement<'tcx>, tracked: Tracked) -> Option<Step> {
+        let local = tracked.local();
+        match statement.kind {
+            StatementKind::Assign(box (place, ref rvalue))
+                if place.as_local() == Some(local) && matches!(tracked, Tracked::Value(_)) =>
+            {
+                match *rvalue {
+                    Rvalue::Use(Operand::Constant(ref constant)) => {
+                        let ty = self.body.local_decls[local].ty;
+                        constant
+                            .literal
+                            .try_eval_bits(self.tcx, self.param_env, ty)
+                            .map(Step::Known)
+                    }
+                    Rvalue::Use(Operand::Copy(place) | Operand::Move(place)) => {
+                        Some(Step::Tracked(Tracked::Value(self.trackable(place)?)))
+                    }
+                    Rvalue::Discriminant(place) => {
+                        Some(Step::Tracked(Tracked::Discriminant(self.trackable(place)?)))
+                    }
+                    _ => None,
+                }
+            }
+            StatementKind::SetDiscriminant { box place, variant_index }
+                if place.as_local() == Some(local)
+                    && matches!(tracked, Tracked::Discriminant(_)) =>
+            {
+                let ty = self.body.local_decls[local].ty;
+                let discr = ty.discriminant_for_variant(self.tcx, variant_index)?;
+                Some(Step::Known(discr.val))
+            }
+
+            // Any other write to the local, as a whole or partially.
+            StatementKind::Assign(box (place, _))
+            | StatementKind::SetDiscriminant { box place, .. }
+            | StatementKind::Deinit(box place)
+                if place.local == local =>
+            {
+                None
+            }
+            StatementKind::StorageLive(dead) | StatementKind::StorageDead(dead)
+                if dead == local =>
+            {
+                None
+            }
+
+            // The local isn't borrowed, so no other statement can modify it.
+            StatementKind::Assign(..)
+            | StatementKind::SetDiscriminant { .. }
+            | StatementKind::Deinit(_)
+            | StatementKind::StorageLive(_)
+            | StatementKind::StorageDead(_)
+            | StatementKind::FakeRead(_)
+            | StatementKind::Retag(..)
+            | StatementKind::AscribeUserType(..)
+            | StatementKind::Coverage(_)
+            | StatementKind::Intrinsic(_)
+            | StatementKind::Nop => Some(Step::Tracked(tracked)),
+        }
+    }
+}
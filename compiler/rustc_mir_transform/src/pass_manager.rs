@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 
 use rustc_middle::mir::{self, Body, MirPhase, RuntimePhase};
+use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::TyCtxt;
 use rustc_session::Session;
 
@@ -70,6 +71,10 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
     fn phase_change(&self) -> Option<MirPhase> {
         self.1.phase_change()
     }
+
+    fn is_required(&self) -> bool {
+        self.1.is_required()
+    }
 }
 
 /// Run the sequence of passes without validating the MIR after each pass. The MIR is still
@@ -112,8 +117,23 @@ fn run_passes_inner<'tcx>(
                 );
                 *polarity
             });
-        let is_enabled = overridden.unwrap_or_else(|| pass.is_enabled(&tcx.sess));
         let new_phase = pass.phase_change();
+        let mut is_enabled = overridden.unwrap_or_else(|| pass.is_enabled(&tcx.sess));
+        // Only the optional passes of the optimization pipeline are counted for
+        // `-Z mir-opt-bisect-limit`, the MIR can't be codegened without the others.
+        if is_enabled
+            && start_phase == MirPhase::Runtime(RuntimePhase::PostCleanup)
+            && new_phase.is_none()
+            && !pass.is_required()
+        {
+            is_enabled = tcx.sess.consider_mir_opt_bisect(|| {
+                let def_path = with_no_trimmed_paths!(tcx.def_path_str(body.source.def_id()));
+                match body.source.promoted {
+                    Some(promoted) => format!("`{}` to `{}::{:?}`", name, def_path, promoted),
+                    None => format!("`{}` to `{}`", name, def_path),
+                }
+            });
+        }
         let dump_enabled = (is_enabled && pass.is_mir_dump_enabled()) || new_phase.is_some();
         let validate = (validate && is_enabled)
             || new_phase == Some(MirPhase::Runtime(RuntimePhase::Optimized));
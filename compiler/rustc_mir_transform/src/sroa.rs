@@ -0,0 +1,180 @@
+//! Scalar replacement of aggregates (SROA) splits the locals of struct, tuple and closure
+//! type into a local for each of their fields.
+//!
+//! After deaggregation, such locals are often only accessed field by field, like the
+//! temporary `_3` in `_3 = (_1, _2); _4 = (_3.0: u32)`. Giving each field a local of its
+//! own turns these accesses into plain uses of scalar locals, which shrinks the stack
+//! frames of unoptimized builds and lets the other MIR optimizations see through them.
+//!
+//! A local can only be split when nothing depends on its fields being stored next to each
+//! other: it must not be used as a whole, e.g. moved, dropped or passed to a call, and
+//! neither it nor its fields may have their address taken. The arguments and the return
+//! place are never split, since their layout is part of the calling convention.
+
+use crate::MirPass;
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+use rustc_middle::mir::visit::{MutVisitor, PlaceContext, Visitor};
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_session::config::DebugInfo;
+
+pub struct ScalarReplacementOfAggregates;
+
+impl<'tcx> MirPass<'tcx> for ScalarReplacementOfAggregates {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        // This also runs for unoptimized builds, whose debuginfo is kept below.
+        sess.mir_opt_level() >= 1
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        // The debuginfo of a variable can only describe a single local, so with full
+        // debuginfo the variables aren't split, whatever the optimization level. Without it,
+        // the debuginfo of the variables that are split isn't emitted anyway and is dropped.
+        let keep_debuginfo = tcx.sess.opts.debuginfo == DebugInfo::Full;
+
+        let mut candidates = SplitCandidates {
+            escaping: BitSet::new_empty(body.local_decls.len()),
+            fields: FxIndexMap::default(),
+            keep_debuginfo,
+        };
+        candidates.escaping.insert_range(RETURN_PLACE..=Local::new(body.arg_count));
+        for (local, decl) in body.local_decls.iter_enumerated() {
+            let splittable = match decl.ty.kind() {
+                ty::Adt(adt_def, _) => adt_def.is_struct() && !adt_def.is_box(),
+                ty::Tuple(..) | ty::Closure(..) => true,
+                _ => false,
+            };
+            if !splittable {
+                candidates.escaping.insert(local);
+            }
+        }
+        candidates.visit_body(body);
+
+        let SplitCandidates { escaping, mut fields, .. } = candidates;
+        fields.retain(|&(local, _), _| !escaping.contains(local));
+        if fields.is_empty() {
+            return;
+        }
+        debug!(?fields);
+
+        // Create a local for each field that is used, in the scope of the split local.
+        let mut fragments = IndexVec::from_elem(Vec::new(), &body.local_decls);
+        let replacements: FxIndexMap<(Local, Field), Local> = fields
+            .into_iter()
+            .map(|((local, field), ty)| {
+                let source_info = body.local_decls[local].source_info;
+                let fragment = body.local_decls.push(LocalDecl::with_source_info(ty, source_info));
+                fragments[local].push(fragment);
+                ((local, field), fragment)
+            })
+            .collect();
+
+        // The storage statements and deinitialization of a split local apply to all its fields.
+        for data in body.basic_blocks.as_mut_preserves_cfg() {
+            data.expand_statements(|statement| {
+                let (local, kind): (_, fn(Local) -> StatementKind<'tcx>) = match statement.kind {
+                    StatementKind::StorageLive(local) => (local, StatementKind::StorageLive),
+                    StatementKind::StorageDead(local) => (local, StatementKind::StorageDead),
+                    StatementKind::Deinit(box place) if let Some(local) = place.as_local() => {
+                        (local, |fragment| StatementKind::Deinit(Box::new(fragment.into())))
+                    }
+                    _ => return None,
+                };
+                let fragments = fragments.get(local).filter(|fragments| !fragments.is_empty())?;
+                let source_info = statement.source_info;
+                let statements: Vec<_> = fragments
+                    .iter()
+                    .map(|&fragment| Statement { source_info, kind: kind(fragment) })
+                    .collect();
+                Some(statements.into_iter())
+            });
+        }
+
+        body.var_debug_info.retain(|var_debug_info| match var_debug_info.value {
+            VarDebugInfoContents::Place(place) => {
+                !(place.projection.is_empty() && !fragments[place.local].is_empty())
+            }
+            VarDebugInfoContents::Const(_) => true,
+        });
+
+        ReplacementVisitor { tcx, replacements: &replacements }.visit_body(body);
+    }
+}
+
+/// Finds the fields of the locals that are used, and the locals that can't be split.
+struct SplitCandidates<'tcx> {
+    /// The locals that are used as a whole or have their address taken.
+    escaping: BitSet<Local>,
+    /// The type of each field that is used.
+    fields: FxIndexMap<(Local, Field), Ty<'tcx>>,
+    keep_debuginfo: bool,
+}
+
+impl<'tcx> Visitor<'tcx> for SplitCandidates<'tcx> {
+    fn visit_local(&mut self, local: Local, _: PlaceContext, _: Location) {
+        // Any use that isn't through a field projection.
+        self.escaping.insert(local);
+    }
+
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
+        if let [PlaceElem::Field(field, ty), ..] = place.projection[..] {
+            self.fields.entry((place.local, field)).or_insert(ty);
+            // Still visit the locals used as indices.
+            self.visit_projection(place.as_ref(), context, location);
+        } else {
+            self.super_place(place, context, location);
+        }
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        if let Rvalue::Ref(_, _, place) | Rvalue::AddressOf(_, place) = rvalue
+            && !place.is_indirect()
+        {
+            // A pointer to a field can be offset to reach the other fields.
+            self.escaping.insert(place.local);
+        }
+        self.super_rvalue(rvalue, location);
+    }
+
+    fn visit_statement(&mut self, statement: &Statement<'tcx>, location: Location) {
+        match statement.kind {
+            // These are expanded to all the fields.
+            StatementKind::StorageLive(_) | StatementKind::StorageDead(_) => {}
+            StatementKind::Deinit(box place) if place.as_local().is_some() => {}
+            _ => self.super_statement(statement, location),
+        }
+    }
+
+    fn visit_var_debug_info(&mut self, var_debug_info: &VarDebugInfo<'tcx>) {
+        match var_debug_info.value {
+            VarDebugInfoContents::Place(place)
+                if place.projection.is_empty() && !self.keep_debuginfo => {}
+            _ => self.super_var_debug_info(var_debug_info),
+        }
+    }
+}
+
+/// Replaces the fields of the split locals with their new locals.
+struct ReplacementVisitor<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    replacements: &'a FxIndexMap<(Local, Field), Local>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for ReplacementVisitor<'_, 'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_place(&mut self, place: &mut Place<'tcx>, context: PlaceContext, location: Location) {
+        let projection = place.projection;
+        if let [PlaceElem::Field(field, _), ref rest @ ..] = projection[..]
+            && let Some(&local) = self.replacements.get(&(place.local, field))
+        {
+            *place = Place { local, projection: self.tcx.intern_place_elems(rest) };
+        } else {
+            self.super_place(place, context, location);
+        }
+    }
+}
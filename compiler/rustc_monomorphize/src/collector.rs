@@ -197,6 +197,8 @@
 use rustc_session::Limit;
 use rustc_span::source_map::{dummy_spanned, respan, Span, Spanned, DUMMY_SP};
 use rustc_target::abi::Size;
+use std::collections::hash_map::Entry;
+use std::collections::VecDeque;
 use std::iter;
 use std::ops::Range;
 use std::path::PathBuf;
@@ -342,7 +344,7 @@ pub fn collect_crate_mono_items(
         let inlining_map: MTRef<'_, _> = &mut inlining_map;
 
         tcx.sess.time("monomorphization_collector_graph_walk", || {
-            par_for_each_in(roots, |root| {
+            par_for_each_in(&roots, |&root| {
                 let mut recursion_depths = DefIdMap::default();
                 collect_items_rec(
                     tcx,
@@ -356,7 +358,13 @@ pub fn collect_crate_mono_items(
         });
     }
 
-    (visited.into_inner(), inlining_map.into_inner())
+    let (visited, inlining_map) = (visited.into_inner(), inlining_map.into_inner());
+
+    if tcx.sess.opts.unstable_opts.print_mono_bloat {
+        print_mono_bloat_report(tcx, &roots, &visited, &inlining_map);
+    }
+
+    (visited, inlining_map)
 }
 
 // Find all non-generic items by walking the HIR. These items serve as roots to
@@ -1451,3 +1459,72 @@ fn collect_const_value<'tcx>(
         _ => {}
     }
 }
+
+/// Prints the report of `-Z print-mono-bloat`: the instances of each generic
+/// function with their estimated size, and for each instance the chain of mono
+/// items through which it was collected, starting at a root.
+fn print_mono_bloat_report<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    roots: &[MonoItem<'tcx>],
+    items: &FxHashSet<MonoItem<'tcx>>,
+    inlining_map: &InliningMap<'tcx>,
+) {
+    // Find the shortest chain from a root to every item with a breadth-first
+    // search, recording for each item the item it was first reached from.
+    let mut collected_by: FxHashMap<MonoItem<'tcx>, Option<MonoItem<'tcx>>> =
+        roots.iter().map(|&root| (root, None)).collect();
+    let mut queue: VecDeque<_> = roots.iter().copied().collect();
+    while let Some(item) = queue.pop_front() {
+        let Some(range) = inlining_map.index.get(&item) else { continue };
+        for &target in &inlining_map.targets[range.clone()] {
+            if let Entry::Vacant(entry) = collected_by.entry(target) {
+                entry.insert(Some(item));
+                queue.push_back(target);
+            }
+        }
+    }
+
+    let usage_chain = |mut item: MonoItem<'tcx>| {
+        let mut chain = vec![item];
+        while let Some(&Some(user)) = collected_by.get(&item) {
+            chain.push(user);
+            item = user;
+        }
+        let chain: Vec<_> =
+            chain.iter().rev().map(|item| with_no_trimmed_paths!(format!("`{}`", item))).collect();
+        chain.join(" -> ")
+    };
+
+    let mut instances_by_def: FxHashMap<DefId, Vec<(String, usize, String)>> = Default::default();
+    for &item in items {
+        let MonoItem::Fn(instance) = item else { continue };
+        if !item.is_generic_fn() {
+            continue;
+        }
+        instances_by_def.entry(instance.def_id()).or_default().push((
+            with_no_trimmed_paths!(item.to_string()),
+            item.size_estimate(tcx),
+            usage_chain(item),
+        ));
+    }
+
+    let mut groups: Vec<_> = instances_by_def
+        .into_iter()
+        .map(|(def_id, mut instances)| {
+            instances.sort();
+            let size: usize = instances.iter().map(|(_, size, _)| size).sum();
+            (with_no_trimmed_paths!(tcx.def_path_str(def_id)), size, instances)
+        })
+        .collect();
+    // Largest total size first, then most instances, then by path.
+    groups.sort_by(|(path1, size1, instances1), (path2, size2, instances2)| {
+        (size2, instances2.len(), path1).cmp(&(size1, instances1.len(), path2))
+    });
+
+    for (path, size, instances) in groups {
+        println!("MONO_BLOAT `{}`: {} instances, estimated size {}", path, instances.len(), size);
+        for (instance, size, chain) in instances {
+            println!("MONO_BLOAT     `{}` (estimated size {}): {}", instance, size, chain);
+        }
+    }
+}
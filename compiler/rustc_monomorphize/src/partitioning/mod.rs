@@ -339,6 +339,35 @@ fn assert_symbols_are_distinct<'a, 'tcx, I>(tcx: TyCtxt<'tcx>, mono_items: I)
     }
 }
 
+/// Records the number of mono items of each item, and which items use the
+/// instances of generic functions, for `-Z item-compile-cost`.
+fn record_mono_item_costs<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    items: &FxHashSet<MonoItem<'tcx>>,
+    inlining_map: &InliningMap<'tcx>,
+) {
+    let def_id = |item: MonoItem<'tcx>| match item {
+        MonoItem::Fn(instance) => Some(instance.def_id()),
+        MonoItem::Static(def_id) => Some(def_id),
+        MonoItem::GlobalAsm(_) => None,
+    };
+
+    let costs = &tcx.sess.item_compile_costs;
+    for &item in items {
+        if let Some(def_id) = def_id(item) {
+            costs.record_mono_item(def_id);
+        }
+    }
+    inlining_map.iter_accesses(|accessor, accessees| {
+        let Some(instantiator) = def_id(accessor) else { return };
+        for &accessee in accessees.iter().filter(|accessee| accessee.is_generic_fn()) {
+            if let Some(def_id) = def_id(accessee) {
+                costs.record_instantiation(def_id, instantiator);
+            }
+        }
+    });
+}
+
 fn collect_and_partition_mono_items<'tcx>(
     tcx: TyCtxt<'tcx>,
     (): (),
@@ -375,6 +404,10 @@ fn collect_and_partition_mono_items<'tcx>(
 
     tcx.sess.abort_if_errors();
 
+    if tcx.sess.opts.unstable_opts.item_compile_cost.is_some() {
+        record_mono_item_costs(tcx, &items, &inlining_map);
+    }
+
     let (codegen_units, _) = tcx.sess.time("partition_and_assert_distinct_symbols", || {
         sync::join(
             || {
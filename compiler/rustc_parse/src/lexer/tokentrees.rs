@@ -3,11 +3,26 @@
 use rustc_ast::tokenstream::{DelimSpan, Spacing, TokenStream, TokenTree};
 use rustc_ast_pretty::pprust::token_to_string;
 use rustc_data_structures::fx::FxHashMap;
-use rustc_errors::{PErr, PResult};
+use rustc_errors::{Applicability, PErr, PResult};
+use rustc_session::parse::ParseSess;
 use rustc_span::Span;
+use std::vec;
+
+/// Where the `TokenTreesReader` gets its tokens from.
+enum TokenSource<'a> {
+    Lexer(StringReader<'a>),
+    /// Already lexed tokens, along with the virtual closing delimiters
+    /// inserted while recovering from unclosed delimiters.
+    Replay {
+        tokens: vec::IntoIter<(Token, Spacing)>,
+        eof: Token,
+        preceded_by_whitespace: bool,
+    },
+}
 
 pub(super) struct TokenTreesReader<'a> {
-    string_reader: StringReader<'a>,
+    sess: &'a ParseSess,
+    source: TokenSource<'a>,
     /// The "next" token, which has been obtained from the `StringReader` but
     /// not yet handled by the `TokenTreesReader`.
     token: Token,
@@ -24,14 +39,53 @@ pub(super) struct TokenTreesReader<'a> {
     /// Collect the spans of braces (Open, Close). Used only
     /// for detecting if blocks are empty and only braces.
     matching_block_spans: Vec<(Span, Span)>,
+    /// The errors for reaching EOF with unclosed delimiters, one per nesting
+    /// level. Emitted once we know whether we can recover from them.
+    eof_errs: Vec<PErr<'a>>,
 }
 
 impl<'a> TokenTreesReader<'a> {
     pub(super) fn parse_token_trees(
         string_reader: StringReader<'a>,
     ) -> (PResult<'a, TokenStream>, Vec<UnmatchedBrace>) {
-        let mut tt_reader = TokenTreesReader {
-            string_reader,
+        let sess = string_reader.sess;
+        let override_span = string_reader.override_span;
+        let mut tt_reader = TokenTreesReader::new(sess, TokenSource::Lexer(string_reader));
+        let res = tt_reader.parse_all_token_trees();
+
+        let mut eof_errs = std::mem::take(&mut tt_reader.eof_errs);
+        if !eof_errs.is_empty()
+            && override_span.is_none()
+            && let Ok(stream) = &res
+            && let Some((stream, insertions)) = tt_reader.recover_unclosed_delims(stream)
+        {
+            // All the errors are about the same EOF, keep the first one.
+            for err in eof_errs.drain(1..) {
+                err.cancel();
+            }
+            let mut err = eof_errs.pop().unwrap();
+            let msg = if insertions.len() == 1 {
+                "you might have meant to close the delimiter here"
+            } else {
+                "you might have meant to close the delimiters here"
+            };
+            err.multipart_suggestion(msg, insertions, Applicability::MaybeIncorrect);
+            err.emit();
+            // Like with unrecovered unclosed delimiters, this silences errors
+            // about a missing `main`, the file may be incomplete.
+            *sess.reached_eof.borrow_mut() = true;
+            return (Ok(stream), Vec::new());
+        }
+        for err in eof_errs {
+            err.emit();
+        }
+        (res, tt_reader.unmatched_braces)
+    }
+
+    fn new(sess: &'a ParseSess, source: TokenSource<'a>) -> TokenTreesReader<'a> {
+        TokenTreesReader {
+            sess,
+            source,
             token: Token::dummy(),
             open_braces: Vec::new(),
             unmatched_braces: Vec::new(),
@@ -39,14 +93,25 @@ pub(super) fn parse_token_trees(
             last_unclosed_found_span: None,
             last_delim_empty_block_spans: FxHashMap::default(),
             matching_block_spans: Vec::new(),
-        };
-        let res = tt_reader.parse_all_token_trees();
-        (res, tt_reader.unmatched_braces)
+            eof_errs: Vec::new(),
+        }
+    }
+
+    fn next_token(&mut self) -> (Token, bool) {
+        match &mut self.source {
+            TokenSource::Lexer(string_reader) => string_reader.next_token(),
+            TokenSource::Replay { tokens, eof, preceded_by_whitespace } => {
+                let (token, spacing) = tokens.next().unwrap_or((eof.clone(), Spacing::Alone));
+                let is_preceded_by_whitespace =
+                    std::mem::replace(preceded_by_whitespace, spacing == Spacing::Alone);
+                (token, is_preceded_by_whitespace)
+            }
+        }
     }
 
     // Parse a stream of tokens into a list of `TokenTree`s, up to an `Eof`.
     fn parse_all_token_trees(&mut self) -> PResult<'a, TokenStream> {
-        self.token = self.string_reader.next_token().0;
+        self.token = self.next_token().0;
         let mut buf = TokenStreamBuilder::default();
         loop {
             match self.token.kind {
@@ -66,7 +131,8 @@ fn parse_token_trees_until_close_delim(&mut self) -> TokenStream {
                 token::OpenDelim(delim) => buf.push(self.parse_token_tree_open_delim(delim)),
                 token::CloseDelim(..) => return buf.into_token_stream(),
                 token::Eof => {
-                    self.eof_err().emit();
+                    let err = self.eof_err();
+                    self.eof_errs.push(err);
                     return buf.into_token_stream();
                 }
                 _ => buf.push(self.parse_token_tree_non_delim_non_eof()),
@@ -76,7 +142,7 @@ fn parse_token_trees_until_close_delim(&mut self) -> TokenStream {
 
     fn eof_err(&mut self) -> PErr<'a> {
         let msg = "this file contains an unclosed delimiter";
-        let mut err = self.string_reader.sess.span_diagnostic.struct_span_err(self.token.span, msg);
+        let mut err = self.sess.span_diagnostic.struct_span_err(self.token.span, msg);
         for &(_, sp) in &self.open_braces {
             err.span_label(sp, "unclosed delimiter");
             self.unmatched_braces.push(UnmatchedBrace {
@@ -91,7 +157,7 @@ fn eof_err(&mut self) -> PErr<'a> {
         if let Some((delim, _)) = self.open_braces.last() {
             if let Some((_, open_sp, close_sp)) =
                 self.matching_delim_spans.iter().find(|(d, open_sp, close_sp)| {
-                    let sm = self.string_reader.sess.source_map();
+                    let sm = self.sess.source_map();
                     if let Some(close_padding) = sm.span_to_margin(*close_sp) {
                         if let Some(open_padding) = sm.span_to_margin(*open_sp) {
                             return delim == d && close_padding != open_padding;
@@ -115,7 +181,7 @@ fn parse_token_tree_open_delim(&mut self, open_delim: Delimiter) -> TokenTree {
 
         // Move past the open delimiter.
         self.open_braces.push((open_delim, self.token.span));
-        self.token = self.string_reader.next_token().0;
+        self.token = self.next_token().0;
 
         // Parse the token trees within the delimiters.
         // We stop at any delimiter so we can try to recover if the user
@@ -133,7 +199,7 @@ fn parse_token_tree_open_delim(&mut self, open_delim: Delimiter) -> TokenTree {
 
                 if tts.is_empty() {
                     let empty_block_span = open_brace_span.to(close_brace_span);
-                    let sm = self.string_reader.sess.source_map();
+                    let sm = self.sess.source_map();
                     if !sm.is_multiline(empty_block_span) {
                         // Only track if the block is in the form of `{}`, otherwise it is
                         // likely that it was written on purpose.
@@ -154,7 +220,7 @@ fn parse_token_tree_open_delim(&mut self, open_delim: Delimiter) -> TokenTree {
                     self.matching_delim_spans.push((open_brace, open_brace_span, close_brace_span));
                 }
                 // Move past the closing delimiter.
-                self.token = self.string_reader.next_token().0;
+                self.token = self.next_token().0;
             }
             // Incorrect delimiter.
             token::CloseDelim(close_delim) => {
@@ -170,7 +236,7 @@ fn parse_token_tree_open_delim(&mut self, open_delim: Delimiter) -> TokenTree {
                     if let Some(&(_, sp)) = self.open_braces.last() {
                         unclosed_delimiter = Some(sp);
                     };
-                    let sm = self.string_reader.sess.source_map();
+                    let sm = self.sess.source_map();
                     if let Some(current_padding) = sm.span_to_margin(self.token.span) {
                         for (brace, brace_span) in &self.open_braces {
                             if let Some(padding) = sm.span_to_margin(*brace_span) {
@@ -201,7 +267,7 @@ fn parse_token_tree_open_delim(&mut self, open_delim: Delimiter) -> TokenTree {
                 //     bar(baz(
                 // }  // Incorrect delimiter but matches the earlier `{`
                 if !self.open_braces.iter().any(|&(b, _)| b == close_delim) {
-                    self.token = self.string_reader.next_token().0;
+                    self.token = self.next_token().0;
                 }
             }
             token::Eof => {
@@ -220,8 +286,7 @@ fn close_delim_err(&mut self, delim: Delimiter) -> PErr<'a> {
         // matching opening delimiter).
         let token_str = token_to_string(&self.token);
         let msg = format!("unexpected closing delimiter: `{}`", token_str);
-        let mut err =
-            self.string_reader.sess.span_diagnostic.struct_span_err(self.token.span, &msg);
+        let mut err = self.sess.span_diagnostic.struct_span_err(self.token.span, &msg);
 
         // Braces are added at the end, so the last element is the biggest block
         if let Some(parent) = self.matching_block_spans.last() {
@@ -243,12 +308,148 @@ fn close_delim_err(&mut self, delim: Delimiter) -> PErr<'a> {
         err
     }
 
+    /// Tries to find where the braces still open at EOF were meant to be
+    /// closed, going by indentation: a block likely ends before the first line
+    /// that is indented no more than the line opening it. Files whose blocks
+    /// aren't all indented are left alone. Returns the token
+    /// stream with the missing closing braces, along with where to insert them,
+    /// if the delimiters of the file all match with them.
+    fn recover_unclosed_delims(
+        &self,
+        stream: &TokenStream,
+    ) -> Option<(TokenStream, Vec<(Span, String)>)> {
+        // Mismatched closing delimiters are hard to guess right, leave them be.
+        if self.unmatched_braces.iter().any(|unmatched| unmatched.found_delim.is_some()) {
+            return None;
+        }
+        let eof = self.token.clone();
+        let mut tokens = Vec::new();
+        flatten_token_stream(stream, eof.span, &mut tokens);
+
+        // Indentation only tells where blocks end if the lines of the blocks
+        // are indented more than the lines opening them.
+        let sm = self.sess.source_map();
+        let indented = tokens.windows(2).all(|pair| {
+            let (open, next) = (&pair[0].0, &pair[1].0);
+            open.kind != token::OpenDelim(Delimiter::Brace)
+                || next.kind == token::CloseDelim(Delimiter::Brace)
+                || !sm.is_multiline(open.span.between(next.span))
+                || matches!(
+                    (sm.span_to_margin(open.span), sm.span_to_margin(next.span)),
+                    (Some(open_padding), Some(next_padding)) if next_padding > open_padding
+                )
+        });
+        if !indented {
+            return None;
+        }
+
+        let mut insertions: Vec<(Span, String)> = Vec::new();
+        let mut replay: Option<TokenTreesReader<'a>> = None;
+        for _ in 0..self.open_braces.len() {
+            let state = replay.as_ref().unwrap_or(self);
+            let (index, span, text) = state.infer_missing_close_brace(&tokens)?;
+            let close = Token::new(token::CloseDelim(Delimiter::Brace), span);
+            tokens.insert(index, (close, Spacing::Alone));
+            match insertions.iter_mut().find(|(sp, _)| *sp == span) {
+                Some((_, existing)) => existing.push_str(&text),
+                None => insertions.push((span, text)),
+            }
+
+            let source = TokenSource::Replay {
+                tokens: tokens.clone().into_iter(),
+                eof: eof.clone(),
+                preceded_by_whitespace: true,
+            };
+            let mut reader = TokenTreesReader::new(self.sess, source);
+            let res = reader.parse_all_token_trees();
+            let recovered = reader.eof_errs.is_empty() && reader.unmatched_braces.is_empty();
+            for err in reader.eof_errs.drain(..) {
+                err.cancel();
+            }
+            match res {
+                Ok(stream) if recovered => {
+                    insertions.sort_by_key(|(span, _)| span.lo());
+                    return Some((stream, insertions));
+                }
+                Ok(_) if reader.unmatched_braces.iter().all(|u| u.found_delim.is_none()) => {}
+                Ok(_) => return None,
+                Err(err) => {
+                    err.cancel();
+                    return None;
+                }
+            }
+            replay = Some(reader);
+        }
+        None
+    }
+
+    /// Picks the innermost brace that is either unclosed or closed by a `}`
+    /// less indented than itself, and guesses where its block ends. Returns
+    /// the index in `tokens` to insert its closing brace at, along with the
+    /// span and the text of the suggestion adding it.
+    fn infer_missing_close_brace(
+        &self,
+        tokens: &[(Token, Spacing)],
+    ) -> Option<(usize, Span, String)> {
+        let sm = self.sess.source_map();
+        let unclosed = self
+            .open_braces
+            .iter()
+            .filter(|&&(delim, _)| delim == Delimiter::Brace)
+            .map(|&(_, open_sp)| (open_sp, None));
+        let misindented = self
+            .matching_delim_spans
+            .iter()
+            .filter(|&&(delim, open_sp, close_sp)| {
+                // Empty closing spans are the braces inserted by the recovery.
+                delim == Delimiter::Brace
+                    && !close_sp.is_empty()
+                    && matches!(
+                        (sm.span_to_margin(open_sp), sm.span_to_margin(close_sp)),
+                        (Some(open_padding), Some(close_padding)) if close_padding < open_padding
+                    )
+            })
+            .map(|&(_, open_sp, close_sp)| (open_sp, Some(close_sp)));
+        let (open_sp, close_sp) =
+            unclosed.chain(misindented).max_by_key(|(open_sp, _)| open_sp.lo())?;
+
+        let open_index = tokens.iter().position(|(tok, _)| tok.span == open_sp)?;
+        // Only a brace ending its line tells where its block ends, e.g. not the
+        // one of `if x { foo();`.
+        if let Some((next, _)) = tokens.get(open_index + 1)
+            && !sm.is_multiline(open_sp.between(next.span))
+        {
+            return None;
+        }
+        let padding = sm.span_to_margin(open_sp)?;
+        let mut index = open_index + 1;
+        while let Some((tok, _)) = tokens.get(index) {
+            if Some(tok.span) == close_sp {
+                break;
+            }
+            let prev_sp = tokens[index - 1].0.span;
+            if sm.is_multiline(prev_sp.between(tok.span))
+                && sm.span_to_margin(tok.span).map_or(false, |margin| margin <= padding)
+            {
+                break;
+            }
+            index += 1;
+        }
+
+        // Close the block at the end of its last line, with the indentation
+        // of the line opening it.
+        let prev_sp = tokens[index - 1].0.span;
+        let span = sm.span_extend_to_next_char(prev_sp, '\n', false).shrink_to_hi();
+        let indent = sm.indentation_before(open_sp)?;
+        Some((index, span, format!("\n{indent}}}")))
+    }
+
     #[inline]
     fn parse_token_tree_non_delim_non_eof(&mut self) -> TokenTree {
         // `this_spacing` for the returned token refers to whether the token is
         // immediately followed by another op token. It is determined by the
         // next token: its kind and its `preceded_by_whitespace` status.
-        let (next_tok, is_next_tok_preceded_by_whitespace) = self.string_reader.next_token();
+        let (next_tok, is_next_tok_preceded_by_whitespace) = self.next_token();
         let this_spacing = if is_next_tok_preceded_by_whitespace || !next_tok.is_op() {
             Spacing::Alone
         } else {
@@ -259,6 +460,25 @@ fn parse_token_tree_non_delim_non_eof(&mut self) -> TokenTree {
     }
 }
 
+/// Flattens `stream` back into the tokens it was built from, except for the
+/// closing delimiters missing at EOF.
+fn flatten_token_stream(stream: &TokenStream, eof_span: Span, tokens: &mut Vec<(Token, Spacing)>) {
+    for tree in stream.trees() {
+        match tree {
+            TokenTree::Token(token, spacing) => tokens.push((token.clone(), *spacing)),
+            TokenTree::Delimited(delim_span, delim, tts) => {
+                let open = Token::new(token::OpenDelim(*delim), delim_span.open);
+                tokens.push((open, Spacing::Alone));
+                flatten_token_stream(tts, eof_span, tokens);
+                if delim_span.close != eof_span {
+                    let close = Token::new(token::CloseDelim(*delim), delim_span.close);
+                    tokens.push((close, Spacing::Alone));
+                }
+            }
+        }
+    }
+}
+
 #[derive(Default)]
 struct TokenStreamBuilder {
     buf: Vec<TokenTree>,
@@ -0,0 +1,330 @@
+//! Writes the `--emit=dep-usage` report, which lists what the crate uses from
+//! each of its dependencies, to find out what each of them is needed for.
+//!
+//! The items of other crates that paths, method calls and type-relative paths
+//! resolve to are gathered from the HIR and the typeck results. Macros are gone
+//! by then, so the resolver records their invocations, along with the names of
+//! crates that paths start with. Each use is attributed the `#[cfg]`s of the
+//! code containing it, by span, to tell apart the dependencies that are only
+//! used in tests or behind a `cfg`.
+
+use crate::errors::ErrorWritingDepUsage;
+
+use rustc_ast_pretty::pprust;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_hir as hir;
+use rustc_hir::def::{CtorOf, DefKind, Res};
+use rustc_hir::def_id::{CrateNum, DefId};
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_middle::ty::{self, DefIdTree, TyCtxt};
+use rustc_session::config::{ExternLocation, OutputFilenames, OutputType};
+use rustc_span::symbol::{sym, Symbol};
+use rustc_span::Span;
+use serde::Serialize;
+
+use std::collections::BTreeSet;
+use std::fs;
+
+/// The usage of a crate by the current crate.
+#[derive(Serialize)]
+struct CrateUsage {
+    #[serde(rename = "crate")]
+    name: String,
+    /// Whether the crate was passed with `--extern`.
+    #[serde(rename = "extern")]
+    is_extern: bool,
+    /// Whether a resolved path starts with the name of the crate, or an
+    /// `extern crate` item refers to it.
+    named: bool,
+    /// Whether the crate is named but no items of it are used, so that it can
+    /// only be needed for its trait impls.
+    impls_only: bool,
+    /// Whether macros are the only items of the crate that are used.
+    macros_only: bool,
+    /// Whether all the uses of the crate are in code gated by `#[cfg(test)]`.
+    tests_only: bool,
+    /// The `#[cfg]` predicates of the code using the crate if all of it is
+    /// gated by one, empty otherwise.
+    cfgs: BTreeSet<String>,
+    types: BTreeSet<String>,
+    traits: BTreeSet<String>,
+    macros: BTreeSet<String>,
+    values: BTreeSet<String>,
+    /// Whether a use of the crate isn't gated by a `#[cfg]`.
+    #[serde(skip)]
+    ungated: bool,
+    /// Whether a use of the crate isn't gated by `#[cfg(test)]`.
+    #[serde(skip)]
+    untested: bool,
+}
+
+impl CrateUsage {
+    fn new(name: String, is_extern: bool) -> Self {
+        CrateUsage {
+            name,
+            is_extern,
+            named: false,
+            impls_only: false,
+            macros_only: false,
+            tests_only: false,
+            cfgs: Default::default(),
+            types: Default::default(),
+            traits: Default::default(),
+            macros: Default::default(),
+            values: Default::default(),
+            ungated: false,
+            untested: false,
+        }
+    }
+}
+
+enum UsageKind {
+    Type,
+    Trait,
+    Macro,
+    Value,
+}
+
+/// Returns the item of another crate that a use of `def_id` is reported as,
+/// with the list of the report it belongs to.
+fn usage_item(tcx: TyCtxt<'_>, def_id: DefId) -> Option<(DefId, UsageKind)> {
+    if def_id.is_local() {
+        return None;
+    }
+    let kind = match tcx.def_kind(def_id) {
+        // Report the struct or variant rather than its constructor.
+        DefKind::Ctor(CtorOf::Struct | CtorOf::Variant, _) => {
+            return Some((tcx.parent(def_id), UsageKind::Type));
+        }
+        DefKind::Struct
+        | DefKind::Union
+        | DefKind::Enum
+        | DefKind::Variant
+        | DefKind::TyAlias
+        | DefKind::ForeignTy
+        | DefKind::AssocTy => UsageKind::Type,
+        DefKind::Trait | DefKind::TraitAlias => UsageKind::Trait,
+        DefKind::Macro(_) => UsageKind::Macro,
+        DefKind::Fn
+        | DefKind::Const
+        | DefKind::Static(_)
+        | DefKind::AssocFn
+        | DefKind::AssocConst => UsageKind::Value,
+        _ => return None,
+    };
+    Some((def_id, kind))
+}
+
+/// Returns the path of `def_id` in the crate defining it, even if it is used
+/// through a reexport. Items of inherent impls are prefixed with the type they
+/// are implemented for.
+fn extern_def_path_str(tcx: TyCtxt<'_>, def_id: DefId) -> String {
+    if let Some(parent) = tcx.opt_parent(def_id)
+        && tcx.def_kind(parent) == DefKind::Impl
+        && tcx.impl_trait_ref(parent).is_none()
+        && let Some(adt) = tcx.type_of(parent).ty_adt_def()
+    {
+        return format!("{}::{}", extern_def_path_str(tcx, adt.did()), tcx.item_name(def_id));
+    }
+    format!("{}{}", tcx.crate_name(def_id.krate), tcx.def_path(def_id).to_string_no_crate_verbose())
+}
+
+/// Returns the spans of the code gated by a `#[cfg]`, with its predicate.
+fn gated_spans(tcx: TyCtxt<'_>) -> Vec<(Span, String)> {
+    let crate_items = tcx.hir_crate_items(());
+    let owners = crate_items
+        .items()
+        .map(|id| id.def_id)
+        .chain(crate_items.trait_items().map(|id| id.def_id))
+        .chain(crate_items.impl_items().map(|id| id.def_id))
+        .chain(crate_items.foreign_items().map(|id| id.def_id));
+    let mut gated = Vec::new();
+    for owner in owners {
+        for &(local_id, attrs) in tcx.hir_attrs(owner).map.iter() {
+            let hir_id = hir::HirId { owner, local_id };
+            for attr in attrs.iter().filter(|attr| attr.has_name(sym::cfg)) {
+                let Some([predicate]) = attr.meta_item_list().as_deref() else { continue };
+                let predicate = pprust::meta_list_item_to_string(predicate);
+                // The span of an out-of-line module is the one of its declaration.
+                if let Some(hir::Node::Item(hir::Item {
+                    kind: hir::ItemKind::Mod(module), ..
+                })) = tcx.hir().find(hir_id)
+                {
+                    gated.push((module.spans.inner_span, predicate.clone()));
+                }
+                gated.push((tcx.hir().span(hir_id), predicate));
+            }
+        }
+    }
+    gated
+}
+
+struct DepUsageCollector<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    maybe_typeck_results: Option<&'tcx ty::TypeckResults<'tcx>>,
+    /// The names of the crates passed with `--extern`.
+    externs: FxHashSet<Symbol>,
+    /// The spans of the code gated by a `#[cfg]`, with its predicate.
+    gated: Vec<(Span, String)>,
+    usages: FxHashMap<CrateNum, CrateUsage>,
+}
+
+impl<'tcx> DepUsageCollector<'tcx> {
+    /// Records a use of `cnum` at `span`, along with the `#[cfg]`s gating it.
+    fn record_use(&mut self, cnum: CrateNum, span: Span) -> &mut CrateUsage {
+        let span = span.source_callsite();
+        let cfgs: Vec<_> = self
+            .gated
+            .iter()
+            .filter(|(gated, _)| gated.contains(span))
+            .map(|(_, predicate)| predicate.clone())
+            .collect();
+
+        let tcx = self.tcx;
+        let externs = &self.externs;
+        let usage = self.usages.entry(cnum).or_insert_with(|| {
+            let name = tcx.crate_name(cnum);
+            CrateUsage::new(name.to_string(), externs.contains(&name))
+        });
+        usage.ungated |= cfgs.is_empty();
+        usage.untested |= !cfgs.iter().any(|predicate| predicate == "test");
+        usage.cfgs.extend(cfgs);
+        usage
+    }
+
+    fn record_name(&mut self, cnum: CrateNum, span: Span) {
+        self.record_use(cnum, span).named = true;
+    }
+
+    fn record_item(&mut self, def_id: DefId, span: Span) {
+        let Some((def_id, kind)) = usage_item(self.tcx, def_id) else { return };
+        let path = extern_def_path_str(self.tcx, def_id);
+        let usage = self.record_use(def_id.krate, span);
+        match kind {
+            UsageKind::Type => usage.types.insert(path),
+            UsageKind::Trait => usage.traits.insert(path),
+            UsageKind::Macro => usage.macros.insert(path),
+            UsageKind::Value => usage.values.insert(path),
+        };
+    }
+
+    fn record_res(&mut self, res: Res, span: Span) {
+        if let Res::Def(_, def_id) = res {
+            self.record_item(def_id, span);
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for DepUsageCollector<'tcx> {
+    fn visit_nested_body(&mut self, body: hir::BodyId) {
+        let old_maybe_typeck_results =
+            self.maybe_typeck_results.replace(self.tcx.typeck_body(body));
+        let body = self.tcx.hir().body(body);
+        self.visit_body(body);
+        self.maybe_typeck_results = old_maybe_typeck_results;
+    }
+
+    fn visit_item(&mut self, item: &'tcx hir::Item<'tcx>) {
+        if let hir::ItemKind::ExternCrate(_) = item.kind
+            && let Some(cnum) = self.tcx.extern_mod_stmt_cnum(item.def_id.def_id)
+        {
+            self.record_name(cnum, item.span);
+        }
+        intravisit::walk_item(self, item);
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr<'tcx>) {
+        if let hir::ExprKind::MethodCall(..) = expr.kind
+            && let Some(typeck_results) = self.maybe_typeck_results
+            && let Some(def_id) = typeck_results.type_dependent_def_id(expr.hir_id)
+        {
+            self.record_item(def_id, expr.span);
+        }
+        intravisit::walk_expr(self, expr);
+    }
+
+    fn visit_qpath(&mut self, qpath: &'tcx hir::QPath<'tcx>, id: hir::HirId, span: Span) {
+        if let hir::QPath::TypeRelative(..) = qpath
+            && let Some(typeck_results) = self.maybe_typeck_results
+        {
+            self.record_res(typeck_results.qpath_res(qpath, id), span);
+        }
+        intravisit::walk_qpath(self, qpath, id);
+    }
+
+    fn visit_path(&mut self, path: &'tcx hir::Path<'tcx>, _: hir::HirId) {
+        self.record_res(path.res, path.span);
+        intravisit::walk_path(self, path);
+    }
+}
+
+/// Writes the `--emit=dep-usage` report, if requested. It lists the crates
+/// passed with `--extern` and the crates the current crate uses items of.
+pub fn write_dep_usage(tcx: TyCtxt<'_>, outputs: &OutputFilenames) {
+    if !tcx.sess.opts.output_types.contains_key(&OutputType::DepUsage) {
+        return;
+    }
+
+    let externs: FxHashSet<Symbol> = tcx
+        .sess
+        .opts
+        .externs
+        .iter()
+        .filter(|(_, entry)| {
+            !matches!(entry.location, ExternLocation::FoundInLibrarySearchDirectories)
+        })
+        .map(|(name, _)| Symbol::intern(name))
+        .collect();
+    let mut collector = DepUsageCollector {
+        tcx,
+        maybe_typeck_results: None,
+        externs,
+        gated: gated_spans(tcx),
+        usages: Default::default(),
+    };
+
+    let resolutions = tcx.resolutions(());
+    for &(name, cnum, span) in &resolutions.extern_prelude_uses {
+        let is_extern = collector.externs.contains(&name);
+        let usage = collector.record_use(cnum, span);
+        usage.named = true;
+        usage.is_extern |= is_extern;
+    }
+    for &(def_id, span) in &resolutions.extern_macro_uses {
+        collector.record_item(def_id, span);
+    }
+    tcx.hir().visit_all_item_likes_in_crate(&mut collector);
+
+    let mut usages: Vec<_> = collector.usages.into_values().collect();
+    for usage in &mut usages {
+        let no_types_or_values =
+            usage.types.is_empty() && usage.traits.is_empty() && usage.values.is_empty();
+        usage.impls_only = usage.named && no_types_or_values && usage.macros.is_empty();
+        usage.macros_only = no_types_or_values && !usage.macros.is_empty();
+        usage.tests_only = !usage.untested;
+        if usage.ungated {
+            usage.cfgs.clear();
+        }
+    }
+    // `--extern` crates that were never loaded are unused.
+    for &name in &collector.externs {
+        if !usages.iter().any(|usage| usage.is_extern && usage.name == name.as_str())
+            && !resolutions.extern_prelude_uses.iter().any(|&(used, ..)| used == name)
+        {
+            usages.push(CrateUsage::new(name.to_string(), true));
+        }
+    }
+    usages.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let path = outputs.path(OutputType::DepUsage);
+    match fs::write(&path, serde_json::to_string_pretty(&usages).unwrap()) {
+        Ok(()) => {
+            if tcx.sess.opts.json_artifact_notifications {
+                tcx.sess.parse_sess.span_diagnostic.emit_artifact_notification(&path, "dep-usage");
+            }
+        }
+        Err(error) => {
+            tcx.sess.emit_fatal(ErrorWritingDepUsage { path: &path, error: error.to_string() })
+        }
+    }
+}
@@ -2,6 +2,8 @@
 use rustc_macros::{Diagnostic, LintDiagnostic, Subdiagnostic};
 use rustc_span::{Span, Symbol};
 
+use std::path::Path;
+
 #[derive(LintDiagnostic)]
 #[diag(passes::outer_crate_level_attr)]
 pub struct OuterCrateLevelAttr;
@@ -665,3 +667,47 @@ pub struct DeprecatedAnnotationHasNoEffect {
     #[suggestion(applicability = "machine-applicable", code = "")]
     pub span: Span,
 }
+
+#[derive(Diagnostic)]
+#[diag(passes::error_writing_dep_usage)]
+pub struct ErrorWritingDepUsage<'a> {
+    pub path: &'a Path,
+    pub error: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(passes::error_writing_used_items)]
+pub struct ErrorWritingUsedItems<'a> {
+    pub path: &'a Path,
+    pub error: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(passes::error_reading_used_items)]
+pub struct ErrorReadingUsedItems<'a> {
+    pub path: &'a Path,
+    pub error: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(passes::unused_pub_item)]
+#[note]
+pub struct UnusedPubItem<'a> {
+    #[primary_span]
+    pub span: Span,
+    pub descr: &'static str,
+    pub name: String,
+    pub dir: &'a Path,
+}
+
+#[derive(Diagnostic)]
+#[diag(passes::pub_item_only_used_locally)]
+#[note]
+#[help]
+pub struct PubItemOnlyUsedLocally<'a> {
+    #[primary_span]
+    pub span: Span,
+    pub descr: &'static str,
+    pub name: String,
+    pub dir: &'a Path,
+}
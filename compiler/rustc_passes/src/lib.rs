@@ -24,6 +24,7 @@
 mod check_const;
 pub mod dead;
 mod debugger_visualizer;
+pub mod dep_usage;
 mod diagnostic_items;
 pub mod entry;
 mod errors;
@@ -38,6 +39,7 @@
 mod reachable;
 pub mod stability;
 mod upvars;
+pub mod used_items;
 mod weak_lang_items;
 
 pub fn provide(providers: &mut Providers) {
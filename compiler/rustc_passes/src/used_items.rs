@@ -0,0 +1,308 @@
+//! Finds the public items of a library that none of the crates depending on it
+//! use, which `dead` can't do as it only sees a single crate.
+//!
+//! `--emit=used-items` writes the items of other crates that a crate uses to
+//! `<crate name>.used-items.json`. `-Z report-unused-pub-items=<dir>` then
+//! warns about the public items of a crate that none of the crates whose
+//! reports are in `<dir>` use, separately for the ones the crate itself uses.
+
+use crate::errors::{
+    ErrorReadingUsedItems, ErrorWritingUsedItems, PubItemOnlyUsedLocally, UnusedPubItem,
+};
+
+use rustc_data_structures::fx::{FxHashSet, FxIndexSet};
+use rustc_hir as hir;
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::{DefId, LOCAL_CRATE};
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_middle::ty::subst::GenericArgKind;
+use rustc_middle::ty::{self, DefIdTree, Ty, TyCtxt};
+use rustc_session::config::{CrateType, OutputFilenames, OutputType};
+use rustc_session::lint;
+use rustc_span::Span;
+use serde::{Deserialize, Serialize};
+
+use std::fs;
+use std::path::Path;
+
+#[derive(Serialize, Deserialize)]
+struct UsedItems {
+    #[serde(rename = "crate")]
+    krate: String,
+    items: Vec<UsedItem>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct UsedItem {
+    /// The `DefPathHash` of the item, which identifies it across crates.
+    hash: String,
+    path: String,
+}
+
+fn def_path_hash_str(tcx: TyCtxt<'_>, def_id: DefId) -> String {
+    let (hi, lo) = tcx.def_path_hash(def_id).0.as_value();
+    format!("{hi:016x}{lo:016x}")
+}
+
+/// Gathers the items that the paths and method calls of the crate resolve to,
+/// along with the items containing them, e.g. the enum of a variant, and the
+/// types of its expressions.
+struct UsedItemsVisitor<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    maybe_typeck_results: Option<&'tcx ty::TypeckResults<'tcx>>,
+    used: FxIndexSet<DefId>,
+}
+
+impl<'tcx> UsedItemsVisitor<'tcx> {
+    fn record(&mut self, owner: hir::OwnerId, def_id: DefId) {
+        // Items referring to themselves, e.g. recursive functions, or to the
+        // type they are implemented for, e.g. constructors, don't use them.
+        let self_ty = self
+            .tcx
+            .opt_parent(owner.to_def_id())
+            .filter(|&parent| self.tcx.def_kind(parent) == DefKind::Impl)
+            .and_then(|impl_| self.tcx.type_of(impl_).ty_adt_def())
+            .map(|adt| adt.did());
+        // An associated item uses the type it is implemented for, which may
+        // not be named anywhere, e.g. in `mylib::make().method()`.
+        if let Some(parent) = self.tcx.opt_parent(def_id)
+            && self.tcx.def_kind(parent) == DefKind::Impl
+            && let Some(adt) = self.tcx.type_of(parent).ty_adt_def()
+            && Some(adt.did()) != self_ty
+        {
+            self.record(owner, adt.did());
+        }
+        let mut def_id = Some(def_id);
+        while let Some(id) = def_id {
+            if id.is_crate_root() {
+                break;
+            }
+            if id != owner.to_def_id() && Some(id) != self_ty && !self.used.insert(id) {
+                break;
+            }
+            def_id = self.tcx.opt_parent(id);
+        }
+    }
+
+    fn record_res(&mut self, owner: hir::OwnerId, res: Res) {
+        if let Res::Def(_, def_id) = res {
+            self.record(owner, def_id);
+        }
+    }
+
+    /// Records the types `ty` is made of, which are used even when they are
+    /// only inferred.
+    fn record_ty(&mut self, owner: hir::OwnerId, ty: Ty<'tcx>) {
+        for arg in ty.walk() {
+            if let GenericArgKind::Type(ty) = arg.unpack()
+                && let ty::Adt(adt, _) = *ty.kind()
+            {
+                self.record(owner, adt.did());
+            }
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for UsedItemsVisitor<'tcx> {
+    fn visit_nested_body(&mut self, body: hir::BodyId) {
+        let old_maybe_typeck_results =
+            self.maybe_typeck_results.replace(self.tcx.typeck_body(body));
+        let body = self.tcx.hir().body(body);
+        self.visit_body(body);
+        self.maybe_typeck_results = old_maybe_typeck_results;
+    }
+
+    fn visit_item(&mut self, item: &'tcx hir::Item<'tcx>) {
+        match item.kind {
+            // The type an impl is for isn't used by the impl, but the trait is.
+            hir::ItemKind::Impl(ref impl_) => {
+                self.visit_generics(impl_.generics);
+                if let Some(ref trait_ref) = impl_.of_trait {
+                    self.visit_trait_ref(trait_ref);
+                }
+            }
+            _ => intravisit::walk_item(self, item),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr<'tcx>) {
+        if let Some(typeck_results) = self.maybe_typeck_results {
+            if let hir::ExprKind::MethodCall(..) = expr.kind
+                && let Some(def_id) = typeck_results.type_dependent_def_id(expr.hir_id)
+            {
+                self.record(expr.hir_id.owner, def_id);
+            }
+            if let Some(ty) = typeck_results.expr_ty_opt(expr) {
+                self.record_ty(expr.hir_id.owner, ty);
+            }
+        }
+        intravisit::walk_expr(self, expr);
+    }
+
+    fn visit_qpath(&mut self, qpath: &'tcx hir::QPath<'tcx>, id: hir::HirId, _: Span) {
+        if let hir::QPath::TypeRelative(..) = qpath
+            && let Some(typeck_results) = self.maybe_typeck_results
+        {
+            self.record_res(id.owner, typeck_results.qpath_res(qpath, id));
+        }
+        intravisit::walk_qpath(self, qpath, id);
+    }
+
+    fn visit_path(&mut self, path: &'tcx hir::Path<'tcx>, id: hir::HirId) {
+        self.record_res(id.owner, path.res);
+        intravisit::walk_path(self, path);
+    }
+}
+
+fn collect_used_items(tcx: TyCtxt<'_>) -> FxIndexSet<DefId> {
+    let mut visitor =
+        UsedItemsVisitor { tcx, maybe_typeck_results: None, used: Default::default() };
+    tcx.hir().visit_all_item_likes_in_crate(&mut visitor);
+    visitor.used
+}
+
+/// Returns the public items of the crate that `-Z report-unused-pub-items`
+/// checks: the items other crates may name, except trait impls and macros.
+fn pub_items(tcx: TyCtxt<'_>) -> Vec<DefId> {
+    let access_levels = tcx.privacy_access_levels(());
+    let crate_items = tcx.hir_crate_items(());
+    let items = crate_items.items().map(|id| id.def_id.def_id).filter(|&def_id| {
+        matches!(
+            tcx.def_kind(def_id),
+            DefKind::Fn
+                | DefKind::Const
+                | DefKind::Static(_)
+                | DefKind::Struct
+                | DefKind::Enum
+                | DefKind::Union
+                | DefKind::Trait
+                | DefKind::TyAlias
+        )
+    });
+    let inherent_impl_items =
+        crate_items.impl_items().map(|id| id.def_id.def_id).filter(|&def_id| {
+            matches!(tcx.def_kind(def_id), DefKind::AssocFn | DefKind::AssocConst)
+                && tcx.impl_trait_ref(tcx.parent(def_id.to_def_id())).is_none()
+        });
+    items
+        .chain(inherent_impl_items)
+        .filter(|&def_id| {
+            access_levels.is_exported(def_id)
+                && !tcx.def_span(def_id).from_expansion()
+                && !(matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::Static(_))
+                    && tcx.codegen_fn_attrs(def_id).contains_extern_indicator())
+        })
+        .map(|def_id| def_id.to_def_id())
+        .collect()
+}
+
+/// Reads the items used by the crates whose `--emit=used-items` reports are in
+/// `dir`, as their `DefPathHash`es.
+fn read_used_items(tcx: TyCtxt<'_>, dir: &Path) -> FxHashSet<String> {
+    let mut hashes = FxHashSet::default();
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) => {
+            tcx.sess.emit_fatal(ErrorReadingUsedItems { path: dir, error: error.to_string() })
+        }
+    };
+    for entry in entries {
+        let path = match entry {
+            Ok(entry) => entry.path(),
+            Err(error) => {
+                tcx.sess.emit_fatal(ErrorReadingUsedItems { path: dir, error: error.to_string() })
+            }
+        };
+        let is_report = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map_or(false, |name| name.ends_with(".used-items.json"));
+        if !is_report {
+            continue;
+        }
+        let report =
+            fs::read_to_string(&path).map_err(|error| error.to_string()).and_then(|report| {
+                serde_json::from_str::<UsedItems>(&report).map_err(|error| error.to_string())
+            });
+        match report {
+            Ok(report) => hashes.extend(report.items.into_iter().map(|item| item.hash)),
+            Err(error) => tcx.sess.emit_fatal(ErrorReadingUsedItems { path: &path, error }),
+        }
+    }
+    hashes
+}
+
+fn write_used_items(tcx: TyCtxt<'_>, used: &FxIndexSet<DefId>, outputs: &OutputFilenames) {
+    let mut items: Vec<_> = used
+        .iter()
+        .filter(|def_id| !def_id.is_local())
+        .map(|&def_id| UsedItem {
+            hash: def_path_hash_str(tcx, def_id),
+            path: tcx.def_path_str(def_id),
+        })
+        .collect();
+    items.sort_by(|a, b| (&a.path, &a.hash).cmp(&(&b.path, &b.hash)));
+    let report = UsedItems { krate: tcx.crate_name(LOCAL_CRATE).to_string(), items };
+
+    let path = outputs.path(OutputType::UsedItems);
+    match fs::write(&path, serde_json::to_string_pretty(&report).unwrap()) {
+        Ok(()) => {
+            if tcx.sess.opts.json_artifact_notifications {
+                tcx.sess.parse_sess.span_diagnostic.emit_artifact_notification(&path, "used-items");
+            }
+        }
+        Err(error) => {
+            tcx.sess.emit_fatal(ErrorWritingUsedItems { path: &path, error: error.to_string() })
+        }
+    }
+}
+
+fn report_unused_pub_items(tcx: TyCtxt<'_>, used: &FxIndexSet<DefId>, dir: &Path) {
+    // Only libraries have dependents.
+    if tcx
+        .sess
+        .crate_types()
+        .iter()
+        .all(|ty| matches!(ty, CrateType::Executable | CrateType::ProcMacro))
+    {
+        return;
+    }
+    let used_by_dependents = read_used_items(tcx, dir);
+    for def_id in pub_items(tcx) {
+        if used_by_dependents.contains(&def_path_hash_str(tcx, def_id)) {
+            continue;
+        }
+        // `#[allow(dead_code)]` also silences this report.
+        let hir_id = tcx.hir().local_def_id_to_hir_id(def_id.expect_local());
+        if tcx.lint_level_at_node(lint::builtin::DEAD_CODE, hir_id).0 == lint::Level::Allow {
+            continue;
+        }
+        let span = tcx.def_span(def_id);
+        let descr = tcx.def_kind(def_id).descr(def_id);
+        let name = tcx.def_path_str(def_id);
+        // The uses of the crate itself don't need the item to be public.
+        if used.contains(&def_id) {
+            tcx.sess.emit_warning(PubItemOnlyUsedLocally { span, descr, name, dir });
+        } else {
+            tcx.sess.emit_warning(UnusedPubItem { span, descr, name, dir });
+        }
+    }
+}
+
+/// Writes the `--emit=used-items` report and runs `-Z report-unused-pub-items`,
+/// whichever are requested.
+pub fn check_used_items(tcx: TyCtxt<'_>, outputs: &OutputFilenames) {
+    let emit = tcx.sess.opts.output_types.contains_key(&OutputType::UsedItems);
+    let report_dir = tcx.sess.opts.unstable_opts.report_unused_pub_items.as_deref();
+    if !emit && report_dir.is_none() {
+        return;
+    }
+
+    let used = collect_used_items(tcx);
+    if emit {
+        write_used_items(tcx, &used, outputs);
+    }
+    if let Some(dir) = report_dir {
+        report_unused_pub_items(tcx, &used, dir);
+    }
+}
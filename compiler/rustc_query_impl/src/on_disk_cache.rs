@@ -272,19 +272,23 @@ fn serialize<'tcx>(&self, tcx: TyCtxt<'tcx>, encoder: FileEncoder) -> FileEncode
                 QueryCtxt::from_tcx(tcx).encode_query_results(enc, qri);
             });
 
-            // Encode side effects.
-            let side_effects_index: EncodedDepNodeIndex = self
-                .current_side_effects
-                .borrow()
-                .iter()
-                .map(|(dep_node_index, side_effects)| {
-                    let pos = AbsoluteBytePos::new(encoder.position());
-                    let dep_node_index = SerializedDepNodeIndex::new(dep_node_index.index());
-                    encoder.encode_tagged(dep_node_index, side_effects);
+            // Encode side effects, in dep node order so that the cache file does not
+            // depend on hash map iteration order.
+            let side_effects_index: EncodedDepNodeIndex = {
+                let current_side_effects = self.current_side_effects.borrow();
+                let mut sorted_side_effects: Vec<_> = current_side_effects.iter().collect();
+                sorted_side_effects.sort_unstable_by_key(|&(dep_node_index, _)| *dep_node_index);
+                sorted_side_effects
+                    .into_iter()
+                    .map(|(dep_node_index, side_effects)| {
+                        let pos = AbsoluteBytePos::new(encoder.position());
+                        let dep_node_index = SerializedDepNodeIndex::new(dep_node_index.index());
+                        encoder.encode_tagged(dep_node_index, side_effects);
 
-                    (dep_node_index, pos)
-                })
-                .collect();
+                        (dep_node_index, pos)
+                    })
+                    .collect()
+            };
 
             let interpret_alloc_index = {
                 let mut interpret_alloc_index = Vec::new();
@@ -84,6 +84,11 @@ struct DepGraphData<K: DepKind> {
 
     processed_side_effects: Mutex<FxHashSet<DepNodeIndex>>,
 
+    /// The number of diagnostics that were loaded from the previous session and
+    /// re-emitted because the query that emitted them was marked green.
+    /// Reported by `-Z incremental-info`.
+    replayed_diagnostic_count: AtomicU64,
+
     /// When we load, there may be `.o` files, cached MIR, or other such
     /// things available to us. If we find that they are not dirty, we
     /// load the path to the file storing those work-products here into
@@ -155,6 +160,7 @@ pub fn new(
                 dep_node_debug: Default::default(),
                 current,
                 processed_side_effects: Default::default(),
+                replayed_diagnostic_count: AtomicU64::new(0),
                 previous: prev_graph,
                 colors,
                 debug_loaded_from_disk: Default::default(),
@@ -798,6 +804,8 @@ fn emit_side_effects<Ctxt: QueryContext<DepKind = K>>(
 
             let handle = tcx.dep_context().sess().diagnostic();
 
+            data.replayed_diagnostic_count
+                .fetch_add(side_effects.diagnostics.len() as u64, Relaxed);
             for mut diagnostic in side_effects.diagnostics {
                 handle.emit_diagnostic(&mut diagnostic);
             }
@@ -848,6 +856,7 @@ pub fn print_incremental_info(&self) {
             data.current.encoder.borrow().print_incremental_info(
                 data.current.total_read_count.load(Relaxed),
                 data.current.total_duplicate_read_count.load(Relaxed),
+                data.replayed_diagnostic_count.load(Relaxed),
             )
         }
     }
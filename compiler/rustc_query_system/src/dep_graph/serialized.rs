@@ -260,6 +260,7 @@ pub(crate) fn print_incremental_info(
         &self,
         total_read_count: u64,
         total_duplicate_read_count: u64,
+        replayed_diagnostic_count: u64,
     ) {
         let status = self.status.lock();
         if let Some(record_stats) = &status.stats {
@@ -276,6 +277,7 @@ pub(crate) fn print_incremental_info(
             eprintln!("[incremental]");
             eprintln!("[incremental] Total Node Count: {}", status.total_node_count);
             eprintln!("[incremental] Total Edge Count: {}", status.total_edge_count);
+            eprintln!("[incremental] Replayed Diagnostics: {}", replayed_diagnostic_count);
 
             if cfg!(debug_assertions) {
                 eprintln!("[incremental] Total Edge Reads: {}", total_read_count);
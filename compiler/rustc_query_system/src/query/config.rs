@@ -21,6 +21,7 @@ pub trait QueryConfig {
 
 #[derive(Copy, Clone)]
 pub struct QueryVTable<CTX: QueryContext, K, V> {
+    pub name: &'static str,
     pub anon: bool,
     pub dep_kind: CTX::DepKind,
     pub eval_always: bool,
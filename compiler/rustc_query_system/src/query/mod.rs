@@ -114,12 +114,15 @@ fn store_side_effects_for_anon_node(
 
     /// Executes a job by changing the `ImplicitCtxt` to point to the
     /// new query job while it executes. It returns the diagnostics
-    /// captured during execution and the actual result.
+    /// captured during execution and the actual result. If
+    /// `diagnostics_replayed` is set, the diagnostics emitted by the job
+    /// were already replayed from the previous session and are dropped.
     fn start_query<R>(
         &self,
         token: QueryJobId,
         depth_limit: bool,
         diagnostics: Option<&Lock<ThinVec<Diagnostic>>>,
+        diagnostics_replayed: bool,
         compute: impl FnOnce() -> R,
     ) -> R;
 
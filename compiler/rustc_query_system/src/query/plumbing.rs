@@ -411,10 +411,12 @@ fn execute_job<CTX, K, V>(
     // Fast path for when incr. comp. is off.
     if !dep_graph.is_fully_enabled() {
         let prof_timer = tcx.dep_context().profiler().query_provider();
-        let result = tcx.start_query(job_id, query.depth_limit, None, || {
+        let query_timer = tcx.dep_context().profiler().time_passes_query(query.name);
+        let result = tcx.start_query(job_id, query.depth_limit, None, false, || {
             query.compute(*tcx.dep_context(), key)
         });
         let dep_node_index = dep_graph.next_virtual_depnode_index();
+        query_timer.finish();
         prof_timer.finish_with_query_invocation_id(dep_node_index.into());
         return (result, dep_node_index);
     }
@@ -426,18 +428,19 @@ fn execute_job<CTX, K, V>(
 
         // The diagnostics for this query will be promoted to the current session during
         // `try_mark_green()`, so we can ignore them here.
-        if let Some(ret) = tcx.start_query(job_id, false, None, || {
-            try_load_from_disk_and_cache_in_memory(tcx, &key, &dep_node, query)
+        if let Some(ret) = tcx.start_query(job_id, false, None, false, || {
+            try_load_from_disk_and_cache_in_memory(tcx, &key, &dep_node, query, job_id)
         }) {
             return ret;
         }
     }
 
     let prof_timer = tcx.dep_context().profiler().query_provider();
+    let query_timer = tcx.dep_context().profiler().time_passes_query(query.name);
     let diagnostics = Lock::new(ThinVec::new());
 
     let (result, dep_node_index) =
-        tcx.start_query(job_id, query.depth_limit, Some(&diagnostics), || {
+        tcx.start_query(job_id, query.depth_limit, Some(&diagnostics), false, || {
             if query.anon {
                 return dep_graph.with_anon_task(*tcx.dep_context(), query.dep_kind, || {
                     query.compute(*tcx.dep_context(), key)
@@ -451,6 +454,7 @@ fn execute_job<CTX, K, V>(
             dep_graph.with_task(dep_node, *tcx.dep_context(), key, query.compute, query.hash_result)
         });
 
+    query_timer.finish();
     prof_timer.finish_with_query_invocation_id(dep_node_index.into());
 
     let diagnostics = diagnostics.into_inner();
@@ -472,6 +476,7 @@ fn try_load_from_disk_and_cache_in_memory<CTX, K, V>(
     key: &K,
     dep_node: &DepNode<CTX::DepKind>,
     query: &QueryVTable<CTX, K, V>,
+    job_id: QueryJobId,
 ) -> Option<(V, DepNodeIndex)>
 where
     K: Clone,
@@ -540,10 +545,15 @@ fn try_load_from_disk_and_cache_in_memory<CTX, K, V>(
     // We could not load a result from the on-disk cache, so
     // recompute.
     let prof_timer = tcx.dep_context().profiler().query_provider();
+    let query_timer = tcx.dep_context().profiler().time_passes_query(query.name);
 
-    // The dep-graph for this computation is already in-place.
-    let result = dep_graph.with_ignore(|| query.compute(*tcx.dep_context(), key.clone()));
+    // The dep-graph for this computation is already in-place. The diagnostics of the
+    // query were replayed when it was marked green, so they must not be emitted again.
+    let result = tcx.start_query(job_id, false, None, true, || {
+        dep_graph.with_ignore(|| query.compute(*tcx.dep_context(), key.clone()))
+    });
 
+    query_timer.finish();
     prof_timer.finish_with_query_invocation_id(dep_node_index.into());
 
     // Verify that re-running the query produced a result with the expected hash
@@ -265,6 +265,12 @@ pub(crate) fn check_unused(&mut self, krate: &ast::Crate) {
         };
         visit::walk_crate(&mut visitor, krate);
 
+        let unused_ids: FxHashSet<ast::NodeId> = visitor
+            .unused_imports
+            .values()
+            .flat_map(|unused| unused.unused.iter().copied())
+            .collect();
+
         for unused in visitor.unused_imports.values() {
             let mut fixes = Vec::new();
             let mut spans = match calc_unused_spans(unused, unused.use_tree, unused.use_tree_id) {
@@ -346,5 +352,7 @@ pub(crate) fn check_unused(&mut self, krate: &ast::Crate) {
                 BuiltinLintDiagnostics::UnusedImports(fix_msg.into(), fixes, test_module_span),
             );
         }
+
+        visitor.r.check_import_organization(krate, &unused_ids);
     }
 }
@@ -45,6 +45,7 @@
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::{self, DefIdTree, MainDefinition, RegisteredTools, ResolverOutputs};
 use rustc_query_system::ich::StableHashingContext;
+use rustc_session::config::OutputType;
 use rustc_session::cstore::{CrateStore, CrateStoreDyn, MetadataLoaderDyn};
 use rustc_session::lint::LintBuffer;
 use rustc_session::Session;
@@ -76,6 +77,7 @@
 mod imports;
 mod late;
 mod macros;
+mod organize_imports;
 
 enum Weak {
     Yes,
@@ -896,6 +898,12 @@ pub struct Resolver<'a> {
 
     /// `CrateNum` resolutions of `extern crate` items.
     extern_crate_map: FxHashMap<LocalDefId, CrateNum>,
+    /// Names of the extern prelude that resolved paths start with, with the crates
+    /// they refer to and their spans. Only collected for `--emit=dep-usage`.
+    extern_prelude_uses: Vec<(Symbol, CrateNum, Span)>,
+    /// Macros of other crates that were invoked, with the spans of their paths.
+    /// Only collected for `--emit=dep-usage`.
+    extern_macro_uses: Vec<(DefId, Span)>,
     reexport_map: FxHashMap<LocalDefId, Vec<ModChild>>,
     trait_map: NodeMap<Vec<TraitCandidate>>,
 
@@ -1255,6 +1263,8 @@ pub fn new(
             lifetimes_res_map: Default::default(),
             extra_lifetime_params_map: Default::default(),
             extern_crate_map: Default::default(),
+            extern_prelude_uses: Vec::new(),
+            extern_macro_uses: Vec::new(),
             reexport_map: FxHashMap::default(),
             trait_map: NodeMap::default(),
             underscore_disambiguator: 0,
@@ -1412,6 +1422,8 @@ pub fn into_outputs(
             proc_macros,
             confused_type_with_std_module,
             registered_tools: self.registered_tools,
+            extern_prelude_uses: self.extern_prelude_uses,
+            extern_macro_uses: self.extern_macro_uses,
         };
         let resolutions_lowering = ty::ResolverAstLowering {
             legacy_const_generic_args: self.legacy_const_generic_args,
@@ -1456,6 +1468,8 @@ pub fn clone_outputs(
             confused_type_with_std_module: self.confused_type_with_std_module.clone(),
             registered_tools: self.registered_tools.clone(),
             access_levels: self.access_levels.clone(),
+            extern_prelude_uses: self.extern_prelude_uses.clone(),
+            extern_macro_uses: self.extern_macro_uses.clone(),
         };
         let resolutions_lowering = ty::ResolverAstLowering {
             legacy_const_generic_args: self.legacy_const_generic_args.clone(),
@@ -1841,6 +1855,9 @@ fn extern_prelude_get(&mut self, ident: Ident, finalize: bool) -> Option<&'a Nam
                 let crate_id = if finalize {
                     let Some(crate_id) =
                         self.crate_loader.process_path_extern(ident.name, ident.span) else { return Some(self.dummy_binding); };
+                    if self.session.opts.output_types.contains_key(&OutputType::DepUsage) {
+                        self.extern_prelude_uses.push((ident.name, crate_id, ident.span));
+                    }
                     crate_id
                 } else {
                     self.crate_loader.maybe_process_path_extern(ident.name)?
@@ -21,6 +21,7 @@
 use rustc_hir::def_id::{CrateNum, LocalDefId};
 use rustc_middle::middle::stability;
 use rustc_middle::ty::RegisteredTools;
+use rustc_session::config::OutputType;
 use rustc_session::lint::builtin::{LEGACY_DERIVE_HELPERS, SOFT_UNSTABLE};
 use rustc_session::lint::builtin::{UNUSED_MACROS, UNUSED_MACRO_RULES};
 use rustc_session::lint::BuiltinLintDiagnostics;
@@ -513,6 +514,8 @@ fn smart_resolve_macro_path(
                             "can't use a procedural macro from the same crate that defines it",
                         );
                     }
+                } else if self.session.opts.output_types.contains_key(&OutputType::DepUsage) {
+                    self.extern_macro_uses.push((def_id, path.span));
                 }
             }
             Res::NonMacroAttr(..) | Res::Err => {}
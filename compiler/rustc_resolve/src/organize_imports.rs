@@ -0,0 +1,261 @@
+//! The `unorganized_imports` lint, which suggests replacing the private `use`
+//! items of each module with one sorted `use` item per crate, without the
+//! imports that a glob import of the same module already provides, and
+//! optionally without the unused imports.
+
+use crate::Resolver;
+
+use rustc_ast as ast;
+use rustc_ast::node_id::NodeId;
+use rustc_ast::ptr::P;
+use rustc_data_structures::fx::FxHashSet;
+use rustc_session::lint::builtin::UNORGANIZED_IMPORTS;
+use rustc_session::lint::BuiltinLintDiagnostics;
+use rustc_span::source_map::SourceMap;
+use rustc_span::symbol::kw;
+use rustc_span::Span;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// An import of a `use` item: a path and how its last segment is imported.
+struct Leaf {
+    path: Vec<String>,
+    kind: LeafKind,
+    id: NodeId,
+}
+
+#[derive(PartialEq)]
+enum LeafKind {
+    /// `use path;`, `use path as rename;` or `use path::{self};`.
+    Simple(Option<String>),
+    /// `use path::*;`
+    Glob,
+}
+
+/// The imports of a module, merged by common prefixes.
+#[derive(Default)]
+struct ImportTree {
+    /// Whether the path of this tree is imported, e.g. `use a::b;`.
+    imported: bool,
+    /// The names the path of this tree is imported as, e.g. `use a::b as c;`.
+    renames: BTreeSet<String>,
+    /// Whether the path of this tree is imported with `self` in braces, which
+    /// only imports modules and types, e.g. `use a::b::{self};`, and the names
+    /// it is imported as that way.
+    self_imports: BTreeSet<Option<String>>,
+    glob: bool,
+    children: BTreeMap<String, ImportTree>,
+}
+
+impl ImportTree {
+    fn insert(&mut self, path: &[String], kind: &LeafKind) {
+        match (path, kind) {
+            ([], LeafKind::Glob) => self.glob = true,
+            ([], LeafKind::Simple(None)) => self.imported = true,
+            ([], LeafKind::Simple(Some(rename))) => {
+                self.renames.insert(rename.clone());
+            }
+            ([segment], LeafKind::Simple(rename)) if segment == "self" => {
+                self.self_imports.insert(rename.clone());
+            }
+            ([segment, rest @ ..], _) => {
+                self.children.entry(segment.clone()).or_default().insert(rest, kind)
+            }
+        }
+    }
+
+    /// Returns what follows the path of this tree in its imports, to be put in
+    /// braces if there are several of them.
+    fn entries(&self) -> Vec<String> {
+        let mut entries = Vec::new();
+        for rename in &self.self_imports {
+            entries.push(match rename {
+                Some(rename) => format!("self as {rename}"),
+                None => "self".to_string(),
+            });
+        }
+        if self.glob {
+            entries.push("*".to_string());
+        }
+        for (segment, child) in &self.children {
+            entries.extend(child.paths(segment));
+        }
+        entries
+    }
+
+    /// Returns the imports of this tree, whose path ends with `segment`.
+    fn paths(&self, segment: &str) -> Vec<String> {
+        let mut paths = Vec::new();
+        if self.imported {
+            paths.push(segment.to_string());
+        }
+        for rename in &self.renames {
+            paths.push(format!("{segment} as {rename}"));
+        }
+        let entries = self.entries();
+        match &entries[..] {
+            [] => {}
+            [entry] if entry != "self" && !entry.starts_with("self as ") => {
+                paths.push(format!("{segment}::{entry}"))
+            }
+            _ => paths.push(format!("{segment}::{{{}}}", entries.join(", "))),
+        }
+        paths
+    }
+}
+
+/// Pushes the imports of `tree` to `leaves`, with `prefix` prepended to their
+/// paths.
+fn flatten_use_tree(
+    tree: &ast::UseTree,
+    id: NodeId,
+    prefix: &mut Vec<String>,
+    leaves: &mut Vec<Leaf>,
+) {
+    let len = prefix.len();
+    prefix.extend(tree.prefix.segments.iter().map(|segment| {
+        // The segment of a leading `::`.
+        if segment.ident.name == kw::PathRoot { String::new() } else { segment.ident.to_string() }
+    }));
+    match tree.kind {
+        ast::UseTreeKind::Simple(rename, ..) => leaves.push(Leaf {
+            path: prefix.clone(),
+            kind: LeafKind::Simple(rename.map(|rename| rename.to_string())),
+            id,
+        }),
+        ast::UseTreeKind::Glob => {
+            leaves.push(Leaf { path: prefix.clone(), kind: LeafKind::Glob, id })
+        }
+        ast::UseTreeKind::Nested(ref trees) => {
+            for (tree, id) in trees {
+                flatten_use_tree(tree, *id, prefix, leaves);
+            }
+        }
+    }
+    prefix.truncate(len);
+}
+
+/// Returns the organized `use` items that import `leaves`.
+fn organize<'a>(leaves: impl Iterator<Item = &'a Leaf>) -> Vec<String> {
+    let leaves: Vec<_> = leaves.collect();
+
+    // An import of an item of a module that is also glob imported is
+    // redundant, unless another glob import may provide the same name.
+    let globs: Vec<_> = leaves.iter().filter(|leaf| leaf.kind == LeafKind::Glob).collect();
+    let redundant = |leaf: &Leaf| match &globs[..] {
+        [glob] => {
+            leaf.kind == LeafKind::Simple(None)
+                && leaf.path.len() == glob.path.len() + 1
+                && leaf.path.starts_with(&glob.path)
+                && leaf.path.last().map_or(false, |segment| segment != "self")
+        }
+        _ => false,
+    };
+
+    let mut tree = ImportTree::default();
+    for leaf in leaves.iter().filter(|leaf| !redundant(leaf)) {
+        tree.insert(&leaf.path, &leaf.kind);
+    }
+    tree.children
+        .iter()
+        .flat_map(|(segment, child)| child.paths(segment))
+        .map(|path| format!("use {path};"))
+        .collect()
+}
+
+/// Extends `span` to the whole lines it covers, if nothing else is on them.
+fn extend_to_lines(source_map: &SourceMap, span: Span) -> Span {
+    let file = source_map.lookup_source_file(span.lo());
+    let (Some(first), Some(last)) = (file.lookup_line(span.lo()), file.lookup_line(span.hi()))
+    else {
+        return span;
+    };
+    let lines = span.with_lo(file.line_bounds(first).start).with_hi(file.line_bounds(last).end);
+    let is_blank =
+        |span: Span| source_map.span_to_snippet(span).map_or(false, |s| s.trim().is_empty());
+    if is_blank(lines.with_hi(span.lo())) && is_blank(lines.with_lo(span.hi())) {
+        lines
+    } else {
+        span
+    }
+}
+
+impl Resolver<'_> {
+    /// Lints the `use` items of the modules of `krate` with `unorganized_imports`.
+    /// `unused` are the imports that are known to be unused.
+    pub(crate) fn check_import_organization(
+        &mut self,
+        krate: &ast::Crate,
+        unused: &FxHashSet<NodeId>,
+    ) {
+        // Imports that failed to resolve are neither used nor unused.
+        if self.session.has_errors().is_some() {
+            return;
+        }
+        self.check_module_imports(&krate.items, unused);
+    }
+
+    fn check_module_imports(&mut self, items: &[P<ast::Item>], unused: &FxHashSet<NodeId>) {
+        for item in items {
+            if let ast::ItemKind::Mod(_, ast::ModKind::Loaded(ref items, ..)) = item.kind {
+                self.check_module_imports(items, unused);
+            }
+        }
+
+        // Public imports are part of the interface of the module, and imports
+        // with attributes, e.g. `#[cfg]`, can't be merged with other ones.
+        let use_items: Vec<_> = items
+            .iter()
+            .filter(|item| {
+                matches!(item.kind, ast::ItemKind::Use(..))
+                    && matches!(item.vis.kind, ast::VisibilityKind::Inherited)
+                    && item.attrs.is_empty()
+                    && !item.span.is_dummy()
+                    && !item.span.from_expansion()
+            })
+            .collect();
+        let Some(first) = use_items.first() else { return };
+
+        let mut leaves = Vec::new();
+        for item in &use_items {
+            let ast::ItemKind::Use(ref tree) = item.kind else { unreachable!() };
+            flatten_use_tree(tree, item.id, &mut Vec::new(), &mut leaves);
+        }
+
+        // Unused imports are kept, they may be allowed on purpose or be used by
+        // `#[cfg(test)]` code, and `unused_imports` already lints them.
+        let organized = organize(leaves.iter());
+        let source_map = self.session.source_map();
+        let Ok(current) = use_items
+            .iter()
+            .map(|item| source_map.span_to_snippet(item.span))
+            .collect::<Result<Vec<_>, _>>()
+        else {
+            return;
+        };
+        if current == organized {
+            return;
+        }
+        let without_unused = organize(leaves.iter().filter(|leaf| !unused.contains(&leaf.id)));
+
+        let indentation = source_map.indentation_before(first.span).unwrap_or_default();
+        let replaces = |organized: Vec<String>| {
+            let mut replaces = vec![(first.span, organized.join(&format!("\n{indentation}")))];
+            if organized.is_empty() {
+                replaces[0].0 = extend_to_lines(source_map, first.span);
+            }
+            for item in &use_items[1..] {
+                replaces.push((extend_to_lines(source_map, item.span), String::new()));
+            }
+            replaces
+        };
+        let without_unused = (without_unused != organized).then(|| replaces(without_unused));
+        self.lint_buffer.buffer_lint_with_diagnostic(
+            UNORGANIZED_IMPORTS,
+            first.id,
+            first.span,
+            "imports are not organized",
+            BuiltinLintDiagnostics::UnorganizedImports(replaces(organized), without_unused),
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use rustc_data_structures::fx::FxIndexSet;
+use rustc_data_structures::sync::Lock;
+use rustc_span::source_map::SourceMap;
+use rustc_span::Span;
+use serde::Serialize;
+
+/// Where a `cfg` predicate was evaluated.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CfgEvalKind {
+    /// `#[cfg(..)]`
+    Cfg,
+    /// `#[cfg_attr(..)]`
+    CfgAttr,
+    /// `cfg!(..)`
+    CfgMacro,
+    /// `#[link(.., cfg(..))]`
+    Link,
+}
+
+#[derive(PartialEq, Eq, Hash)]
+struct CfgEval {
+    kind: CfgEvalKind,
+    predicate: String,
+    result: bool,
+    span: Span,
+}
+
+#[derive(Serialize)]
+struct CfgEvalRow<'a> {
+    kind: CfgEvalKind,
+    predicate: &'a str,
+    result: bool,
+    file: String,
+    line: usize,
+    column: usize,
+}
+
+/// The `cfg` predicates evaluated during the build, gathered for
+/// `-Z print-cfg-eval`.
+#[derive(Default)]
+pub struct CfgEvals {
+    /// The same predicate may be evaluated several times, e.g. when a derive
+    /// configures its input again, but is only reported once.
+    evals: Lock<FxIndexSet<CfgEval>>,
+}
+
+impl CfgEvals {
+    pub fn record(&self, kind: CfgEvalKind, predicate: String, result: bool, span: Span) {
+        self.evals.borrow_mut().insert(CfgEval { kind, predicate, result, span });
+    }
+
+    /// Prints the gathered evaluations as JSON, in the order they happened.
+    pub fn print(&self, source_map: &SourceMap) {
+        let evals = self.evals.borrow();
+        let rows: Vec<_> = evals
+            .iter()
+            .map(|eval| {
+                let loc = source_map.lookup_char_pos(eval.span.lo());
+                CfgEvalRow {
+                    kind: eval.kind,
+                    predicate: &eval.predicate,
+                    result: eval.result,
+                    file: source_map.filename_for_diagnostics(&loc.file.name).to_string(),
+                    line: loc.line,
+                    column: loc.col.0 + 1,
+                }
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&rows).unwrap());
+    }
+}
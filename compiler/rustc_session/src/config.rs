@@ -3,6 +3,7 @@
 
 pub use crate::options::*;
 
+use crate::config::lint_config::LintConfig;
 use crate::errors::TargetDataLayoutErrorsWrapper;
 use crate::search_paths::SearchPath;
 use crate::utils::{CanonicalizedPath, NativeLib, NativeLibKind};
@@ -37,6 +38,7 @@
 use std::path::{Path, PathBuf};
 use std::str::{self, FromStr};
 
+pub mod lint_config;
 pub mod sigpipe;
 
 /// The different settings that the `-C strip` flag can have.
@@ -287,6 +289,8 @@ pub enum OutputType {
     Object,
     Exe,
     DepInfo,
+    DepUsage,
+    UsedItems,
 }
 
 impl<HCX: HashStableContext> ToStableHashKey<HCX> for OutputType {
@@ -300,7 +304,11 @@ fn to_stable_hash_key(&self, _: &HCX) -> Self::KeyType {
 impl OutputType {
     fn is_compatible_with_codegen_units_and_single_output_file(&self) -> bool {
         match *self {
-            OutputType::Exe | OutputType::DepInfo | OutputType::Metadata => true,
+            OutputType::Exe
+            | OutputType::DepInfo
+            | OutputType::DepUsage
+            | OutputType::UsedItems
+            | OutputType::Metadata => true,
             OutputType::Bitcode
             | OutputType::Assembly
             | OutputType::LlvmAssembly
@@ -319,6 +327,8 @@ fn shorthand(&self) -> &'static str {
             OutputType::Metadata => "metadata",
             OutputType::Exe => "link",
             OutputType::DepInfo => "dep-info",
+            OutputType::DepUsage => "dep-usage",
+            OutputType::UsedItems => "used-items",
         }
     }
 
@@ -332,13 +342,15 @@ fn from_shorthand(shorthand: &str) -> Option<Self> {
             "metadata" => OutputType::Metadata,
             "link" => OutputType::Exe,
             "dep-info" => OutputType::DepInfo,
+            "dep-usage" => OutputType::DepUsage,
+            "used-items" => OutputType::UsedItems,
             _ => return None,
         })
     }
 
     fn shorthands_display() -> String {
         format!(
-            "`{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`",
+            "`{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`",
             OutputType::Bitcode.shorthand(),
             OutputType::Assembly.shorthand(),
             OutputType::LlvmAssembly.shorthand(),
@@ -347,6 +359,8 @@ fn shorthands_display() -> String {
             OutputType::Metadata.shorthand(),
             OutputType::Exe.shorthand(),
             OutputType::DepInfo.shorthand(),
+            OutputType::DepUsage.shorthand(),
+            OutputType::UsedItems.shorthand(),
         )
     }
 
@@ -359,6 +373,8 @@ pub fn extension(&self) -> &'static str {
             OutputType::Object => "o",
             OutputType::Metadata => "rmeta",
             OutputType::DepInfo => "d",
+            OutputType::DepUsage => "dep-usage.json",
+            OutputType::UsedItems => "used-items.json",
             OutputType::Exe => "",
         }
     }
@@ -377,6 +393,11 @@ pub enum ErrorOutputType {
         /// human output.
         json_rendered: HumanReadableErrorType,
     },
+    /// A single SARIF 2.1.0 log, consumed by code-scanning tools.
+    Sarif {
+        /// Render the SARIF log in a human readable way (with indents and newlines).
+        pretty: bool,
+    },
 }
 
 impl Default for ErrorOutputType {
@@ -438,7 +459,10 @@ pub fn should_codegen(&self) -> bool {
             | OutputType::Mir
             | OutputType::Object
             | OutputType::Exe => true,
-            OutputType::Metadata | OutputType::DepInfo => false,
+            OutputType::Metadata
+            | OutputType::DepInfo
+            | OutputType::DepUsage
+            | OutputType::UsedItems => false,
         })
     }
 
@@ -451,7 +475,9 @@ pub fn should_link(&self) -> bool {
             | OutputType::Mir
             | OutputType::Metadata
             | OutputType::Object
-            | OutputType::DepInfo => false,
+            | OutputType::DepInfo
+            | OutputType::DepUsage
+            | OutputType::UsedItems => false,
             OutputType::Exe => true,
         })
     }
@@ -549,6 +575,7 @@ pub enum PrintRequest {
     NativeStaticLibs,
     StackProtectorStrategies,
     LinkArgs,
+    PublicApi,
 }
 
 pub enum Input {
@@ -719,6 +746,7 @@ fn default() -> Options {
             debuginfo: DebugInfo::None,
             lint_opts: Vec::new(),
             lint_cap: None,
+            lint_config: None,
             describe_lints: false,
             output_types: OutputTypes(BTreeMap::new()),
             search_paths: vec![],
@@ -795,6 +823,7 @@ pub fn diagnostic_handler_flags(&self, can_emit_warnings: bool) -> HandlerFlags
             report_delayed_bugs: self.report_delayed_bugs,
             macro_backtrace: self.macro_backtrace,
             deduplicate_diagnostics: self.deduplicate_diagnostics,
+            collect_machine_applicable_suggestions: self.apply_suggestions.is_some(),
         }
     }
 }
@@ -1358,7 +1387,7 @@ pub fn rustc_short_optgroups() -> Vec<RustcOptGroup> {
             "[crate-name|file-names|sysroot|target-libdir|cfg|calling-conventions|\
              target-list|target-cpus|target-features|relocation-models|code-models|\
              tls-models|target-spec-json|native-static-libs|stack-protector-strategies|\
-             link-args]",
+             link-args|public-api]",
         ),
         opt::flagmulti_s("g", "", "Equivalent to -C debuginfo=2"),
         opt::flagmulti_s("O", "", "Equivalent to -C opt-level=2"),
@@ -1417,7 +1446,7 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             "",
             "error-format",
             "How errors and other messages are produced",
-            "human|json|short",
+            "human|json|short|sarif|pretty-sarif",
         ),
         opt::multi_s("", "json", "Configure the JSON output of the compiler", "CONFIG"),
         opt::opt_s(
@@ -1441,6 +1470,12 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             "Remap source names in all output (compiler messages and output files)",
             "FROM=TO",
         ),
+        opt::opt(
+            "",
+            "lint-config",
+            "Read lint levels, lint groups and per-path lint overrides from a TOML file",
+            "PATH",
+        ),
     ]);
     opts
 }
@@ -1477,6 +1512,16 @@ pub fn get_cmd_lint_options(
     (lint_opts, describe_lints, lint_cap)
 }
 
+/// Parses the file passed with `--lint-config`.
+pub fn get_lint_config(
+    matches: &getopts::Matches,
+    error_format: ErrorOutputType,
+) -> Option<LintConfig> {
+    matches.opt_str("lint-config").map(|path| {
+        LintConfig::load(Path::new(&path)).unwrap_or_else(|e| early_error(error_format, &e))
+    })
+}
+
 /// Parses the `--color` flag.
 pub fn parse_color(matches: &getopts::Matches) -> ColorConfig {
     match matches.opt_str("color").as_ref().map(|s| &s[..]) {
@@ -1597,13 +1642,15 @@ pub fn parse_error_format(
             }
             Some("json") => ErrorOutputType::Json { pretty: false, json_rendered },
             Some("pretty-json") => ErrorOutputType::Json { pretty: true, json_rendered },
+            Some("sarif") => ErrorOutputType::Sarif { pretty: false },
+            Some("pretty-sarif") => ErrorOutputType::Sarif { pretty: true },
             Some("short") => ErrorOutputType::HumanReadable(HumanReadableErrorType::Short(color)),
 
             Some(arg) => early_error(
                 ErrorOutputType::HumanReadable(HumanReadableErrorType::Default(color)),
                 &format!(
-                    "argument for `--error-format` must be `human`, `json` or \
-                     `short` (instead was `{arg}`)"
+                    "argument for `--error-format` must be `human`, `json`, \
+                     `short`, `sarif` or `pretty-sarif` (instead was `{arg}`)"
                 ),
             ),
         }
@@ -1680,6 +1727,12 @@ fn check_error_format_stability(
                 "`--error-format=human-annotate-rs` is unstable",
             );
         }
+        if let ErrorOutputType::Sarif { .. } = error_format {
+            early_error(
+                ErrorOutputType::Json { pretty: false, json_rendered },
+                "`--error-format=sarif` is unstable",
+            );
+        }
     }
 }
 
@@ -1705,6 +1758,17 @@ fn parse_output_types(
                         ),
                     )
                 });
+                if matches!(output_type, OutputType::DepUsage | OutputType::UsedItems)
+                    && !unstable_opts.unstable_options
+                {
+                    early_error(
+                        error_format,
+                        &format!(
+                            "the `-Z unstable-options` flag must also be passed to \
+                             enable the `{shorthand}` emission type",
+                        ),
+                    );
+                }
                 output_types.insert(output_type, path);
             }
         }
@@ -1816,6 +1880,17 @@ fn collect_print_requests(
             }
         }
         "link-args" => PrintRequest::LinkArgs,
+        "public-api" => {
+            if unstable_opts.unstable_options {
+                PrintRequest::PublicApi
+            } else {
+                early_error(
+                    error_format,
+                    "the `-Z unstable-options` flag must also be passed to \
+                     enable the public-api print option",
+                );
+            }
+        }
         req => early_error(error_format, &format!("unknown print request `{req}`")),
     }));
 
@@ -2236,6 +2311,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
 
     let mut unstable_opts = UnstableOptions::build(matches, error_format);
     let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
+    let lint_config = get_lint_config(matches, error_format);
 
     check_error_format_stability(&unstable_opts, error_format, json_rendered);
 
@@ -2280,6 +2356,21 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         }
     }
 
+    if unstable_opts.cache_proc_macros && incremental.is_none() {
+        early_warn(error_format, "`-Z cache-proc-macros` has no effect without `-C incremental`");
+    }
+
+    if (unstable_opts.proc_macro_time_limit.is_some()
+        || unstable_opts.proc_macro_memory_limit.is_some())
+        && unstable_opts.proc_macro_execution_strategy != ProcMacroExecutionStrategy::OutOfProcess
+    {
+        early_error(
+            error_format,
+            "options `-Z proc-macro-time-limit` and `-Z proc-macro-memory-limit` require \
+            `-Z proc-macro-execution-strategy=out-of-process`",
+        );
+    }
+
     if cg.profile_generate.enabled() && cg.profile_use.is_some() {
         early_error(
             error_format,
@@ -2473,6 +2564,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         debuginfo,
         lint_opts,
         lint_cap,
+        lint_config,
         describe_lints,
         output_types,
         search_paths,
@@ -2742,9 +2834,9 @@ pub fn needs_analysis(&self) -> bool {
 pub(crate) mod dep_tracking {
     use super::{
         BranchProtection, CFGuard, CFProtection, CrateType, DebugInfo, ErrorOutputType,
-        InstrumentCoverage, LdImpl, LinkerPluginLto, LocationDetail, LtoCli, OomStrategy, OptLevel,
-        OutputType, OutputTypes, Passes, SourceFileHashAlgorithm, SplitDwarfKind,
-        SwitchWithOptPath, SymbolManglingVersion, TrimmedDefPaths,
+        InstrumentCoverage, LdImpl, LinkerPluginLto, LintConfig, LocationDetail, LtoCli,
+        OomStrategy, OptLevel, OutputType, OutputTypes, Passes, SourceFileHashAlgorithm,
+        SplitDwarfKind, SwitchWithOptPath, SymbolManglingVersion, TrimmedDefPaths,
     };
     use crate::lint;
     use crate::options::WasiExecModel;
@@ -2844,6 +2936,7 @@ fn hash(
         BranchProtection,
         OomStrategy,
         LanguageIdentifier,
+        LintConfig,
     );
 
     impl<T1, T2> DepTrackingHash for (T1, T2)
@@ -2955,6 +3048,21 @@ pub fn should_panic(self) -> u8 {
     }
 }
 
+/// Where `-Z apply-suggestions` puts the sources patched with the machine-applicable
+/// suggestions of the emitted diagnostics.
+#[derive(Clone, PartialEq, Hash, Debug)]
+pub enum ApplySuggestions {
+    /// Overwrite the original source files.
+    InPlace,
+
+    /// Print a unified diff of the changes to stdout.
+    Diff,
+
+    /// Write the patched files into this directory, at their path relative to the
+    /// working directory.
+    Directory(PathBuf),
+}
+
 /// How to run proc-macro code when building this crate
 #[derive(Clone, Copy, PartialEq, Hash, Debug)]
 pub enum ProcMacroExecutionStrategy {
@@ -2963,4 +3071,8 @@ pub enum ProcMacroExecutionStrategy {
 
     /// Run the proc-macro code on a different thread.
     CrossThread,
+
+    /// Run the proc-macro code in a child process, which can be given time and
+    /// memory limits.
+    OutOfProcess,
 }
@@ -0,0 +1,212 @@
+//! The lint configuration file passed with `--lint-config`.
+//!
+//! It sets lint levels for the whole crate, defines lint groups that can be
+//! used in the file, and overrides lint levels for the source files matching
+//! a glob:
+//!
+//! ```toml
+//! [lints]
+//! unused_variables = "deny"
+//! "clippy::pedantic" = "warn"
+//!
+//! [groups]
+//! our_style = ["unused_imports", "unused_mut"]
+//!
+//! [[overrides]]
+//! paths = ["src/generated/**"]
+//! lints = { dead_code = "allow", our_style = "allow" }
+//! ```
+//!
+//! Paths in overrides are relative to the directory of the configuration file.
+//! A `*` matches any part of a path component, `**` matches any number of
+//! components and `?` matches a single character.
+
+use crate::lint::Level;
+
+use serde::Deserialize;
+use toml::Spanned;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// A parsed `--lint-config` file.
+#[derive(Clone, PartialEq, Hash, Debug)]
+pub struct LintConfig {
+    pub path: PathBuf,
+    /// The canonicalized directory of the file; paths in overrides are relative to it.
+    dir: PathBuf,
+    /// Lint levels for the whole crate, in the order they appear in the file.
+    pub lints: Vec<LintConfigLevel>,
+    pub groups: Vec<LintConfigGroup>,
+    pub overrides: Vec<LintConfigOverride>,
+}
+
+/// The level set for a lint, or for a lint group, by the lint configuration file.
+#[derive(Clone, PartialEq, Hash, Debug)]
+pub struct LintConfigLevel {
+    /// The name of the lint or lint group, with `-` replaced by `_`.
+    pub name: String,
+    pub level: Level,
+    /// The line of the file the level was set at, starting at 1.
+    pub line: u32,
+}
+
+/// A lint group defined by the lint configuration file.
+#[derive(Clone, PartialEq, Hash, Debug)]
+pub struct LintConfigGroup {
+    pub name: String,
+    pub lints: Vec<String>,
+    pub line: u32,
+}
+
+/// Lint levels for the source files matching any of `paths`.
+#[derive(Clone, PartialEq, Hash, Debug)]
+pub struct LintConfigOverride {
+    pub paths: Vec<String>,
+    pub lints: Vec<LintConfigLevel>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawLintConfig {
+    #[serde(default)]
+    lints: BTreeMap<String, Spanned<String>>,
+    #[serde(default)]
+    groups: BTreeMap<String, Spanned<Vec<String>>>,
+    #[serde(default)]
+    overrides: Vec<RawOverride>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawOverride {
+    paths: Vec<String>,
+    #[serde(default)]
+    lints: BTreeMap<String, Spanned<String>>,
+}
+
+impl LintConfig {
+    /// Reads and parses the lint configuration file at `path`.
+    pub fn load(path: &Path) -> Result<LintConfig, String> {
+        let src = fs::read_to_string(path)
+            .map_err(|err| format!("failed to read lint config `{}`: {}", path.display(), err))?;
+        let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        let dir = fs::canonicalize(dir)
+            .map_err(|err| format!("failed to read lint config `{}`: {}", path.display(), err))?;
+        Self::parse(path.to_path_buf(), dir, &src)
+            .map_err(|err| format!("invalid lint config `{}`: {}", path.display(), err))
+    }
+
+    fn parse(path: PathBuf, dir: PathBuf, src: &str) -> Result<LintConfig, String> {
+        let raw: RawLintConfig = toml::from_str(src).map_err(|err| err.to_string())?;
+        let line_of = |offset: usize| 1 + src[..offset].matches('\n').count() as u32;
+
+        let levels = |lints: BTreeMap<String, Spanned<String>>| {
+            let mut levels = lints
+                .into_iter()
+                .map(|(name, level)| {
+                    let line = line_of(level.start());
+                    match Level::from_str(level.get_ref()) {
+                        Some(level) => {
+                            Ok(LintConfigLevel { name: name.replace('-', "_"), level, line })
+                        }
+                        None => Err(format!(
+                            "unknown lint level `{}` for `{}` at line {}, \
+                             expected `allow`, `warn`, `deny` or `forbid`",
+                            level.get_ref(),
+                            name,
+                            line,
+                        )),
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            // Like for `-A`/`-W`/`-D`/`-F`, later levels take precedence.
+            levels.sort_by_key(|level| level.line);
+            Ok::<_, String>(levels)
+        };
+
+        let lints = levels(raw.lints)?;
+        let mut groups: Vec<_> = raw
+            .groups
+            .into_iter()
+            .map(|(name, lints)| LintConfigGroup {
+                name: name.replace('-', "_"),
+                line: line_of(lints.start()),
+                lints: lints.into_inner().into_iter().map(|lint| lint.replace('-', "_")).collect(),
+            })
+            .collect();
+        groups.sort_by_key(|group| group.line);
+        let overrides = raw
+            .overrides
+            .into_iter()
+            .map(|o| Ok(LintConfigOverride { paths: o.paths, lints: levels(o.lints)? }))
+            .collect::<Result<_, String>>()?;
+
+        Ok(LintConfig { path, dir, lints, groups, overrides })
+    }
+
+    pub fn group(&self, name: &str) -> Option<&LintConfigGroup> {
+        self.groups.iter().find(|group| group.name == name)
+    }
+
+    /// Returns the levels the overrides matching `file` set, in the order they
+    /// should be applied.
+    pub fn overrides_for(&self, file: &Path) -> Vec<&LintConfigLevel> {
+        if self.overrides.is_empty() {
+            return Vec::new();
+        }
+        let file = fs::canonicalize(file).unwrap_or_else(|_| file.to_path_buf());
+        let Ok(relative) = file.strip_prefix(&self.dir) else {
+            return Vec::new();
+        };
+        let components: Vec<_> = relative
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(c) => c.to_str(),
+                _ => None,
+            })
+            .collect();
+
+        self.overrides
+            .iter()
+            .filter(|o| {
+                o.paths.iter().any(|pattern| {
+                    let pattern: Vec<_> = pattern.split('/').filter(|c| !c.is_empty()).collect();
+                    glob_matches(&pattern, &components)
+                })
+            })
+            .flat_map(|o| &o.lints)
+            .collect()
+    }
+}
+
+/// Matches the components of a path against the components of a glob.
+fn glob_matches(pattern: &[&str], path: &[&str]) -> bool {
+    match (pattern.split_first(), path.split_first()) {
+        (None, None) => true,
+        (Some((&"**", rest)), _) => {
+            glob_matches(rest, path) || (!path.is_empty() && glob_matches(pattern, &path[1..]))
+        }
+        (Some((pattern_component, rest)), Some((component, path_rest))) => {
+            let pattern_component: Vec<char> = pattern_component.chars().collect();
+            let component: Vec<char> = component.chars().collect();
+            component_matches(&pattern_component, &component) && glob_matches(rest, path_rest)
+        }
+        _ => false,
+    }
+}
+
+/// Matches a single path component against a glob component, which may
+/// contain `*` and `?`.
+fn component_matches(pattern: &[char], s: &[char]) -> bool {
+    match (pattern.split_first(), s.split_first()) {
+        (None, None) => true,
+        (Some(('*', rest)), _) => {
+            component_matches(rest, s) || (!s.is_empty() && component_matches(pattern, &s[1..]))
+        }
+        (Some(('?', rest)), Some((_, s_rest))) => component_matches(rest, s_rest),
+        (Some((p, rest)), Some((c, s_rest))) => p == c && component_matches(rest, s_rest),
+        _ => false,
+    }
+}
@@ -1,4 +1,5 @@
 use std::num::NonZeroU32;
+use std::path::PathBuf;
 
 use crate::cgu_reuse_tracker::CguReuse;
 use rustc_errors::{
@@ -219,3 +220,17 @@ fn into_diagnostic(self, sess: &Handler) -> DiagnosticBuilder<'_, ErrorGuarantee
         diag
     }
 }
+
+#[derive(Diagnostic)]
+#[diag(session::failed_to_write_suggestions)]
+pub struct FailedToWriteSuggestions {
+    pub path: PathBuf,
+    pub err: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(session::failed_to_write_diagnostic_baseline)]
+pub struct FailedToWriteDiagnosticBaseline {
+    pub path: PathBuf,
+    pub err: String,
+}
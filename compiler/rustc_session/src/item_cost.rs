@@ -0,0 +1,180 @@
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::sync::Lock;
+use rustc_span::def_id::DefId;
+use serde::Serialize;
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+
+/// How `-Z item-compile-cost` prints its report.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ItemCompileCostFormat {
+    Table,
+    Json,
+}
+
+/// The phases of compilation whose time is attributed to items.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CostPhase {
+    Typeck,
+    Borrowck,
+    MirOpt,
+    /// Lowering the MIR of a mono item to backend IR in `MonoItem::define`. The
+    /// backend's own work on the IR, e.g. LLVM's optimizations, is done per
+    /// codegen unit and isn't included.
+    IrGen,
+}
+
+#[derive(Default)]
+struct ItemCost {
+    typeck: Duration,
+    borrowck: Duration,
+    mir_opt: Duration,
+    ir_gen: Duration,
+    /// Number of mono items of this item, e.g. one per instantiation of a
+    /// generic function.
+    mono_items: usize,
+    /// The items whose mono items use instances of this generic function, with
+    /// the number of instances each of them uses.
+    instantiated_by: FxHashMap<DefId, usize>,
+}
+
+impl ItemCost {
+    fn total(&self) -> Duration {
+        self.typeck + self.borrowck + self.mir_opt + self.ir_gen
+    }
+}
+
+#[derive(Serialize)]
+struct ItemCostRow<'a> {
+    item: &'a str,
+    total: f64,
+    typeck: f64,
+    borrowck: f64,
+    mir_opt: f64,
+    ir_gen: f64,
+    mono_items: usize,
+    instantiated_by: Vec<Instantiator>,
+}
+
+#[derive(Serialize)]
+struct Instantiator {
+    item: String,
+    instances: usize,
+}
+
+/// The compile time spent on each item, gathered for `-Z item-compile-cost`.
+///
+/// This is a wall-clock timer around the few places that work on a single item
+/// (see `CostPhase`), independent of the self-profiler's events, so it leaves out
+/// everything that isn't done per item. All instances of a generic function are
+/// attributed to the generic function.
+#[derive(Default)]
+pub struct ItemCompileCosts {
+    costs: Lock<FxHashMap<DefId, ItemCost>>,
+    /// The phases being timed on each thread, innermost last, with the time
+    /// they started and the time spent in phases nested in them so far.
+    running: Lock<FxHashMap<ThreadId, Vec<(Instant, Duration)>>>,
+}
+
+impl ItemCompileCosts {
+    /// Runs `f`, attributing the time it takes to `phase` of `def_id`. Phases
+    /// timed while `f` runs, e.g. the type checking of another item required by
+    /// `f`, are not counted.
+    pub fn time<R>(&self, def_id: DefId, phase: CostPhase, f: impl FnOnce() -> R) -> R {
+        let thread = thread::current().id();
+        self.running.borrow_mut().entry(thread).or_default().push((Instant::now(), Duration::ZERO));
+        let result = f();
+
+        let self_time = {
+            let mut running = self.running.borrow_mut();
+            let running = running.get_mut(&thread).unwrap();
+            let (start, nested_time) = running.pop().unwrap();
+            let time = start.elapsed();
+            if let Some((_, parent_nested_time)) = running.last_mut() {
+                *parent_nested_time += time;
+            }
+            time.saturating_sub(nested_time)
+        };
+        let mut costs = self.costs.borrow_mut();
+        let cost = costs.entry(def_id).or_default();
+        match phase {
+            CostPhase::Typeck => cost.typeck += self_time,
+            CostPhase::Borrowck => cost.borrowck += self_time,
+            CostPhase::MirOpt => cost.mir_opt += self_time,
+            CostPhase::IrGen => cost.ir_gen += self_time,
+        }
+        result
+    }
+
+    pub fn record_mono_item(&self, def_id: DefId) {
+        self.costs.borrow_mut().entry(def_id).or_default().mono_items += 1;
+    }
+
+    /// Records that a mono item of `instantiator` uses an instance of the
+    /// generic function `def_id`.
+    pub fn record_instantiation(&self, def_id: DefId, instantiator: DefId) {
+        let mut costs = self.costs.borrow_mut();
+        *costs.entry(def_id).or_default().instantiated_by.entry(instantiator).or_default() += 1;
+    }
+
+    /// Prints the gathered costs, the most expensive items first. `describe`
+    /// returns the path of an item.
+    pub fn print(&self, format: ItemCompileCostFormat, describe: impl Fn(DefId) -> String) {
+        let costs = self.costs.borrow();
+        let mut items: Vec<_> =
+            costs.iter().map(|(&def_id, cost)| (describe(def_id), cost)).collect();
+        // Primary sort: expensive-to-cheap, then by number of mono items.
+        // Secondary sort: path (dictionary order)
+        items.sort_by(|(path1, cost1), (path2, cost2)| {
+            (cost2.total(), cost2.mono_items, path1).cmp(&(cost1.total(), cost1.mono_items, path2))
+        });
+
+        let rows = items.iter().map(|(path, cost)| {
+            let mut instantiated_by: Vec<_> = cost
+                .instantiated_by
+                .iter()
+                .map(|(&def_id, &instances)| Instantiator { item: describe(def_id), instances })
+                .collect();
+            instantiated_by.sort_by(|a, b| (b.instances, &a.item).cmp(&(a.instances, &b.item)));
+            ItemCostRow {
+                item: path,
+                total: cost.total().as_secs_f64(),
+                typeck: cost.typeck.as_secs_f64(),
+                borrowck: cost.borrowck.as_secs_f64(),
+                mir_opt: cost.mir_opt.as_secs_f64(),
+                ir_gen: cost.ir_gen.as_secs_f64(),
+                mono_items: cost.mono_items,
+                instantiated_by,
+            }
+        });
+
+        match format {
+            ItemCompileCostFormat::Json => {
+                let rows: Vec<_> = rows.collect();
+                println!("{}", serde_json::to_string_pretty(&rows).unwrap());
+            }
+            ItemCompileCostFormat::Table => {
+                println!(
+                    "item-compile-cost {:>8} {:>8} {:>8} {:>8} {:>8} {:>10}  item",
+                    "total", "typeck", "borrowck", "mir-opt", "ir-gen", "mono-items"
+                );
+                for row in rows {
+                    println!(
+                        "item-compile-cost {:>8.3} {:>8.3} {:>8.3} {:>8.3} {:>8.3} {:>10}  `{}`",
+                        row.total,
+                        row.typeck,
+                        row.borrowck,
+                        row.mir_opt,
+                        row.ir_gen,
+                        row.mono_items,
+                        row.item,
+                    );
+                    // Line the number of instances up with the mono items above.
+                    for Instantiator { item, instances } in row.instantiated_by {
+                        println!("item-compile-cost {instances:>55}  instantiated by `{item}`");
+                    }
+                }
+            }
+        }
+    }
+}
@@ -18,6 +18,7 @@
 #[macro_use]
 extern crate tracing;
 
+pub mod cfg_eval;
 pub mod cgu_reuse_tracker;
 pub mod utils;
 pub use lint::{declare_lint, declare_lint_pass, declare_tool_lint, impl_lint_pass};
@@ -25,6 +26,7 @@
 pub mod parse;
 
 mod code_stats;
+mod item_cost;
 #[macro_use]
 pub mod config;
 pub mod cstore;
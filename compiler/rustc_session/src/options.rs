@@ -1,9 +1,12 @@
+use crate::config::lint_config::LintConfig;
 use crate::config::*;
 
 use crate::early_error;
 use crate::lint;
 use crate::search_paths::SearchPath;
+use crate::session::ItemCompileCostFormat;
 use crate::utils::NativeLib;
+use rustc_data_structures::profiling::TimePassesFormat;
 use rustc_errors::LanguageIdentifier;
 use rustc_target::spec::{CodeModel, LinkerFlavorCli, MergeFunctions, PanicStrategy, SanitizerSet};
 use rustc_target::spec::{
@@ -140,6 +143,8 @@ pub struct Options {
         debuginfo: DebugInfo [TRACKED],
         lint_opts: Vec<(String, lint::Level)> [TRACKED_NO_CRATE_HASH],
         lint_cap: Option<lint::Level> [TRACKED_NO_CRATE_HASH],
+        /// The lint levels read from the file passed with `--lint-config`.
+        lint_config: Option<LintConfig> [TRACKED_NO_CRATE_HASH],
         describe_lints: bool [UNTRACKED],
         output_types: OutputTypes [TRACKED],
         search_paths: Vec<SearchPath> [UNTRACKED],
@@ -414,8 +419,11 @@ mod desc {
         "one of (`none` (default), `basic`, `strong`, or `all`)";
     pub const parse_branch_protection: &str =
         "a `,` separated combination of `bti`, `b-key`, `pac-ret`, or `leaf`";
-    pub const parse_proc_macro_execution_strategy: &str =
-        "one of supported execution strategies (`same-thread`, or `cross-thread`)";
+    pub const parse_proc_macro_execution_strategy: &str = "one of supported execution strategies \
+        (`same-thread`, `cross-thread`, or `out-of-process`)";
+    pub const parse_apply_suggestions: &str = "one of `diff`, `inplace`, or `dir=<path>`";
+    pub const parse_time_passes_format: &str = "`text` (default) or `json`";
+    pub const parse_item_compile_cost: &str = "either no value, `table`, or `json`";
 }
 
 mod parse {
@@ -1074,6 +1082,46 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         *slot = match v {
             Some("same-thread") => ProcMacroExecutionStrategy::SameThread,
             Some("cross-thread") => ProcMacroExecutionStrategy::CrossThread,
+            Some("out-of-process") => ProcMacroExecutionStrategy::OutOfProcess,
+            _ => return false,
+        };
+        true
+    }
+
+    pub(crate) fn parse_apply_suggestions(
+        slot: &mut Option<ApplySuggestions>,
+        v: Option<&str>,
+    ) -> bool {
+        *slot = match v {
+            Some("diff") => Some(ApplySuggestions::Diff),
+            Some("inplace") => Some(ApplySuggestions::InPlace),
+            Some(v) => match v.strip_prefix("dir=") {
+                Some(dir) if !dir.is_empty() => {
+                    Some(ApplySuggestions::Directory(PathBuf::from(dir)))
+                }
+                _ => return false,
+            },
+            None => return false,
+        };
+        true
+    }
+
+    pub(crate) fn parse_item_compile_cost(
+        slot: &mut Option<ItemCompileCostFormat>,
+        v: Option<&str>,
+    ) -> bool {
+        *slot = match v {
+            None | Some("table") => Some(ItemCompileCostFormat::Table),
+            Some("json") => Some(ItemCompileCostFormat::Json),
+            _ => return false,
+        };
+        true
+    }
+
+    pub(crate) fn parse_time_passes_format(slot: &mut TimePassesFormat, v: Option<&str>) -> bool {
+        *slot = match v {
+            Some("text") => TimePassesFormat::Text,
+            Some("json") => TimePassesFormat::Json,
             _ => return false,
         };
         true
@@ -1224,6 +1272,10 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "only allow the listed language features to be enabled in code (space separated)"),
     always_encode_mir: bool = (false, parse_bool, [TRACKED],
         "encode MIR of all functions into the crate metadata (default: no)"),
+    apply_suggestions: Option<ApplySuggestions> = (None, parse_apply_suggestions, [UNTRACKED],
+        "apply the machine-applicable suggestions of all emitted diagnostics once compilation \
+        finished: `diff` prints a unified diff to stdout, `inplace` overwrites the source files, \
+        `dir=<path>` writes the patched files into a directory"),
     assume_incomplete_release: bool = (false, parse_bool, [TRACKED],
         "make cfg(version) treat the current version as incomplete (default: no)"),
     #[rustc_lint_opt_deny_field_access("use `Session::asm_comments` instead of this field")]
@@ -1236,10 +1288,16 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     binary_dep_depinfo: bool = (false, parse_bool, [TRACKED],
         "include artifacts (sysroot, crate dependencies) used during compilation in dep-info \
         (default: no)"),
+    borrowck_explain: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "dump the region constraint graph of the function with this path, along with the \
+        constraints blamed for its lifetime errors, as Graphviz and JSON"),
     box_noalias: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "emit noalias metadata for box (default: yes)"),
     branch_protection: Option<BranchProtection> = (None, parse_branch_protection, [TRACKED],
         "set options for branch target identification and pointer authentication on AArch64"),
+    cache_proc_macros: bool = (false, parse_bool, [UNTRACKED],
+        "reuse the output of proc-macro derives from the previous incremental build when \
+        their input did not change (default: no)"),
     cf_protection: CFProtection = (CFProtection::None, parse_cfprotection, [TRACKED],
         "instrument control-flow architecture protection"),
     cgu_partitioning_strategy: Option<String> = (None, parse_opt_string, [TRACKED],
@@ -1264,6 +1322,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     dep_tasks: bool = (false, parse_bool, [UNTRACKED],
         "print tasks that execute and the color their dep node gets (requires debug build) \
         (default: no)"),
+    diagnostic_baseline: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "suppress the lint diagnostics recorded in this baseline file and only report new ones \
+        (see `-Z record-diagnostic-baseline`)"),
     dlltool: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
         "import library generation tool (windows-gnu only)"),
     dont_buffer_diagnostics: bool = (false, parse_bool, [UNTRACKED],
@@ -1278,6 +1339,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         (default: no)"),
     dump_drop_tracking_cfg: Option<String> = (None, parse_opt_string, [UNTRACKED],
         "dump drop-tracking control-flow graph as a `.dot` file (default: no)"),
+    dump_macro_expansions: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write every macro invocation expanded, with its input, its output, the `macro_rules!` \
+        arm that matched and the invocations in its output, to this file as JSON"),
     dump_mir: Option<String> = (None, parse_opt_string, [UNTRACKED],
         "dump MIR state to file.
         `val` is used to select which passes and functions to dump. For example:
@@ -1369,6 +1433,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     #[rustc_lint_opt_deny_field_access("use `Session::instrument_mcount` instead of this field")]
     instrument_mcount: bool = (false, parse_bool, [TRACKED],
         "insert function instrument code for mcount-based tracing (default: no)"),
+    item_compile_cost: Option<ItemCompileCostFormat> = (None, parse_item_compile_cost, [UNTRACKED],
+        "print the time spent type checking, borrow checking, optimizing and lowering to \
+        backend IR each item, and its number of mono items, as a `table` (default) or as `json`"),
     keep_hygiene_data: bool = (false, parse_bool, [UNTRACKED],
         "keep hygiene data after analysis (default: no)"),
     link_native_libraries: bool = (true, parse_bool, [UNTRACKED],
@@ -1387,6 +1454,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "list the symbols defined by a library crate (default: no)"),
     macro_backtrace: bool = (false, parse_bool, [UNTRACKED],
         "show macro backtraces (default: no)"),
+    macro_match_explain: bool = (false, parse_bool, [UNTRACKED],
+        "when no rule of a `macro_rules!` macro matches, show where each rule stopped matching \
+        and what it expected (default: no)"),
     merge_functions: Option<MergeFunctions> = (None, parse_merge_functions, [TRACKED],
         "control the operation of the MergeFunctions LLVM pass, taking \
         the same values as the target option of the same name"),
@@ -1402,6 +1472,10 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         disabled by other flags as usual."),
     mir_pretty_relative_line_numbers: bool = (false, parse_bool, [UNTRACKED],
         "use line numbers relative to the function in mir pretty printing"),
+    mir_opt_bisect_limit: Option<usize> = (None, parse_opt_number, [TRACKED],
+        "only apply this many MIR optimization passes to the bodies of the crate, and report \
+        which pass and body the last one applied to, to bisect miscompilations \
+        (default: no limit)"),
     #[rustc_lint_opt_deny_field_access("use `Session::mir_opt_level` instead of this field")]
     mir_opt_level: Option<usize> = (None, parse_opt_number, [TRACKED],
         "MIR optimization level (0-4; default: 1 in non optimized builds and 2 in optimized builds)"),
@@ -1467,11 +1541,17 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "use a more precise version of drop elaboration for matches on enums (default: yes). \
         This results in better codegen, but has caused miscompilations on some tier 2 platforms. \
         See #77382 and #74551."),
+    print_cfg_eval: bool = (false, parse_bool, [UNTRACKED],
+        "print every `cfg` predicate evaluated by the compiler, with its result, as JSON \
+        (default: no)"),
     print_fuel: Option<String> = (None, parse_opt_string, [TRACKED],
         "make rustc print the total optimization fuel used by a crate"),
     #[rustc_lint_opt_deny_field_access("use `Session::print_llvm_passes` instead of this field")]
     print_llvm_passes: bool = (false, parse_bool, [UNTRACKED],
         "print the LLVM optimization passes being run (default: no)"),
+    print_mono_bloat: bool = (false, parse_bool, [UNTRACKED],
+        "print the instances of each generic function, their estimated size and the chain of \
+        items that caused each of them to be collected (default: no)"),
     print_mono_items: Option<String> = (None, parse_opt_string, [UNTRACKED],
         "print the result of the monomorphization collection pass"),
     print_type_sizes: bool = (false, parse_bool, [UNTRACKED],
@@ -1481,6 +1561,11 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     proc_macro_execution_strategy: ProcMacroExecutionStrategy = (ProcMacroExecutionStrategy::SameThread,
         parse_proc_macro_execution_strategy, [UNTRACKED],
         "how to run proc-macro code (default: same-thread)"),
+    proc_macro_memory_limit: Option<u64> = (None, parse_opt_number, [UNTRACKED],
+        "the amount of memory, in MiB, that the process running an out-of-process proc-macro \
+        may use"),
+    proc_macro_time_limit: Option<u64> = (None, parse_opt_number, [UNTRACKED],
+        "the time, in milliseconds, that an out-of-process proc-macro may run for"),
     profile: bool = (false, parse_bool, [TRACKED],
         "insert profiling code (default: no)"),
     profile_closures: bool = (false, parse_no_flag, [UNTRACKED],
@@ -1498,6 +1583,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "randomize the layout of types (default: no)"),
     layout_seed: Option<u64> = (None, parse_opt_number, [TRACKED],
         "seed layout randomization"),
+    record_diagnostic_baseline: bool = (false, parse_bool, [UNTRACKED],
+        "write all lint diagnostics into the file given with `-Z diagnostic-baseline` instead of \
+        suppressing the ones it records (default: no)"),
     relax_elf_relocations: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "whether ELF relocations can be relaxed"),
     relro_level: Option<RelroLevel> = (None, parse_relro_level, [TRACKED],
@@ -1509,6 +1597,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         to rust's source base directory. only meant for testing purposes"),
     report_delayed_bugs: bool = (false, parse_bool, [TRACKED],
         "immediately print bugs registered with `delay_span_bug` (default: no)"),
+    report_unused_pub_items: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "warn about the public items of the crate that none of the crates whose \
+        `--emit=used-items` reports are in this directory use"),
     sanitizer: SanitizerSet = (SanitizerSet::empty(), parse_sanitizers, [TRACKED],
         "use a sanitizer"),
     sanitizer_memory_track_origins: usize = (0, parse_sanitizer_memory_track_origins, [TRACKED],
@@ -1605,6 +1696,8 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     #[rustc_lint_opt_deny_field_access("use `Session::time_passes` instead of this field")]
     time_passes: bool = (false, parse_bool, [UNTRACKED],
         "measure time of each rustc pass (default: no)"),
+    time_passes_format: TimePassesFormat = (TimePassesFormat::Text, parse_time_passes_format, [UNTRACKED],
+        "the format to use for -Z time-passes (`text` (default) or `json`)"),
     #[rustc_lint_opt_deny_field_access("use `Session::tls_model` instead of this field")]
     tls_model: Option<TlsModel> = (None, parse_tls_model, [TRACKED],
         "choose the TLS model to use (`rustc --print tls-models` for details)"),
@@ -1618,6 +1711,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "treat error number `val` that occurs as bug"),
     trim_diagnostic_paths: bool = (true, parse_bool, [UNTRACKED],
         "in diagnostics, use heuristics to shorten paths referring to items"),
+    type_size_limit_file: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
+        "check the sizes of types against the limits in this file, one `<type> = <size in bytes>` \
+        per line, and report an error for each type that exceeds its limit"),
     ui_testing: bool = (false, parse_bool, [UNTRACKED],
         "emit compiler diagnostics in a form suitable for UI testing (default: no)"),
     uninit_const_chunk_threshold: usize = (16, parse_number, [TRACKED],
@@ -1,14 +1,19 @@
 use crate::cgu_reuse_tracker::CguReuseTracker;
 use crate::code_stats::CodeStats;
-pub use crate::code_stats::{DataTypeKind, FieldInfo, SizeKind, VariantInfo};
-use crate::config::{self, CrateType, InstrumentCoverage, OptLevel, OutputType, SwitchWithOptPath};
+pub use crate::code_stats::{DataTypeKind, FieldInfo, SizeKind, TypeSizeInfo, VariantInfo};
+use crate::config::{
+    self, ApplySuggestions, CrateType, InstrumentCoverage, OptLevel, OutputType, SwitchWithOptPath,
+};
 use crate::errors::{
-    CannotEnableCrtStaticLinux, CannotMixAndMatchSanitizers, LinkerPluginToWindowsNotSupported,
-    NotCircumventFeature, ProfileSampleUseFileDoesNotExist, ProfileUseFileDoesNotExist,
-    SanitizerCfiEnabled, SanitizerNotSupported, SanitizersNotSupported,
-    SplitDebugInfoUnstablePlatform, StackProtectorNotSupportedForTarget,
-    TargetRequiresUnwindTables, UnstableVirtualFunctionElimination, UnsupportedDwarfVersion,
+    CannotEnableCrtStaticLinux, CannotMixAndMatchSanitizers, FailedToWriteDiagnosticBaseline,
+    FailedToWriteSuggestions, LinkerPluginToWindowsNotSupported, NotCircumventFeature,
+    ProfileSampleUseFileDoesNotExist, ProfileUseFileDoesNotExist, SanitizerCfiEnabled,
+    SanitizerNotSupported, SanitizersNotSupported, SplitDebugInfoUnstablePlatform,
+    StackProtectorNotSupportedForTarget, TargetRequiresUnwindTables,
+    UnstableVirtualFunctionElimination, UnsupportedDwarfVersion,
 };
+use crate::item_cost::ItemCompileCosts;
+pub use crate::item_cost::{CostPhase, ItemCompileCostFormat};
 use crate::parse::{add_feature_diagnostics, ParseSess};
 use crate::search_paths::{PathKind, SearchPath};
 use crate::{filesearch, lint};
@@ -23,9 +28,11 @@
     self, AtomicU64, AtomicUsize, Lock, Lrc, OnceCell, OneThread, Ordering, Ordering::SeqCst,
 };
 use rustc_errors::annotate_snippet_emitter_writer::AnnotateSnippetEmitterWriter;
+use rustc_errors::baseline::DiagnosticBaseline;
 use rustc_errors::emitter::{Emitter, EmitterWriter, HumanReadableErrorType};
 use rustc_errors::json::JsonEmitter;
 use rustc_errors::registry::Registry;
+use rustc_errors::sarif::SarifEmitter;
 use rustc_errors::{
     error_code, fallback_fluent_bundle, DiagnosticBuilder, DiagnosticId, DiagnosticMessage,
     ErrorGuaranteed, FluentBundle, IntoDiagnostic, LazyFallbackBundle, MultiSpan,
@@ -33,8 +40,8 @@
 use rustc_macros::HashStable_Generic;
 pub use rustc_span::def_id::StableCrateId;
 use rustc_span::edition::Edition;
-use rustc_span::source_map::{FileLoader, RealFileLoader, SourceMap, Span};
-use rustc_span::{sym, SourceFileHashAlgorithm, Symbol};
+use rustc_span::source_map::{FileLoader, FilePathMapping, RealFileLoader, SourceMap, Span};
+use rustc_span::{sym, FileName, SourceFileHashAlgorithm, Symbol};
 use rustc_target::asm::InlineAsmArch;
 use rustc_target::spec::{CodeModel, PanicStrategy, RelocModel, RelroLevel};
 use rustc_target::spec::{
@@ -44,9 +51,10 @@
 use std::cell::{self, RefCell};
 use std::env;
 use std::fmt;
+use std::fs;
 use std::io::Write;
 use std::ops::{Div, Mul};
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -175,12 +183,19 @@ pub struct Session {
     /// Data about code being compiled, gathered during compilation.
     pub code_stats: CodeStats,
 
+    /// The compile time spent on each item, gathered for `-Z item-compile-cost`.
+    pub item_compile_costs: ItemCompileCosts,
+
     /// Tracks fuel info if `-zfuel=crate=n` is specified.
     optimization_fuel: Lock<OptimizationFuel>,
 
     /// Always set to zero and incremented so that we can print fuel expended by a crate.
     pub print_fuel: AtomicU64,
 
+    /// The number of MIR optimization passes applied so far, if `-Z mir-opt-bisect-limit=n`
+    /// is specified.
+    mir_opt_bisect_count: AtomicUsize,
+
     /// Loaded up early on in the initialization of this `Session` to avoid
     /// false positives about a job server in our environment.
     pub jobserver: Client,
@@ -259,10 +274,80 @@ fn check_miri_unleashed_features(&self) {
     /// Invoked all the way at the end to finish off diagnostics printing.
     pub fn finish_diagnostics(&self, registry: &Registry) {
         self.check_miri_unleashed_features();
+        self.apply_suggestions();
+        self.write_diagnostic_baseline();
         self.diagnostic().print_error_count(registry);
         self.emit_future_breakage();
     }
 
+    /// Applies the machine-applicable suggestions collected for `-Z apply-suggestions`.
+    fn apply_suggestions(&self) {
+        let Some(mode) = &self.opts.unstable_opts.apply_suggestions else {
+            return;
+        };
+
+        let suggestions = self.diagnostic().take_machine_applicable_suggestions();
+        let patched =
+            rustc_errors::apply_suggestions::apply_suggestions(self.source_map(), &suggestions);
+        for file in patched {
+            let FileName::Real(name) = &file.name else { continue };
+            let Some(path) = name.local_path() else { continue };
+            let dst = match mode {
+                ApplySuggestions::Diff => {
+                    print!("{}", file.unified_diff());
+                    continue;
+                }
+                ApplySuggestions::InPlace => path.to_path_buf(),
+                ApplySuggestions::Directory(dir) => {
+                    let working_dir = self.opts.working_dir.local_path_if_available();
+                    let relative = path.strip_prefix(working_dir).unwrap_or(path);
+                    dir.join(
+                        relative
+                            .components()
+                            .filter(|c| matches!(c, Component::Normal(_)))
+                            .collect::<PathBuf>(),
+                    )
+                }
+            };
+            // Patch the file as it is on disk rather than its normalized source, which
+            // lost its byte order mark and `\r\n` line endings.
+            let patched = match fs::read_to_string(path) {
+                Ok(contents) => file.patch_contents(&contents),
+                Err(err) => {
+                    self.emit_err(FailedToWriteSuggestions { path: dst, err: err.to_string() });
+                    continue;
+                }
+            };
+            let Some(patched) = patched else {
+                let err = format!("`{}` changed during compilation", path.display());
+                self.emit_err(FailedToWriteSuggestions { path: dst, err });
+                continue;
+            };
+            let written = dst
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|()| fs::write(&dst, patched));
+            if let Err(err) = written {
+                self.emit_err(FailedToWriteSuggestions { path: dst, err: err.to_string() });
+            }
+        }
+    }
+
+    /// Writes the lint diagnostics recorded for `-Z record-diagnostic-baseline`.
+    fn write_diagnostic_baseline(&self) {
+        let Some(path) = &self.opts.unstable_opts.diagnostic_baseline else { return };
+        let Some(baseline) = self.diagnostic().take_diagnostic_baseline() else { return };
+        if !baseline.is_recording() {
+            return;
+        }
+        if let Err(err) = baseline.write(path) {
+            self.emit_err(FailedToWriteDiagnosticBaseline {
+                path: path.clone(),
+                err: err.to_string(),
+            });
+        }
+    }
+
     fn emit_future_breakage(&self) {
         if !self.opts.json_future_incompat {
             return;
@@ -833,6 +918,25 @@ pub fn consider_optimizing<T: Fn() -> String>(&self, crate_name: &str, msg: T) -
         ret
     }
 
+    /// We want to know if we're allowed to apply one more MIR optimization pass with
+    /// `-Z mir-opt-bisect-limit=n`. This counts the pass, and reports it with `msg` if it is
+    /// the `n`th one.
+    pub fn consider_mir_opt_bisect<T: Fn() -> String>(&self, msg: T) -> bool {
+        let Some(limit) = self.opts.unstable_opts.mir_opt_bisect_limit else {
+            return true;
+        };
+        assert_eq!(self.threads(), 1);
+        let count = self.mir_opt_bisect_count.fetch_add(1, SeqCst) + 1;
+        if count == limit {
+            self.note_without_error(&format!(
+                "mir-opt-bisect-limit reached: optimization #{} applied {}",
+                count,
+                msg()
+            ));
+        }
+        count <= limit
+    }
+
     pub fn rust_2015(&self) -> bool {
         self.edition() == Edition::Edition2015
     }
@@ -1274,6 +1378,21 @@ fn default_emitter(
             )
             .ui_testing(sopts.unstable_opts.ui_testing),
         ),
+        (config::ErrorOutputType::Sarif { pretty }, None) => Box::new(SarifEmitter::stderr(
+            Some(registry),
+            source_map,
+            bundle,
+            fallback_bundle,
+            pretty,
+        )),
+        (config::ErrorOutputType::Sarif { pretty }, Some(dst)) => Box::new(SarifEmitter::new(
+            dst,
+            Some(registry),
+            source_map,
+            bundle,
+            fallback_bundle,
+            pretty,
+        )),
     }
 }
 
@@ -1349,6 +1468,19 @@ pub fn build_session(
         emitter,
         sopts.unstable_opts.diagnostic_handler_flags(can_emit_warnings),
     );
+    if let Some(path) = &sopts.unstable_opts.diagnostic_baseline {
+        let baseline = if sopts.unstable_opts.record_diagnostic_baseline {
+            DiagnosticBaseline::recording()
+        } else {
+            DiagnosticBaseline::load(path).unwrap_or_else(|err| {
+                early_error(
+                    sopts.error_format,
+                    &format!("failed to load diagnostic baseline `{}`: {err}", path.display()),
+                )
+            })
+        };
+        span_diagnostic.set_diagnostic_baseline(baseline);
+    }
 
     let self_profiler = if let SwitchWithOptPath::Enabled(ref d) = sopts.unstable_opts.self_profile
     {
@@ -1374,6 +1506,7 @@ pub fn build_session(
 
     let mut parse_sess = ParseSess::with_span_handler(span_diagnostic, source_map);
     parse_sess.assume_incomplete_release = sopts.unstable_opts.assume_incomplete_release;
+    parse_sess.record_cfg_evals = sopts.unstable_opts.print_cfg_eval;
 
     let host_triple = config::host_triple();
     let target_triple = sopts.target_triple.triple();
@@ -1396,6 +1529,7 @@ pub fn build_session(
         out_of_fuel: false,
     });
     let print_fuel = AtomicU64::new(0);
+    let mir_opt_bisect_count = AtomicUsize::new(0);
 
     let cgu_reuse_tracker = if sopts.unstable_opts.query_dep_graph {
         CguReuseTracker::new()
@@ -1403,8 +1537,12 @@ pub fn build_session(
         CguReuseTracker::new_disabled()
     };
 
-    let prof =
-        SelfProfilerRef::new(self_profiler, sopts.time_passes(), sopts.unstable_opts.time_passes);
+    let prof = SelfProfilerRef::new(
+        self_profiler,
+        sopts.time_passes(),
+        sopts.unstable_opts.time_passes,
+        sopts.unstable_opts.time_passes_format,
+    );
 
     let ctfe_backtrace = Lock::new(match env::var("RUSTC_CTFE_BACKTRACE") {
         Ok(ref val) if val == "immediate" => CtfeBacktrace::Immediate,
@@ -1437,8 +1575,10 @@ pub fn build_session(
             normalize_projection_ty: AtomicUsize::new(0),
         },
         code_stats: Default::default(),
+        item_compile_costs: Default::default(),
         optimization_fuel,
         print_fuel,
+        mir_opt_bisect_count,
         jobserver: jobserver::client(),
         driver_lint_caps,
         ctfe_backtrace,
@@ -1591,6 +1731,13 @@ fn early_error_handler(output: config::ErrorOutputType) -> rustc_errors::Handler
         config::ErrorOutputType::Json { pretty, json_rendered } => {
             Box::new(JsonEmitter::basic(pretty, json_rendered, None, fallback_bundle, None, false))
         }
+        config::ErrorOutputType::Sarif { pretty } => Box::new(SarifEmitter::stderr(
+            None,
+            Lrc::new(SourceMap::new(FilePathMapping::empty())),
+            None,
+            fallback_bundle,
+            pretty,
+        )),
     };
     rustc_errors::Handler::with_emitter(true, None, emitter)
 }
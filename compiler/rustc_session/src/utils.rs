@@ -1,5 +1,6 @@
-use crate::session::Session;
+use crate::session::{CostPhase, Session};
 use rustc_data_structures::profiling::VerboseTimingGuard;
+use rustc_span::def_id::DefId;
 use std::path::{Path, PathBuf};
 
 impl Session {
@@ -9,6 +10,15 @@ pub fn timer<'a>(&'a self, what: &'static str) -> VerboseTimingGuard<'a> {
     pub fn time<R>(&self, what: &'static str, f: impl FnOnce() -> R) -> R {
         self.prof.verbose_generic_activity(what).run(f)
     }
+    /// Runs `f`, attributing the time it takes to `phase` of `def_id` for
+    /// `-Z item-compile-cost`.
+    pub fn time_item<R>(&self, def_id: DefId, phase: CostPhase, f: impl FnOnce() -> R) -> R {
+        if self.opts.unstable_opts.item_compile_cost.is_some() {
+            self.item_compile_costs.time(def_id, phase, f)
+        } else {
+            f()
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Encodable, Decodable)]
@@ -0,0 +1,55 @@
+//! Module that implements the bridge between Stable MIR and internal compiler MIR.
+//!
+//! For that, we define APIs that will temporarily be public to 3P that exposes rustc internal APIs
+//! until stable MIR is complete.
+
+use crate::rustc_smir::Tables;
+use crate::stable_mir::mir::Body;
+use crate::stable_mir::{Context, CrateItem};
+use crate::very_unstable::driver::{Callbacks, Compilation, RunCompiler};
+use crate::very_unstable::interface::{interface, Queries};
+use crate::very_unstable::middle::ty::TyCtxt;
+
+/// Gives `f` access to the Stable MIR of the crate being compiled by `tcx`.
+pub fn run<T>(tcx: TyCtxt<'_>, f: impl FnOnce(&mut dyn Context) -> T) -> T {
+    f(&mut Tables::new(tcx))
+}
+
+/// Runs the compiler with `args` (as if they were passed on the command line,
+/// including the binary name in `args[0]`) and, once analysis succeeded, calls
+/// `callback` with the Stable MIR of every item of the local crate that has a body.
+///
+/// Compilation stops after the callbacks ran; no code is generated.
+pub fn run_compiler<F>(args: &[String], callback: F) -> interface::Result<()>
+where
+    F: FnMut(&mut dyn Context, &CrateItem, &Body) + Send,
+{
+    RunCompiler::new(args, &mut BodyCallbacks { callback }).run()
+}
+
+struct BodyCallbacks<F> {
+    callback: F,
+}
+
+impl<F> Callbacks for BodyCallbacks<F>
+where
+    F: FnMut(&mut dyn Context, &CrateItem, &Body) + Send,
+{
+    fn after_analysis<'tcx>(
+        &mut self,
+        compiler: &interface::Compiler,
+        queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        compiler.session().abort_if_errors();
+
+        queries.global_ctxt().unwrap().peek_mut().enter(|tcx| {
+            let mut tables = Tables::new(tcx);
+            for item in tables.all_local_items() {
+                let body = tables.mir_body(&item);
+                (self.callback)(&mut tables, &item, &body);
+            }
+        });
+
+        Compilation::Stop
+    }
+}
@@ -0,0 +1,671 @@
+//! Module that implements what will become the rustc side of Stable MIR.
+//!
+//! This module is responsible for building Stable MIR components from internal components.
+//!
+//! This module is not intended to be invoked directly by users. It will eventually
+//! become the public API of rustc that will be invoked by the `stable_mir` crate.
+//!
+//! For now, we are developing everything inside `rustc`, thus, we keep this module private.
+
+use crate::stable_mir::mir::{self as smir, BasicBlockIdx};
+use crate::stable_mir::ty as sty;
+use crate::stable_mir::{self, Context};
+use crate::very_unstable::data_structures::fx::FxHashMap;
+use crate::very_unstable::hir;
+use crate::very_unstable::hir::def_id::{DefId, LOCAL_CRATE};
+use crate::very_unstable::middle::mir;
+use crate::very_unstable::middle::ty::adjustment::PointerCast;
+use crate::very_unstable::middle::ty::{self, TyCtxt};
+use crate::very_unstable::span::Span;
+
+/// Holds the compiler state needed to translate internal items into their stable counterpart.
+pub struct Tables<'tcx> {
+    pub tcx: TyCtxt<'tcx>,
+    /// Maps a stable `DefId` (the index) back to the rustc `DefId` it was created for.
+    pub def_ids: Vec<DefId>,
+    stable_ids: FxHashMap<DefId, stable_mir::DefId>,
+}
+
+impl<'tcx> Tables<'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>) -> Self {
+        Tables { tcx, def_ids: Vec::new(), stable_ids: FxHashMap::default() }
+    }
+
+    /// Returns the rustc `DefId` that `item` was created for.
+    pub fn item_def_id(&self, item: &stable_mir::CrateItem) -> DefId {
+        self.def_ids[item.def_id]
+    }
+
+    pub fn create_def_id(&mut self, did: DefId) -> stable_mir::DefId {
+        let def_ids = &mut self.def_ids;
+        *self.stable_ids.entry(did).or_insert_with(|| {
+            def_ids.push(did);
+            def_ids.len() - 1
+        })
+    }
+
+    pub fn crate_item(&mut self, did: DefId) -> stable_mir::CrateItem {
+        stable_mir::CrateItem {
+            def_id: self.create_def_id(did),
+            name: self.tcx.def_path_str(did),
+            span: self.stable_span(self.tcx.def_span(did)),
+        }
+    }
+
+    pub fn stable_span(&self, span: Span) -> stable_mir::Span {
+        let source_map = self.tcx.sess.source_map();
+        let lo = source_map.lookup_char_pos(span.lo());
+        let hi = source_map.lookup_char_pos(span.hi());
+        stable_mir::Span {
+            file: source_map.filename_for_diagnostics(&lo.file.name).to_string(),
+            lo_line: lo.line,
+            lo_col: lo.col.0,
+            hi_line: hi.line,
+            hi_col: hi.col.0,
+        }
+    }
+
+    fn stable_crate(&self, crate_num: hir::def_id::CrateNum) -> stable_mir::Crate {
+        stable_mir::Crate {
+            id: crate_num.as_usize(),
+            name: self.tcx.crate_name(crate_num).to_string(),
+            is_local: crate_num == LOCAL_CRATE,
+        }
+    }
+}
+
+impl<'tcx> Context for Tables<'tcx> {
+    fn local_crate(&self) -> stable_mir::Crate {
+        self.stable_crate(LOCAL_CRATE)
+    }
+
+    fn external_crates(&self) -> Vec<stable_mir::Crate> {
+        self.tcx.crates(()).iter().map(|&crate_num| self.stable_crate(crate_num)).collect()
+    }
+
+    fn all_local_items(&mut self) -> Vec<stable_mir::CrateItem> {
+        let tcx = self.tcx;
+        tcx.mir_keys(()).iter().map(|def_id| self.crate_item(def_id.to_def_id())).collect()
+    }
+
+    fn mir_body(&mut self, item: &stable_mir::CrateItem) -> smir::Body {
+        let def_id = self.item_def_id(item);
+        let body =
+            self.tcx.instance_mir(ty::InstanceDef::Item(ty::WithOptConstParam::unknown(def_id)));
+        body.stable(self)
+    }
+
+    fn def_path_str(&self, def_id: stable_mir::DefId) -> stable_mir::Symbol {
+        self.tcx.def_path_str(self.def_ids[def_id])
+    }
+}
+
+/// Trait used to convert between an internal MIR type to a Stable MIR type.
+pub trait Stable<'tcx> {
+    /// The stable representation of the type implementing Stable.
+    type T;
+    /// Converts an object to the equivalent Stable MIR representation.
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T;
+}
+
+impl<'tcx, S: Stable<'tcx>> Stable<'tcx> for Option<S> {
+    type T = Option<S::T>;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        self.as_ref().map(|value| value.stable(tables))
+    }
+}
+
+impl<'tcx, S: Stable<'tcx>> Stable<'tcx> for [S] {
+    type T = Vec<S::T>;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        self.iter().map(|value| value.stable(tables)).collect()
+    }
+}
+
+impl<'tcx> Stable<'tcx> for Span {
+    type T = stable_mir::Span;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        tables.stable_span(*self)
+    }
+}
+
+impl<'tcx> Stable<'tcx> for DefId {
+    type T = stable_mir::DefId;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        tables.create_def_id(*self)
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::BasicBlock {
+    type T = BasicBlockIdx;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        self.as_usize()
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Local {
+    type T = smir::Local;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        self.as_usize()
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Body<'tcx> {
+    type T = smir::Body;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        smir::Body {
+            blocks: self.basic_blocks.raw.stable(tables),
+            locals: self.local_decls.raw.stable(tables),
+            arg_count: self.arg_count,
+            span: self.span.stable(tables),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::LocalDecl<'tcx> {
+    type T = smir::LocalDecl;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        smir::LocalDecl {
+            ty: self.ty.stable(tables),
+            mutability: self.mutability.stable(tables),
+            span: self.source_info.span.stable(tables),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::BasicBlockData<'tcx> {
+    type T = smir::BasicBlock;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        smir::BasicBlock {
+            statements: self.statements.stable(tables),
+            terminator: self.terminator().stable(tables),
+            is_cleanup: self.is_cleanup,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Statement<'tcx> {
+    type T = smir::Statement;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use mir::StatementKind::*;
+        let kind = match &self.kind {
+            Assign(assign) => {
+                smir::StatementKind::Assign(assign.0.stable(tables), assign.1.stable(tables))
+            }
+            FakeRead(fake_read) => smir::StatementKind::FakeRead(fake_read.1.stable(tables)),
+            SetDiscriminant { place, variant_index } => smir::StatementKind::SetDiscriminant {
+                place: place.stable(tables),
+                variant_index: variant_index.as_usize(),
+            },
+            Deinit(place) => smir::StatementKind::Deinit(place.stable(tables)),
+            StorageLive(local) => smir::StatementKind::StorageLive(local.stable(tables)),
+            StorageDead(local) => smir::StatementKind::StorageDead(local.stable(tables)),
+            Retag(kind, place) => {
+                smir::StatementKind::Retag(kind.stable(tables), place.stable(tables))
+            }
+            AscribeUserType(ascription, _) => {
+                smir::StatementKind::AscribeUserType(ascription.0.stable(tables))
+            }
+            Coverage(_) => smir::StatementKind::Coverage,
+            Intrinsic(intrinsic) => smir::StatementKind::Intrinsic(intrinsic.stable(tables)),
+            Nop => smir::StatementKind::Nop,
+        };
+        smir::Statement { kind, span: self.source_info.span.stable(tables) }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::RetagKind {
+    type T = smir::RetagKind;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            mir::RetagKind::FnEntry => smir::RetagKind::FnEntry,
+            mir::RetagKind::TwoPhase => smir::RetagKind::TwoPhase,
+            mir::RetagKind::Raw => smir::RetagKind::Raw,
+            mir::RetagKind::Default => smir::RetagKind::Default,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::NonDivergingIntrinsic<'tcx> {
+    type T = smir::NonDivergingIntrinsic;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            mir::NonDivergingIntrinsic::Assume(op) => {
+                smir::NonDivergingIntrinsic::Assume(op.stable(tables))
+            }
+            mir::NonDivergingIntrinsic::CopyNonOverlapping(copy) => {
+                smir::NonDivergingIntrinsic::CopyNonOverlapping {
+                    src: copy.src.stable(tables),
+                    dst: copy.dst.stable(tables),
+                    count: copy.count.stable(tables),
+                }
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Terminator<'tcx> {
+    type T = smir::Terminator;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use mir::TerminatorKind::*;
+        let kind = match &self.kind {
+            Goto { target } => smir::TerminatorKind::Goto { target: target.stable(tables) },
+            SwitchInt { discr, targets, .. } => smir::TerminatorKind::SwitchInt {
+                discr: discr.stable(tables),
+                targets: targets.iter().map(|(value, target)| (value, target.as_usize())).collect(),
+                otherwise: targets.otherwise().as_usize(),
+            },
+            Resume => smir::TerminatorKind::Resume,
+            Abort => smir::TerminatorKind::Abort,
+            Return => smir::TerminatorKind::Return,
+            Unreachable => smir::TerminatorKind::Unreachable,
+            Drop { place, target, unwind } => smir::TerminatorKind::Drop {
+                place: place.stable(tables),
+                target: target.stable(tables),
+                unwind: unwind.stable(tables),
+            },
+            DropAndReplace { place, value, target, unwind } => {
+                smir::TerminatorKind::DropAndReplace {
+                    place: place.stable(tables),
+                    value: value.stable(tables),
+                    target: target.stable(tables),
+                    unwind: unwind.stable(tables),
+                }
+            }
+            Call { func, args, destination, target, cleanup, .. } => smir::TerminatorKind::Call {
+                func: func.stable(tables),
+                args: args.stable(tables),
+                destination: destination.stable(tables),
+                target: target.stable(tables),
+                cleanup: cleanup.stable(tables),
+            },
+            Assert { cond, expected, msg, target, cleanup } => smir::TerminatorKind::Assert {
+                cond: cond.stable(tables),
+                expected: *expected,
+                msg: msg.stable(tables),
+                target: target.stable(tables),
+                cleanup: cleanup.stable(tables),
+            },
+            Yield { value, resume, resume_arg, drop } => smir::TerminatorKind::Yield {
+                value: value.stable(tables),
+                resume: resume.stable(tables),
+                resume_arg: resume_arg.stable(tables),
+                drop: drop.stable(tables),
+            },
+            GeneratorDrop => smir::TerminatorKind::GeneratorDrop,
+            // False edges only exist for the borrow checker, and are removed
+            // before optimizations run.
+            FalseEdge { real_target, .. } | FalseUnwind { real_target, .. } => {
+                smir::TerminatorKind::Goto { target: real_target.stable(tables) }
+            }
+            InlineAsm { destination, cleanup, .. } => {
+                let mut asm = String::new();
+                self.kind.fmt_head(&mut asm).unwrap();
+                smir::TerminatorKind::InlineAsm {
+                    asm,
+                    destination: destination.stable(tables),
+                    cleanup: cleanup.stable(tables),
+                }
+            }
+        };
+        smir::Terminator { kind, span: self.source_info.span.stable(tables) }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::AssertMessage<'tcx> {
+    type T = smir::AssertMessage;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use mir::AssertKind::*;
+        match self {
+            BoundsCheck { len, index } => smir::AssertMessage::BoundsCheck {
+                len: len.stable(tables),
+                index: index.stable(tables),
+            },
+            Overflow(op, lhs, rhs) => smir::AssertMessage::Overflow(
+                op.stable(tables),
+                lhs.stable(tables),
+                rhs.stable(tables),
+            ),
+            OverflowNeg(op) => smir::AssertMessage::OverflowNeg(op.stable(tables)),
+            DivisionByZero(op) => smir::AssertMessage::DivisionByZero(op.stable(tables)),
+            RemainderByZero(op) => smir::AssertMessage::RemainderByZero(op.stable(tables)),
+            ResumedAfterReturn(kind) => {
+                smir::AssertMessage::ResumedAfterReturn(kind.stable(tables))
+            }
+            ResumedAfterPanic(kind) => smir::AssertMessage::ResumedAfterPanic(kind.stable(tables)),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::GeneratorKind {
+    type T = smir::GeneratorKind;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            hir::GeneratorKind::Async(_) => smir::GeneratorKind::Async,
+            hir::GeneratorKind::Gen => smir::GeneratorKind::Gen,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Rvalue<'tcx> {
+    type T = smir::Rvalue;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use mir::Rvalue::*;
+        match self {
+            Use(op) => smir::Rvalue::Use(op.stable(tables)),
+            Repeat(op, len) => {
+                let len = len.try_eval_usize(tables.tcx, ty::ParamEnv::reveal_all());
+                smir::Rvalue::Repeat(op.stable(tables), len)
+            }
+            Ref(_, kind, place) => smir::Rvalue::Ref(kind.stable(tables), place.stable(tables)),
+            ThreadLocalRef(def_id) => smir::Rvalue::ThreadLocalRef(def_id.stable(tables)),
+            AddressOf(mutability, place) => {
+                smir::Rvalue::AddressOf(mutability.stable(tables), place.stable(tables))
+            }
+            Len(place) => smir::Rvalue::Len(place.stable(tables)),
+            Cast(kind, op, ty) => {
+                smir::Rvalue::Cast(kind.stable(tables), op.stable(tables), ty.stable(tables))
+            }
+            BinaryOp(bin_op, ops) => smir::Rvalue::BinaryOp(
+                bin_op.stable(tables),
+                ops.0.stable(tables),
+                ops.1.stable(tables),
+            ),
+            CheckedBinaryOp(bin_op, ops) => smir::Rvalue::CheckedBinaryOp(
+                bin_op.stable(tables),
+                ops.0.stable(tables),
+                ops.1.stable(tables),
+            ),
+            NullaryOp(null_op, ty) => {
+                smir::Rvalue::NullaryOp(null_op.stable(tables), ty.stable(tables))
+            }
+            UnaryOp(un_op, op) => smir::Rvalue::UnaryOp(un_op.stable(tables), op.stable(tables)),
+            Discriminant(place) => smir::Rvalue::Discriminant(place.stable(tables)),
+            Aggregate(kind, ops) => {
+                smir::Rvalue::Aggregate(kind.stable(tables), ops.stable(tables))
+            }
+            ShallowInitBox(op, ty) => {
+                smir::Rvalue::ShallowInitBox(op.stable(tables), ty.stable(tables))
+            }
+            CopyForDeref(place) => smir::Rvalue::CopyForDeref(place.stable(tables)),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::BorrowKind {
+    type T = smir::BorrowKind;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match *self {
+            mir::BorrowKind::Shared => smir::BorrowKind::Shared,
+            mir::BorrowKind::Shallow => smir::BorrowKind::Shallow,
+            mir::BorrowKind::Unique => smir::BorrowKind::Unique,
+            mir::BorrowKind::Mut { allow_two_phase_borrow } => {
+                smir::BorrowKind::Mut { allow_two_phase_borrow }
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::CastKind {
+    type T = smir::CastKind;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            mir::CastKind::PointerExposeAddress => smir::CastKind::PointerExposeAddress,
+            mir::CastKind::PointerFromExposedAddress => smir::CastKind::PointerFromExposedAddress,
+            mir::CastKind::Pointer(cast) => smir::CastKind::Pointer(match cast {
+                PointerCast::ReifyFnPointer => smir::PointerCast::ReifyFnPointer,
+                PointerCast::UnsafeFnPointer => smir::PointerCast::UnsafeFnPointer,
+                PointerCast::ClosureFnPointer(_) => smir::PointerCast::ClosureFnPointer,
+                PointerCast::MutToConstPointer => smir::PointerCast::MutToConstPointer,
+                PointerCast::ArrayToPointer => smir::PointerCast::ArrayToPointer,
+                PointerCast::Unsize => smir::PointerCast::Unsize,
+            }),
+            mir::CastKind::DynStar => smir::CastKind::DynStar,
+            mir::CastKind::Misc => smir::CastKind::Misc,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::AggregateKind<'tcx> {
+    type T = smir::AggregateKind;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            mir::AggregateKind::Array(ty) => smir::AggregateKind::Array(ty.stable(tables)),
+            mir::AggregateKind::Tuple => smir::AggregateKind::Tuple,
+            mir::AggregateKind::Adt(def_id, variant, substs, _, active_field) => {
+                smir::AggregateKind::Adt(
+                    def_id.stable(tables),
+                    variant.as_usize(),
+                    substs.stable(tables),
+                    *active_field,
+                )
+            }
+            mir::AggregateKind::Closure(def_id, substs) => smir::AggregateKind::Closure(
+                def_id.to_def_id().stable(tables),
+                substs.stable(tables),
+            ),
+            mir::AggregateKind::Generator(def_id, substs, movability) => {
+                smir::AggregateKind::Generator(
+                    def_id.to_def_id().stable(tables),
+                    substs.stable(tables),
+                    movability.stable(tables),
+                )
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::BinOp {
+    type T = smir::BinOp;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            mir::BinOp::Add => smir::BinOp::Add,
+            mir::BinOp::Sub => smir::BinOp::Sub,
+            mir::BinOp::Mul => smir::BinOp::Mul,
+            mir::BinOp::Div => smir::BinOp::Div,
+            mir::BinOp::Rem => smir::BinOp::Rem,
+            mir::BinOp::BitXor => smir::BinOp::BitXor,
+            mir::BinOp::BitAnd => smir::BinOp::BitAnd,
+            mir::BinOp::BitOr => smir::BinOp::BitOr,
+            mir::BinOp::Shl => smir::BinOp::Shl,
+            mir::BinOp::Shr => smir::BinOp::Shr,
+            mir::BinOp::Eq => smir::BinOp::Eq,
+            mir::BinOp::Lt => smir::BinOp::Lt,
+            mir::BinOp::Le => smir::BinOp::Le,
+            mir::BinOp::Ne => smir::BinOp::Ne,
+            mir::BinOp::Ge => smir::BinOp::Ge,
+            mir::BinOp::Gt => smir::BinOp::Gt,
+            mir::BinOp::Offset => smir::BinOp::Offset,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::UnOp {
+    type T = smir::UnOp;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            mir::UnOp::Not => smir::UnOp::Not,
+            mir::UnOp::Neg => smir::UnOp::Neg,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::NullOp {
+    type T = smir::NullOp;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            mir::NullOp::SizeOf => smir::NullOp::SizeOf,
+            mir::NullOp::AlignOf => smir::NullOp::AlignOf,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Operand<'tcx> {
+    type T = smir::Operand;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            mir::Operand::Copy(place) => smir::Operand::Copy(place.stable(tables)),
+            mir::Operand::Move(place) => smir::Operand::Move(place.stable(tables)),
+            mir::Operand::Constant(constant) => smir::Operand::Constant(smir::Constant {
+                ty: constant.ty().stable(tables),
+                literal: constant.literal.to_string(),
+                span: constant.span.stable(tables),
+            }),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Place<'tcx> {
+    type T = smir::Place;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        smir::Place {
+            local: self.local.stable(tables),
+            projection: self.projection.iter().map(|elem| elem.stable(tables)).collect(),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::PlaceElem<'tcx> {
+    type T = smir::ProjectionElem;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use mir::ProjectionElem::*;
+        match *self {
+            Deref => smir::ProjectionElem::Deref,
+            Field(field, ty) => smir::ProjectionElem::Field(field.as_usize(), ty.stable(tables)),
+            Index(local) => smir::ProjectionElem::Index(local.stable(tables)),
+            ConstantIndex { offset, min_length, from_end } => {
+                smir::ProjectionElem::ConstantIndex { offset, min_length, from_end }
+            }
+            Subslice { from, to, from_end } => {
+                smir::ProjectionElem::Subslice { from, to, from_end }
+            }
+            Downcast(_, variant) => smir::ProjectionElem::Downcast(variant.as_usize()),
+            OpaqueCast(ty) => smir::ProjectionElem::OpaqueCast(ty.stable(tables)),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::Ty<'tcx> {
+    type T = sty::Ty;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match *self.kind() {
+            ty::Bool => sty::Ty::Bool,
+            ty::Char => sty::Ty::Char,
+            ty::Int(int_ty) => sty::Ty::Int(match int_ty {
+                ty::IntTy::Isize => sty::IntTy::Isize,
+                ty::IntTy::I8 => sty::IntTy::I8,
+                ty::IntTy::I16 => sty::IntTy::I16,
+                ty::IntTy::I32 => sty::IntTy::I32,
+                ty::IntTy::I64 => sty::IntTy::I64,
+                ty::IntTy::I128 => sty::IntTy::I128,
+            }),
+            ty::Uint(uint_ty) => sty::Ty::Uint(match uint_ty {
+                ty::UintTy::Usize => sty::UintTy::Usize,
+                ty::UintTy::U8 => sty::UintTy::U8,
+                ty::UintTy::U16 => sty::UintTy::U16,
+                ty::UintTy::U32 => sty::UintTy::U32,
+                ty::UintTy::U64 => sty::UintTy::U64,
+                ty::UintTy::U128 => sty::UintTy::U128,
+            }),
+            ty::Float(float_ty) => sty::Ty::Float(match float_ty {
+                ty::FloatTy::F32 => sty::FloatTy::F32,
+                ty::FloatTy::F64 => sty::FloatTy::F64,
+            }),
+            ty::Adt(adt_def, substs) => {
+                sty::Ty::Adt(adt_def.did().stable(tables), substs.stable(tables))
+            }
+            ty::Foreign(def_id) => sty::Ty::Foreign(def_id.stable(tables)),
+            ty::Str => sty::Ty::Str,
+            ty::Array(elem_ty, len) => sty::Ty::Array(
+                Box::new(elem_ty.stable(tables)),
+                len.try_eval_usize(tables.tcx, ty::ParamEnv::reveal_all()),
+            ),
+            ty::Slice(elem_ty) => sty::Ty::Slice(Box::new(elem_ty.stable(tables))),
+            ty::RawPtr(ty::TypeAndMut { ty, mutbl }) => {
+                sty::Ty::RawPtr(Box::new(ty.stable(tables)), mutbl.stable(tables))
+            }
+            ty::Ref(_, ty, mutbl) => {
+                sty::Ty::Ref(Box::new(ty.stable(tables)), mutbl.stable(tables))
+            }
+            ty::FnDef(def_id, substs) => {
+                sty::Ty::FnDef(def_id.stable(tables), substs.stable(tables))
+            }
+            ty::FnPtr(sig) => sty::Ty::FnPtr(sig.skip_binder().stable(tables)),
+            ty::Dynamic(predicates, ..) => sty::Ty::Dynamic(sty::DynTy {
+                principal: predicates.principal_def_id().map(|def_id| def_id.stable(tables)),
+                auto_traits: predicates.auto_traits().map(|def_id| def_id.stable(tables)).collect(),
+            }),
+            ty::Closure(def_id, substs) => {
+                sty::Ty::Closure(def_id.stable(tables), substs.stable(tables))
+            }
+            ty::Generator(def_id, substs, movability) => sty::Ty::Generator(
+                def_id.stable(tables),
+                substs.stable(tables),
+                movability.stable(tables),
+            ),
+            ty::Never => sty::Ty::Never,
+            ty::Tuple(fields) => {
+                sty::Ty::Tuple(fields.iter().map(|ty| ty.stable(tables)).collect())
+            }
+            ty::Projection(projection) => sty::Ty::Projection(
+                projection.item_def_id.stable(tables),
+                projection.substs.stable(tables),
+            ),
+            ty::Opaque(def_id, substs) => {
+                sty::Ty::Opaque(def_id.stable(tables), substs.stable(tables))
+            }
+            ty::Param(param) => {
+                sty::Ty::Param(sty::ParamTy { index: param.index, name: param.name.to_string() })
+            }
+            ty::GeneratorWitness(_)
+            | ty::Bound(..)
+            | ty::Placeholder(_)
+            | ty::Infer(_)
+            | ty::Error(_) => sty::Ty::Unsupported(self.to_string()),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::GenericArg<'tcx> {
+    type T = sty::GenericArg;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self.unpack() {
+            ty::GenericArgKind::Lifetime(_) => sty::GenericArg::Lifetime,
+            ty::GenericArgKind::Type(ty) => sty::GenericArg::Type(ty.stable(tables)),
+            ty::GenericArgKind::Const(ct) => sty::GenericArg::Const(ct.to_string()),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::FnSig<'tcx> {
+    type T = sty::FnSig;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        sty::FnSig {
+            inputs: self.inputs().stable(tables),
+            output: Box::new(self.output().stable(tables)),
+            c_variadic: self.c_variadic,
+            is_unsafe: self.unsafety == hir::Unsafety::Unsafe,
+            abi: self.abi.name().to_owned(),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::Mutability {
+    type T = sty::Mutability;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            hir::Mutability::Not => sty::Mutability::Not,
+            hir::Mutability::Mut => sty::Mutability::Mut,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::Movability {
+    type T = sty::Movability;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            hir::Movability::Static => sty::Movability::Static,
+            hir::Movability::Movable => sty::Movability::Movable,
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Module that implements the public interface to the Stable MIR.
+//!
+//! This module shall contain all type definitions and APIs that we expect 3P tools to use.
+//! Everything here is owned data: nothing borrows from the `TyCtxt` or mentions a `'tcx`
+//! lifetime, so tools built on top of it do not need to be touched when rustc internals change.
+//!
+//! Conversions from the internal compiler representation live in [`crate::rustc_smir`], and
+//! [`crate::rustc_internal`] provides the entry points to obtain this data from a compilation.
+
+pub mod mir;
+pub mod ty;
+
+/// Use String for now but we should replace it.
+pub type Symbol = String;
+
+/// The number that identifies a crate.
+pub type CrateNum = usize;
+
+/// A unique identification number for each item accessible for the current compilation unit.
+///
+/// The same rustc `DefId` is always assigned the same stable `DefId` within one compilation.
+pub type DefId = usize;
+
+/// Holds information about a crate.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Crate {
+    pub id: CrateNum,
+    pub name: Symbol,
+    pub is_local: bool,
+}
+
+/// Holds information about an item in the crate.
+/// For now, it only stores the item `DefId`, its path and where it is declared.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CrateItem {
+    pub def_id: DefId,
+    /// The path of the item as printed by rustc, e.g. `foo::Bar::new`.
+    pub name: Symbol,
+    pub span: Span,
+}
+
+/// A region of source code, resolved to a file and line/column positions.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Span {
+    pub file: String,
+    /// 1-based.
+    pub lo_line: usize,
+    /// 0-based, character offset.
+    pub lo_col: usize,
+    /// 1-based.
+    pub hi_line: usize,
+    /// 0-based, character offset.
+    pub hi_col: usize,
+}
+
+/// The interface tools use to query the compiler for Stable MIR.
+///
+/// An implementation is handed out by [`crate::rustc_internal::run`] and
+/// [`crate::rustc_internal::run_compiler`].
+pub trait Context {
+    /// The crate being compiled.
+    fn local_crate(&self) -> Crate;
+
+    /// All crates the local crate depends on, directly or indirectly.
+    fn external_crates(&self) -> Vec<Crate>;
+
+    /// All items of the local crate that have a MIR body.
+    fn all_local_items(&mut self) -> Vec<CrateItem>;
+
+    /// The optimized MIR of `item`, or its MIR for CTFE for constants and statics.
+    fn mir_body(&mut self, item: &CrateItem) -> mir::Body;
+
+    /// The path of the item identified by `def_id`, as printed by rustc.
+    fn def_path_str(&self, def_id: DefId) -> Symbol;
+}
@@ -0,0 +1,108 @@
+//! Stable representation of types.
+//!
+//! Regions are not represented: they are erased in the MIR that is exposed through this API.
+
+use super::{DefId, Symbol};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Ty {
+    Bool,
+    Char,
+    Int(IntTy),
+    Uint(UintTy),
+    Float(FloatTy),
+    Adt(DefId, GenericArgs),
+    Foreign(DefId),
+    Str,
+    /// The length is `None` if it could not be evaluated, e.g. because it is generic.
+    Array(Box<Ty>, Option<u64>),
+    Slice(Box<Ty>),
+    RawPtr(Box<Ty>, Mutability),
+    Ref(Box<Ty>, Mutability),
+    FnDef(DefId, GenericArgs),
+    FnPtr(FnSig),
+    Dynamic(DynTy),
+    Closure(DefId, GenericArgs),
+    Generator(DefId, GenericArgs, Movability),
+    Never,
+    Tuple(Vec<Ty>),
+    /// An associated type projection such as `<T as Iterator>::Item`.
+    Projection(DefId, GenericArgs),
+    /// An opaque type such as `impl Trait`.
+    Opaque(DefId, GenericArgs),
+    Param(ParamTy),
+    /// A type that has no stable representation yet. The string is the type as
+    /// printed by rustc.
+    Unsupported(String),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IntTy {
+    Isize,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UintTy {
+    Usize,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FloatTy {
+    F32,
+    F64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mutability {
+    Not,
+    Mut,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Movability {
+    Static,
+    Movable,
+}
+
+/// The generic arguments an item is instantiated with, in declaration order.
+pub type GenericArgs = Vec<GenericArg>;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum GenericArg {
+    Lifetime,
+    Type(Ty),
+    /// A const argument, as printed by rustc.
+    Const(String),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FnSig {
+    pub inputs: Vec<Ty>,
+    pub output: Box<Ty>,
+    pub c_variadic: bool,
+    pub is_unsafe: bool,
+    pub abi: Symbol,
+}
+
+/// A `dyn Trait` type.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DynTy {
+    pub principal: Option<DefId>,
+    pub auto_traits: Vec<DefId>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParamTy {
+    pub index: u32,
+    pub name: Symbol,
+}
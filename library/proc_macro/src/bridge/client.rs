@@ -347,6 +347,27 @@ pub struct Client<I, O> {
     pub(super) _marker: PhantomData<fn(I) -> O>,
 }
 
+impl<I, O> Client<I, O> {
+    /// Runs the client with `dispatch` as its connection to the server. This is
+    /// used to run the client in a different process than the server, with
+    /// `dispatch` forwarding requests to the `server::ExecutionStrategy` which
+    /// produced `input` and returning its replies. The returned buffer is the
+    /// output the strategy has to return.
+    pub fn run_with_dispatch(
+        &self,
+        input: Buffer,
+        mut dispatch: impl FnMut(Buffer) -> Buffer,
+        force_show_panics: bool,
+    ) -> Buffer {
+        (self.run)(BridgeConfig {
+            input,
+            dispatch: (&mut dispatch).into(),
+            force_show_panics,
+            _marker: PhantomData,
+        })
+    }
+}
+
 impl<I, O> Copy for Client<I, O> {}
 impl<I, O> Clone for Client<I, O> {
     fn clone(&self) -> Self {
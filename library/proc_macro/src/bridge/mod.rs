@@ -155,7 +155,7 @@ macro_rules! reverse_decode {
 #[allow(unsafe_code)]
 mod symbol;
 
-use buffer::Buffer;
+pub use buffer::Buffer;
 pub use rpc::PanicMessage;
 use rpc::{Decode, DecodeMut, Encode, Reader, Writer};
 
@@ -277,6 +277,14 @@ fn run_bridge_and_client(
     }
 }
 
+/// Returns the output of a client that panicked with `message`, for
+/// `ExecutionStrategy`s that could not run the client to completion.
+pub fn client_panicked(message: PanicMessage) -> Buffer {
+    let mut buf = Buffer::new();
+    Err::<(), _>(message).encode(&mut buf, &mut ());
+    buf
+}
+
 /// A message pipe used for communicating between server and client threads.
 pub trait MessagePipe<T>: Sized {
     /// Create a new pair of endpoints for the message pipe.
@@ -553,6 +553,7 @@ fn test_with_no_doc_stage0() {
             doc_tests: DocTests::No,
             bless: false,
             force_rerun: false,
+            mir_opt_bisect: false,
             compare_mode: None,
             rustfix_coverage: false,
             pass: None,
@@ -623,6 +624,7 @@ fn test_docs() {
             doc_tests: DocTests::Yes,
             bless: false,
             force_rerun: false,
+            mir_opt_bisect: false,
             compare_mode: None,
             rustfix_coverage: false,
             pass: None,
@@ -114,6 +114,8 @@ pub enum Subcommand {
         /// Whether to automatically update stderr/stdout files
         bless: bool,
         force_rerun: bool,
+        /// Whether to find the first MIR optimization that makes failing run tests fail
+        mir_opt_bisect: bool,
         compare_mode: Option<String>,
         pass: Option<String>,
         run: Option<String>,
@@ -295,6 +297,11 @@ pub fn parse(args: &[String]) -> Flags {
                 opts.optflag("", "doc", "only run doc tests");
                 opts.optflag("", "bless", "update all stderr/stdout files of failing ui tests");
                 opts.optflag("", "force-rerun", "rerun tests even if the inputs are unchanged");
+                opts.optflag(
+                    "",
+                    "mir-opt-bisect",
+                    "find the first MIR optimization that makes failing run tests fail",
+                );
                 opts.optopt(
                     "",
                     "compare-mode",
@@ -572,6 +579,7 @@ pub fn parse(args: &[String]) -> Flags {
                 paths,
                 bless: matches.opt_present("bless"),
                 force_rerun: matches.opt_present("force-rerun"),
+                mir_opt_bisect: matches.opt_present("mir-opt-bisect"),
                 compare_mode: matches.opt_str("compare-mode"),
                 pass: matches.opt_str("pass"),
                 run: matches.opt_str("run"),
@@ -764,6 +772,13 @@ pub fn force_rerun(&self) -> bool {
         }
     }
 
+    pub fn mir_opt_bisect(&self) -> bool {
+        match *self {
+            Subcommand::Test { mir_opt_bisect, .. } => mir_opt_bisect,
+            _ => false,
+        }
+    }
+
     pub fn rustfix_coverage(&self) -> bool {
         match *self {
             Subcommand::Test { rustfix_coverage, .. } => rustfix_coverage,
@@ -1360,6 +1360,10 @@ fn run(self, builder: &Builder<'_>) {
             cmd.arg("--force-rerun");
         }
 
+        if builder.config.cmd.mir_opt_bisect() {
+            cmd.arg("--mir-opt-bisect");
+        }
+
         let compare_mode =
             builder.config.cmd.compare_mode().or_else(|| {
                 if builder.config.test_compare_mode { self.compare_mode } else { None }
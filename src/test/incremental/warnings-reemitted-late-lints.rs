@@ -0,0 +1,34 @@
+// Check that warnings from several lint passes are all replayed, exactly once,
+// when their queries are marked green. In cfail2, `main` has to be optimized
+// again because the body of `answer` it inlines changed, which recomputes the
+// green MIR the `arithmetic_overflow` lint is emitted from. That must not emit
+// the replayed warning a second time.
+
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z inline-mir=yes -Z deduplicate-diagnostics=no
+// build-pass
+
+#![warn(unused_variables, dead_code, unused_mut, arithmetic_overflow)]
+
+fn unused() {} //~ WARNING function `unused` is never used
+
+#[cfg(cfail1)]
+#[inline]
+fn answer() -> u8 {
+    41
+}
+
+#[cfg(not(cfail1))]
+#[inline]
+fn answer() -> u8 {
+    42
+}
+
+fn main() {
+    let x = 1; //~ WARNING unused variable: `x`
+    let mut y = 2; //~ WARNING variable does not need to be mutable
+    let _ = y;
+    let max: u8 = 255;
+    let _ = max + 1; //~ WARNING this arithmetic operation will overflow
+    let _ = answer();
+}
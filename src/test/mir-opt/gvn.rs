@@ -0,0 +1,37 @@
+// unit-test: GlobalValueNumbering
+
+// EMIT_MIR gvn.arithmetic.GlobalValueNumbering.diff
+fn arithmetic(x: u64, y: u64) -> u64 {
+    let a = x * y + 1;
+    let b = x * y + 1;
+    a ^ b
+}
+
+// EMIT_MIR gvn.casts.GlobalValueNumbering.diff
+fn casts(x: u32) -> u64 {
+    (x as u64) + (x as u64)
+}
+
+// The temporary holding the first `x + 1` is moved out of, so the second one is computed again.
+// EMIT_MIR gvn.moved.GlobalValueNumbering.diff
+fn moved(x: u64) -> u64 {
+    (x + 1) * 2 + (x + 1)
+}
+
+// `a` is only assigned on one path, so it can't replace the computation on the other.
+// EMIT_MIR gvn.not_dominated.GlobalValueNumbering.diff
+fn not_dominated(c: bool, x: u64) -> u64 {
+    if c {
+        let a = x * 2;
+        a
+    } else {
+        x * 2
+    }
+}
+
+fn main() {
+    arithmetic(1, 2);
+    casts(3);
+    moved(4);
+    not_dominated(true, 5);
+}
@@ -0,0 +1,41 @@
+// unit-test: JumpThreading
+#![feature(control_flow_enum)]
+#![feature(try_trait_v2)]
+
+use std::ops::ControlFlow;
+
+pub enum Foo {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+}
+
+// EMIT_MIR jump_threading.matches_to_bool.JumpThreading.diff
+fn matches_to_bool(num: Foo) -> u64 {
+    if matches!(num, Foo::B | Foo::C) { 23 } else { 42 }
+}
+
+// EMIT_MIR jump_threading.too_complex.JumpThreading.diff
+fn too_complex(x: Result<i32, usize>) -> Option<i32> {
+    // The outer match switches on the discriminant
+    // that each arm of the inner match sets, so
+    // the pass should make both arms jump to the arm
+    // of the outer match that they would reach.
+    match {
+        match x {
+            Ok(v) => ControlFlow::Continue(v),
+            Err(r) => ControlFlow::Break(r),
+        }
+    } {
+        ControlFlow::Continue(v) => Some(v),
+        ControlFlow::Break(r) => None,
+    }
+}
+
+fn main() {
+    matches_to_bool(Foo::A);
+    too_complex(Ok(0));
+}
@@ -0,0 +1,25 @@
+// unit-test: ScalarReplacementOfAggregates
+
+struct Point {
+    x: u32,
+    y: u32,
+}
+
+// EMIT_MIR sroa.structs.ScalarReplacementOfAggregates.diff
+pub fn structs(a: u32, b: u32) -> u32 {
+    let p = Point { x: a, y: b };
+    p.x + p.y
+}
+
+// EMIT_MIR sroa.escaping.ScalarReplacementOfAggregates.diff
+pub fn escaping(a: u32, b: u32) -> u32 {
+    // The address of a field is taken, so the tuple is kept whole.
+    let t = (a, b);
+    let r = &t.0;
+    *r + t.1
+}
+
+fn main() {
+    structs(1, 2);
+    escaping(1, 2);
+}
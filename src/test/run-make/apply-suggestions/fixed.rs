@@ -0,0 +1,5 @@
+pub fn answer() -> i32 {
+    let x = 42;
+    let _unused = 1;
+    x
+}
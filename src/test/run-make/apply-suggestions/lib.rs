@@ -0,0 +1,5 @@
+pub fn answer() -> i32 {
+    let mut x = 42;
+    let unused = 1;
+    x
+}
@@ -0,0 +1,15 @@
+mod store {
+    pub fn longest<'a, 'b>(x: &'a str, y: &'b str) -> &'a str {
+        let pick = |a: &'a str, b: &'b str| if a.len() > b.len() { a } else { b };
+        pick(x, y)
+    }
+}
+
+fn other<'a>(x: &'a u32) -> &'static u32 {
+    x
+}
+
+fn main() {
+    store::longest("a", "b");
+    other(&0);
+}
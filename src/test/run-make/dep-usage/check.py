@@ -0,0 +1,29 @@
+import json
+import sys
+
+usages = {usage["crate"]: usage for usage in json.load(sys.stdin)}
+
+used = usages["used"]
+assert used["extern"] and used["named"] and not used["impls_only"], used
+assert not used["macros_only"] and not used["tests_only"] and not used["cfgs"], used
+assert used["types"] == ["used::Type"], used
+assert used["traits"] == ["used::Trait"], used
+assert used["macros"] == ["used::mac"], used
+# The method call and `Type::new` are only resolved by typeck.
+assert used["values"] == ["used::Trait::method", "used::Type::new", "used::function"], used
+
+impls = usages["impls"]
+assert impls["extern"] and impls["named"] and impls["impls_only"], impls
+
+macros = usages["macros"]
+assert macros["macros_only"] and not macros["impls_only"], macros
+assert macros["macros"] == ["macros::only"], macros
+
+gated = usages["gated"]
+assert not gated["tests_only"] and gated["cfgs"] == ['feature = "extra"'], gated
+
+testonly = usages["testonly"]
+assert testonly["tests_only"] and testonly["cfgs"] == ["test"], testonly
+
+unused = usages["unused"]
+assert unused["extern"] and not unused["named"] and not unused["impls_only"], unused
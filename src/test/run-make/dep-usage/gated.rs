@@ -0,0 +1,3 @@
+pub fn function() -> u32 {
+    2
+}
@@ -0,0 +1,7 @@
+pub struct Helper;
+
+impl Default for Helper {
+    fn default() -> Self {
+        Helper
+    }
+}
@@ -0,0 +1,22 @@
+use impls as _;
+use used::{Trait, Type};
+
+used::mac!();
+macros::only!();
+
+pub fn f(t: Type) -> u32 {
+    t.method() + used::function() + Type::new().method()
+}
+
+#[cfg(feature = "extra")]
+pub fn extra() -> u32 {
+    gated::function()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn helper() {
+        assert_eq!(testonly::helper(), 0);
+    }
+}
@@ -0,0 +1,24 @@
+pub struct Type;
+
+impl Type {
+    pub fn new() -> Self {
+        Type
+    }
+}
+
+pub trait Trait {
+    fn method(&self) -> u32 {
+        0
+    }
+}
+
+impl Trait for Type {}
+
+pub fn function() -> u32 {
+    1
+}
+
+#[macro_export]
+macro_rules! mac {
+    () => {};
+}
@@ -0,0 +1,8 @@
+pub fn added() {
+    let added = 1;
+}
+
+pub fn legacy() {
+    // Moving the old warning to another line doesn't make it new.
+    let legacy = 1;
+}
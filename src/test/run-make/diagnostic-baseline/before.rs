@@ -0,0 +1,3 @@
+pub fn legacy() {
+    let legacy = 1;
+}
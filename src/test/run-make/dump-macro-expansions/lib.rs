@@ -0,0 +1,19 @@
+macro_rules! inner {
+    ($name:ident) => {
+        pub fn $name() {}
+    };
+}
+
+macro_rules! outer {
+    (fn) => {
+        compile_error!("unused arm");
+    };
+    (call $name:ident) => {
+        inner!($name);
+    };
+}
+
+outer!(call generated);
+
+#[derive(Debug)]
+pub struct Derived;
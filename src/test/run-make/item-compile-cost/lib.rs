@@ -0,0 +1,11 @@
+pub fn generic<T: Default>() -> T {
+    T::default()
+}
+
+pub fn one_instance() -> u32 {
+    generic::<u32>()
+}
+
+pub fn two_instances() -> (u8, u16) {
+    (generic::<u8>(), generic::<u16>())
+}
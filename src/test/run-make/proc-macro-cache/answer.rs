@@ -0,0 +1,19 @@
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+
+use proc_macro::{TokenStream, TokenTree};
+
+#[proc_macro_derive(Answer)]
+pub fn derive(input: TokenStream) -> TokenStream {
+    let idents: Vec<String> = input
+        .into_iter()
+        .filter_map(|tree| match tree {
+            TokenTree::Ident(ident) => Some(ident.to_string()),
+            _ => None,
+        })
+        .collect();
+    let name = &idents[idents.iter().position(|ident| ident == "struct").unwrap() + 1];
+    eprintln!("deriving Answer for {}", name);
+    format!("impl {} {{ pub fn answer() -> u32 {{ 42 }} }}", name).parse().unwrap()
+}
@@ -0,0 +1,7 @@
+#![crate_type = "lib"]
+
+#[macro_use]
+extern crate answer;
+
+#[derive(Answer)]
+pub struct Foo;
@@ -0,0 +1,45 @@
+pub fn used_by_user() {}
+
+pub fn unused() {}
+
+pub fn used_internally() {}
+
+pub fn calls_internal() {
+    used_internally();
+}
+
+pub fn recursive(n: u32) -> u32 {
+    if n == 0 { 0 } else { recursive(n - 1) }
+}
+
+#[allow(dead_code)]
+pub fn allowed() {}
+
+pub struct Config;
+
+impl Config {
+    pub fn new() -> Config {
+        Config
+    }
+
+    pub fn unused_method(&self) {}
+}
+
+pub struct Made;
+
+impl Made {
+    pub fn method(&self) {}
+}
+
+pub fn make() -> Made {
+    Made
+}
+
+pub trait Parse {
+    fn parse(&self);
+}
+
+pub enum Mode {
+    Fast,
+    Slow,
+}
@@ -0,0 +1,11 @@
+extern crate mylib;
+
+use mylib::Mode;
+
+fn main() {
+    mylib::used_by_user();
+    let _config = mylib::Config::new();
+    let _mode = Mode::Fast;
+    // `Made` is only used through inference.
+    mylib::make().method();
+}
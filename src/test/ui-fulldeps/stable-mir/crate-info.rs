@@ -0,0 +1,79 @@
+// run-pass
+// Test that users are able to use stable mir APIs to retrieve information of the current crate
+
+// ignore-cross-compile
+// ignore-stage1
+// ignore-remote
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_smir;
+
+use rustc_smir::rustc_internal;
+use rustc_smir::stable_mir::mir::{Rvalue, StatementKind, TerminatorKind};
+use rustc_smir::stable_mir::ty::{IntTy, Ty};
+use std::io::Write;
+
+const CRATE_NAME: &str = "input";
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub fn add(a: i32, b: i32) -> i32 {{
+        a + b
+    }}
+
+    pub fn pick(c: bool) -> i32 {{
+        if c {{ add(1, 2) }} else {{ 0 }}
+    }}
+    "#
+    )?;
+    Ok(())
+}
+
+fn main() {
+    let path = "input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+
+    let mut names = Vec::new();
+    rustc_driver::catch_fatal_errors(|| {
+        rustc_internal::run_compiler(&args, |cx, item, body| {
+            assert_eq!(cx.local_crate().name, CRATE_NAME);
+            names.push(item.name.clone());
+            match item.name.as_str() {
+                "add" => {
+                    assert_eq!(body.arg_count, 2);
+                    assert_eq!(*body.return_ty(), Ty::Int(IntTy::I32));
+                    assert!(body.blocks.iter().flat_map(|block| &block.statements).any(|stmt| {
+                        matches!(stmt.kind, StatementKind::Assign(_, Rvalue::CheckedBinaryOp(..)))
+                    }));
+                }
+                "pick" => {
+                    assert!(matches!(
+                        body.blocks[0].terminator.kind,
+                        TerminatorKind::SwitchInt { .. }
+                    ));
+                    assert!(body.blocks.iter().any(|block| {
+                        matches!(block.terminator.kind, TerminatorKind::Call { .. })
+                    }));
+                }
+                name => panic!("unexpected item `{name}`"),
+            }
+        })
+        .unwrap();
+    })
+    .unwrap();
+
+    names.sort();
+    assert_eq!(names, ["add", "pick"]);
+}
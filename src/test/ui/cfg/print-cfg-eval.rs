@@ -0,0 +1,19 @@
+// Checks that `-Z print-cfg-eval` reports the `cfg` predicates evaluated during the build.
+
+// check-pass
+// compile-flags: -Z print-cfg-eval --cfg foo
+
+#![crate_type = "lib"]
+
+#[cfg(foo)]
+pub fn enabled() {}
+
+#[cfg(not(foo))]
+pub fn disabled() {}
+
+#[cfg_attr(all(foo, bar), derive(Debug))]
+pub struct S;
+
+pub fn check() -> bool {
+    cfg!(any(foo, bar))
+}
@@ -0,0 +1,38 @@
+// run-rustfix
+#![deny(unorganized_imports)]
+#![allow(unused_imports)]
+
+mod a {
+    pub struct S;
+    pub fn f() {}
+    pub mod b {
+        pub struct T;
+    }
+}
+
+mod organized {
+    use std::fmt::{Debug, Display};
+
+    pub fn g(_: &dyn Debug, _: &dyn Display) {}
+}
+
+use std::fmt::Display;
+//~^ ERROR imports are not organized
+use a::f;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use a::b::T;
+use a::*;
+
+fn h(_: &dyn Debug, _: &dyn Display) {}
+
+// `HashMap` looks unused outside of tests, so it must be kept.
+#[cfg(test)]
+fn map(_: HashMap<u8, u8>) {}
+
+fn main() {
+    f();
+    let _ = (S, T);
+    h(&1, &2);
+    organized::g(&1, &2);
+}
@@ -0,0 +1,3 @@
+// Dead code is allowed in this module by the overrides of `lints.toml`.
+
+fn unused_generated() {}
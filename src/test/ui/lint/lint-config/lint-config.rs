@@ -0,0 +1,14 @@
+// Checks that `--lint-config` sets lint levels, expands the lint groups the
+// file defines and applies its per-path overrides.
+
+// compile-flags: -Z unstable-options --lint-config {{src-base}}/lint/lint-config/lints.toml
+
+mod generated;
+
+fn unused() {} //~ ERROR function `unused` is never used
+
+fn main() {
+    let mut x = 1;
+    //~^ WARN unused variable: `x`
+    //~| WARN variable does not need to be mutable
+}
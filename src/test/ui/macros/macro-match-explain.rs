@@ -0,0 +1,12 @@
+// compile-flags: -Z macro-match-explain
+
+macro_rules! api {
+    (get $name:ident) => {};
+    (set $name:ident = $value:expr) => {};
+    (list $($item:ident),*) => {};
+}
+
+fn main() {
+    api!(set x 1);
+    //~^ ERROR no rules expected the token `1`
+}
@@ -0,0 +1,14 @@
+// Check that global value numbering skips the blocks that are unreachable once constant
+// conditions are simplified, which used to ICE as they have no dominators.
+
+// build-pass
+// compile-flags: -Z mir-opt-level=2
+
+fn unreachable(x: u32) -> u32 {
+    let a = x + 1;
+    if 1 > 2 { x + 1 } else { a }
+}
+
+fn main() {
+    unreachable(1);
+}
@@ -0,0 +1,12 @@
+// Check that `-Z mir-opt-bisect-limit` reports the last MIR optimization it applies, and that
+// the MIR can still be codegened after it stops applying them.
+
+// run-pass
+// compile-flags: -Z mir-opt-level=0 -Z mir-opt-bisect-limit=1
+
+fn main() {
+    let x = 2 + 2;
+    if x != 4 {
+        std::process::abort();
+    }
+}
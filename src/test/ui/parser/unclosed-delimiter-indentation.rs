@@ -0,0 +1,23 @@
+// run-rustfix
+// Check that the missing closing braces are inferred from the indentation.
+
+struct Config {
+    verbose: bool,
+}
+
+impl Config { //~ NOTE unclosed delimiter
+    fn new() -> Config {
+    //~^ NOTE this delimiter might not be properly closed...
+        Config { verbose: false }
+
+    pub fn verbose(&self) -> bool {
+        self.verbose
+    }
+}
+//~^ NOTE ...as it matches this but it has different indentation
+
+fn main() { //~ NOTE unclosed delimiter
+    if Config::new().verbose() {
+        println!("verbose");
+}
+//~ ERROR this file contains an unclosed delimiter
@@ -0,0 +1,49 @@
+// check-pass
+// compile-flags: -Zunstable-options --print=public-api
+
+#![crate_type = "lib"]
+
+pub const MAX: u32 = 10;
+
+pub static mut COUNTER: usize = 0;
+
+#[non_exhaustive]
+pub struct Config {
+    pub verbose: bool,
+    level: u8,
+}
+
+impl Config {
+    pub fn new() -> Config {
+        Config { verbose: false, level: 0 }
+    }
+
+    fn level(&self) -> u8 {
+        self.level
+    }
+}
+
+pub enum Shape {
+    Circle(f64),
+    Rect { w: f64, h: f64 },
+}
+
+pub struct Wrapper<T: ?Sized>(pub Box<T>);
+
+pub trait Describe: Clone {
+    const NAME: &'static str;
+
+    fn describe(&self) -> String {
+        String::new()
+    }
+}
+
+pub fn longest<'a, T: Clone>(a: &'a [T], b: &'a [T]) -> &'a [T] {
+    if a.len() >= b.len() { a } else { b }
+}
+
+pub mod inner {
+    pub struct Counter(pub std::cell::Cell<u32>);
+}
+
+fn private() {}
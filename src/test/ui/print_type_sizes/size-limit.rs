@@ -0,0 +1,26 @@
+// compile-flags: -Z type-size-limit-file={{src-base}}/print_type_sizes/size-limit.limits
+// build-fail
+
+// Checks that `-Z type-size-limit-file` reports the types that are larger than
+// their limit, along with their layout.
+
+#![feature(start)]
+#![allow(dead_code)]
+
+enum Small {
+    A(u8),
+    B(u16),
+}
+
+enum Big {
+//~^ ERROR `Big` is 20 bytes, which exceeds its size limit of 16 bytes
+    A(u8),
+    B([u32; 4]),
+}
+
+#[start]
+fn start(_: isize, _: *const *const u8) -> isize {
+    let _small = Small::A(0);
+    let _big = Big::A(0);
+    0
+}
@@ -0,0 +1,27 @@
+// force-host
+// no-prefer-dynamic
+
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+use proc_macro::TokenStream;
+
+#[proc_macro]
+pub fn answer(input: TokenStream) -> TokenStream {
+    format!("fn answer() -> u32 {{ {} }}", input).parse().unwrap()
+}
+
+#[proc_macro]
+pub fn spin(_: TokenStream) -> TokenStream {
+    loop {
+        std::hint::black_box(());
+    }
+}
+
+#[proc_macro]
+pub fn allocate(_: TokenStream) -> TokenStream {
+    let mut allocations = Vec::new();
+    loop {
+        allocations.push(std::hint::black_box(Vec::<u8>::with_capacity(1 << 30)));
+    }
+}
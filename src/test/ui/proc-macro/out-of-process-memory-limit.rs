@@ -0,0 +1,9 @@
+// aux-build:out-of-process.rs
+// compile-flags: -Z proc-macro-execution-strategy=out-of-process -Z proc-macro-memory-limit=4096
+// only-linux the memory limit isn't enforced on macOS and Windows
+
+extern crate out_of_process;
+
+out_of_process::allocate!(); //~ ERROR proc macro may have exceeded its memory limit of 4096 MiB
+
+fn main() {}
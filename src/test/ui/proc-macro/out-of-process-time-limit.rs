@@ -0,0 +1,8 @@
+// aux-build:out-of-process.rs
+// compile-flags: -Z proc-macro-execution-strategy=out-of-process -Z proc-macro-time-limit=1000
+
+extern crate out_of_process;
+
+out_of_process::spin!(); //~ ERROR proc macro exceeded its time limit of 1000ms
+
+fn main() {}
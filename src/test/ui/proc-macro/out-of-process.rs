@@ -0,0 +1,17 @@
+// run-pass
+// aux-build:out-of-process.rs
+// compile-flags: -Z proc-macro-execution-strategy=out-of-process -Z proc-macro-time-limit=60000
+
+extern crate out_of_process;
+
+// Both expansions run in the same child process.
+out_of_process::answer!(42);
+
+mod previous {
+    out_of_process::answer!(41);
+}
+
+fn main() {
+    assert_eq!(answer(), 42);
+    assert_eq!(previous::answer(), 41);
+}
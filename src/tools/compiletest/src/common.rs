@@ -373,6 +373,9 @@ pub struct Config {
     /// Whether to rerun tests even if the inputs are unchanged.
     pub force_rerun: bool,
 
+    /// Whether to bisect the MIR optimizations of the tests that fail to run.
+    pub mir_opt_bisect: bool,
+
     pub target_cfg: LazyCell<TargetCfg>,
 }
 
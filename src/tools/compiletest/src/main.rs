@@ -147,6 +147,12 @@ pub fn parse_config(args: Vec<String>) -> Config {
                 `./<build_base>/rustfix_missing_coverage.txt`",
         )
         .optflag("", "force-rerun", "rerun tests even if the inputs are unchanged")
+        .optflag(
+            "",
+            "mir-opt-bisect",
+            "when a test fails to run, find the first MIR optimization that makes it fail \
+                with `-Zmir-opt-bisect-limit`",
+        )
         .optflag("h", "help", "show this message")
         .reqopt("", "channel", "current Rust channel", "CHANNEL")
         .optopt("", "edition", "default Rust edition", "EDITION");
@@ -294,6 +300,7 @@ fn make_absolute(path: PathBuf) -> PathBuf {
         npm: matches.opt_str("npm"),
 
         force_rerun: matches.opt_present("force-rerun"),
+        mir_opt_bisect: matches.opt_present("mir-opt-bisect"),
 
         target_cfg: LazyCell::new(),
     }
@@ -411,10 +411,72 @@ fn run_rpass_test(&self) {
 
         let proc_res = self.exec_compiled_test();
         if !proc_res.status.success() {
+            self.bisect_mir_opts_if_requested();
             self.fatal_proc_rec("test run failed!", &proc_res);
         }
     }
 
+    /// Finds the first MIR optimization applied while compiling the test after which it
+    /// fails to run, by compiling it with `-Zmir-opt-bisect-limit`, if `--mir-opt-bisect`
+    /// is passed.
+    fn bisect_mir_opts_if_requested(&self) {
+        if !self.config.mir_opt_bisect {
+            return;
+        }
+
+        // Returns whether the test runs successfully when only the first `limit` MIR
+        // optimizations are applied, whether the limit was reached, i.e. whether at least
+        // `limit` optimizations were applied, and the compiler output.
+        let run_with_limit = |limit: usize| {
+            let mut props = self.props.clone();
+            props.compile_flags.push(format!("-Zmir-opt-bisect-limit={}", limit));
+            let cx = TestCx { props: &props, ..*self };
+            let proc_res = cx.compile_test(WillExecute::Yes, EmitMetadata::No);
+            if !proc_res.status.success() {
+                self.fatal_proc_rec(
+                    "compilation failed while bisecting MIR optimizations!",
+                    &proc_res,
+                );
+            }
+            let reached = proc_res.stderr.contains("mir-opt-bisect-limit reached");
+            (cx.exec_compiled_test().status.success(), reached, proc_res.stderr)
+        };
+
+        if !run_with_limit(0).0 {
+            println!("mir-opt-bisect: the test fails to run without MIR optimizations");
+            return;
+        }
+        // Find a limit with which the test fails, then the smallest one.
+        let mut good = 0;
+        let mut bad = 1;
+        loop {
+            let (success, reached, _) = run_with_limit(bad);
+            if !success {
+                break;
+            }
+            if !reached {
+                println!("mir-opt-bisect: the test runs with all MIR optimizations applied");
+                return;
+            }
+            good = bad;
+            bad *= 2;
+        }
+        while bad - good > 1 {
+            let mid = good + (bad - good) / 2;
+            if run_with_limit(mid).0 {
+                good = mid;
+            } else {
+                bad = mid;
+            }
+        }
+
+        println!("mir-opt-bisect: the test fails to run after {} MIR optimizations", bad);
+        let (_, _, stderr) = run_with_limit(bad);
+        for line in stderr.lines().filter(|line| line.contains("mir-opt-bisect-limit reached")) {
+            println!("{}", line);
+        }
+    }
+
     fn run_valgrind_test(&self) {
         assert!(self.revision.is_none(), "revisions not relevant here");
 
@@ -3309,6 +3371,7 @@ fn run_ui_test(&self) {
             }
             if self.should_run_successfully(pm) {
                 if !proc_res.status.success() {
+                    self.bisect_mir_opts_if_requested();
                     self.fatal_proc_rec("test run failed!", &proc_res);
                 }
             } else if proc_res.status.success() {